@@ -0,0 +1,118 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use rand::prelude::*;
+
+use crate::generator::MazeGenerator;
+use crate::grid::{Direction, RectGrid};
+use crate::maze::Maze;
+
+/// Generates mazes with the Aldous-Broder algorithm: a random walk wanders the grid and opens a
+/// wall whenever it steps into a cell it has never visited. Like Wilson's algorithm it produces
+/// every perfect maze with equal probability, but it is slow to find the last few unvisited
+/// cells.
+///
+/// The hybrid variant walks until a fraction of the cells has been visited and then finishes
+/// the maze with Wilson's loop-erased walks, which are fast once much of the maze exists. Both
+/// phases extend the same tree by random walks that stop at it, so the result is still uniform.
+#[derive(Clone, Copy, Debug)]
+pub struct Generator {
+    switch_at: f64,
+}
+
+impl Generator {
+    /// Pure Aldous-Broder generation
+    pub fn new() -> Self {
+        Self::hybrid(1.0)
+    }
+
+    /// Aldous-Broder generation until `fraction` of the cells (between 0 and 1) are in the maze,
+    /// then Wilson's algorithm for the remainder
+    pub fn hybrid(fraction: f64) -> Self {
+        Self {
+            switch_at: fraction.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MazeGenerator for Generator {
+    fn generate<R: Rng + ?Sized>(&self, width: usize, height: usize, rng: &mut R) -> Maze {
+        let grid = RectGrid::new(width, height);
+        let mut maze = Maze::new(width as u32, height as u32);
+        if grid.is_empty() {
+            return maze;
+        }
+
+        let mut in_maze = vec![false; grid.len()];
+        let mut curr_idx = rng.gen_range(0..grid.len());
+        in_maze[curr_idx] = true;
+        let mut visited = 1;
+        // Always add at least the starting cell so that Wilson's has something to walk to
+        let target = ((grid.len() as f64 * self.switch_at) as usize).max(1);
+        while visited < target {
+            let (direction, next_idx) = random_neighbor(&grid, curr_idx, rng);
+            if !in_maze[next_idx] {
+                maze.open_wall(curr_idx, direction);
+                in_maze[next_idx] = true;
+                visited += 1;
+            }
+            curr_idx = next_idx;
+        }
+
+        if visited < grid.len() {
+            finish_with_wilsons(&grid, &mut maze, &mut in_maze, rng);
+        }
+        maze
+    }
+}
+
+fn random_neighbor<R: Rng + ?Sized>(
+    grid: &RectGrid,
+    idx: usize,
+    rng: &mut R,
+) -> (Direction, usize) {
+    let mut directions = Direction::ALL;
+    directions.shuffle(rng);
+    directions
+        .into_iter()
+        .find_map(|direction| {
+            grid.adjacent_index(idx, direction)
+                .map(|adj| (direction, adj))
+        })
+        .expect("grid cell has no neighbors")
+}
+
+/// Add every cell not yet in the maze with loop-erased random walks
+fn finish_with_wilsons<R: Rng + ?Sized>(
+    grid: &RectGrid,
+    maze: &mut Maze,
+    in_maze: &mut [bool],
+    rng: &mut R,
+) {
+    let mut candidates: Vec<usize> = (0..grid.len()).filter(|idx| !in_maze[*idx]).collect();
+    candidates.shuffle(rng);
+    // The direction each walk cell was last left in. Following these from the start of the walk
+    // retraces it with the loops erased.
+    let mut exits = vec![Direction::North; grid.len()];
+    for start_idx in candidates {
+        let mut curr_idx = start_idx;
+        while !in_maze[curr_idx] {
+            let (direction, next_idx) = random_neighbor(grid, curr_idx, rng);
+            exits[curr_idx] = direction;
+            curr_idx = next_idx;
+        }
+
+        curr_idx = start_idx;
+        while !in_maze[curr_idx] {
+            in_maze[curr_idx] = true;
+            maze.open_wall(curr_idx, exits[curr_idx]);
+            curr_idx = grid.adjacent_index(curr_idx, exits[curr_idx]).unwrap();
+        }
+    }
+}
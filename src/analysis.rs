@@ -0,0 +1,168 @@
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+use crate::maze::Maze;
+
+/// Counts and lengths that describe the shape of a maze, as computed by [`Maze::stats`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stats {
+    /// Cells with a single way out
+    pub dead_ends: usize,
+    /// Cells where three passages meet
+    pub three_way_junctions: usize,
+    /// Cells where four passages meet
+    pub four_way_junctions: usize,
+    /// Number of corridors, the unbranching runs of passage between two cells that are dead
+    /// ends or junctions
+    pub corridors: usize,
+    /// Mean length of a corridor in moves
+    pub average_corridor_length: f64,
+    /// Length of the longest corridor in moves
+    pub max_corridor_length: usize,
+    /// Moves from the entrance to the exit, or `None` if the exit cannot be reached
+    pub solution_length: Option<usize>,
+}
+
+impl Maze {
+    /// Count the dead ends and junctions of the maze and measure its corridors and solution
+    pub fn stats(&self) -> Stats {
+        let degrees: Vec<usize> = (0..self.len())
+            .map(|idx| self.passages(idx).len())
+            .collect();
+        let count = |degree: usize| degrees.iter().filter(|d| **d == degree).count();
+
+        let lengths: Vec<usize> = self
+            .corridors(&degrees)
+            .iter()
+            .map(|corridor| corridor.length)
+            .collect();
+        let average_corridor_length = if lengths.is_empty() {
+            0.0
+        } else {
+            lengths.iter().sum::<usize>() as f64 / lengths.len() as f64
+        };
+
+        let solution_length = self.solution().map(|path| path.len() - 1);
+
+        Stats {
+            dead_ends: count(1),
+            three_way_junctions: count(3),
+            four_way_junctions: count(4),
+            corridors: lengths.len(),
+            average_corridor_length,
+            max_corridor_length: lengths.iter().copied().max().unwrap_or(0),
+            solution_length,
+        }
+    }
+
+    /// Measure the texture of the maze: how long its side branches run, how much its corridors
+    /// turn, and how direct its solution is
+    pub fn texture(&self) -> Texture {
+        let grid = self.grid();
+        let degrees: Vec<usize> = (0..grid.len())
+            .map(|idx| self.passages(idx).len())
+            .collect();
+
+        let branches: Vec<usize> = self
+            .corridors(&degrees)
+            .iter()
+            .filter(|corridor| corridor.ends.iter().any(|end| degrees[*end] == 1))
+            .map(|corridor| corridor.length)
+            .collect();
+        let river = if branches.is_empty() {
+            0.0
+        } else {
+            branches.iter().sum::<usize>() as f64 / branches.len() as f64
+        };
+
+        // A corridor cell turns unless its two passages leave on opposite sides
+        let (mut turns, mut corridor_cells) = (0, 0);
+        for idx in (0..grid.len()).filter(|idx| degrees[*idx] == 2) {
+            let (x, y) = grid.coords(idx);
+            let [a, b] = [0, 1].map(|k| {
+                let (nx, ny) = grid.coords(self.passages(idx)[k]);
+                (
+                    (nx as isize - x as isize).signum(),
+                    (ny as isize - y as isize).signum(),
+                )
+            });
+            corridor_cells += 1;
+            if a != (-b.0, -b.1) {
+                turns += 1;
+            }
+        }
+        let twistiness = if corridor_cells == 0 {
+            0.0
+        } else {
+            turns as f64 / corridor_cells as f64
+        };
+
+        let elitism = self
+            .solution()
+            .map(|path| path.len() as f64 / grid.len() as f64);
+
+        Texture {
+            river,
+            twistiness,
+            elitism,
+        }
+    }
+
+    /// Every corridor, found by walking out from each dead end and junction until the next one.
+    /// Loops made only of corridor cells have no ends and are not counted.
+    fn corridors(&self, degrees: &[usize]) -> Vec<Corridor> {
+        let mut walked = BTreeSet::new();
+        let mut corridors = Vec::new();
+        for start in 0..degrees.len() {
+            if degrees[start] == 2 {
+                continue;
+            }
+            for first in self.passages(start) {
+                if walked.contains(&(start, first)) {
+                    continue;
+                }
+                let (mut previous, mut current, mut length) = (start, first, 1);
+                while degrees[current] == 2 {
+                    let next = self
+                        .passages(current)
+                        .into_iter()
+                        .find(|next| *next != previous)
+                        .unwrap_or(previous);
+                    (previous, current) = (current, next);
+                    length += 1;
+                }
+                // Mark the far end so that the corridor is not walked again from there
+                walked.insert((current, previous));
+                corridors.push(Corridor {
+                    ends: [start, current],
+                    length,
+                });
+            }
+        }
+        corridors
+    }
+}
+
+/// The classic measures of maze texture, as computed by [`Maze::texture`]. They make it possible
+/// to compare the mazes that different algorithms produce.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Texture {
+    /// Mean length in moves of the corridors that end in a dead end. Mazes with a high river
+    /// factor flow along long passages before they branch, while those with a low one are
+    /// covered in short stubs.
+    pub river: f64,
+    /// Fraction of corridor cells where the corridor turns instead of going straight on
+    pub twistiness: f64,
+    /// Fraction of all cells that are on the solution from the entrance to the exit, or `None`
+    /// if the exit cannot be reached. Low elitism means a short, direct solution compared to the
+    /// size of the maze.
+    pub elitism: Option<f64>,
+}
+
+/// An unbranching run of passage between the cells at its two `ends`
+struct Corridor {
+    ends: [usize; 2],
+    length: usize,
+}
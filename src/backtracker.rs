@@ -0,0 +1,96 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use rand::prelude::*;
+
+use crate::generator::MazeGenerator;
+use crate::grid::{Direction, Grid, RectGrid};
+use crate::maze::Maze;
+
+/// Generates mazes with a randomized depth-first search (the recursive backtracker). The walk
+/// keeps going until it runs into a dead end before backing up, which produces long, winding
+/// corridors with few branches.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Generator;
+
+impl MazeGenerator for Generator {
+    fn generate<R: Rng + ?Sized>(&self, width: usize, height: usize, rng: &mut R) -> Maze {
+        let grid = RectGrid::new(width, height);
+        let mut maze = Maze::new(width as u32, height as u32);
+        if grid.is_empty() {
+            return maze;
+        }
+
+        let mut visited = vec![false; grid.len()];
+        let start_idx = rng.gen_range(0..grid.len());
+        visited[start_idx] = true;
+        // The stack holds the current path from the start cell. The search backs up along it
+        // whenever the cell on top has no unvisited neighbors left.
+        let mut stack = vec![start_idx];
+        let mut directions = Direction::ALL;
+        while let Some(&curr_idx) = stack.last() {
+            directions.shuffle(rng);
+            let next = directions.iter().find_map(|direction| {
+                grid.adjacent_index(curr_idx, *direction)
+                    .filter(|idx| !visited[*idx])
+                    .map(|idx| (*direction, idx))
+            });
+            match next {
+                Some((direction, next_idx)) => {
+                    maze.open_wall(curr_idx, direction);
+                    visited[next_idx] = true;
+                    stack.push(next_idx);
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+        maze
+    }
+}
+
+/// Carve a maze into `grid` with the recursive backtracker, opening walls between its cells.
+/// The walls should all be closed to begin with. A grid made of several separate pieces gets a
+/// separate maze in each piece.
+pub fn carve<G: Grid, R: Rng + ?Sized>(grid: &mut G, rng: &mut R) {
+    let len = grid.len();
+    let mut starts: Vec<usize> = (0..len).collect();
+    starts.shuffle(rng);
+    let mut visited = vec![false; len];
+    // The search only covers the piece it starts in, so start again from every cell that it
+    // has not reached
+    for start_idx in starts {
+        if visited[start_idx] {
+            continue;
+        }
+        visited[start_idx] = true;
+        search(grid, start_idx, &mut visited, rng);
+    }
+}
+
+fn search<G: Grid, R: Rng + ?Sized>(
+    grid: &mut G,
+    start_idx: usize,
+    visited: &mut [bool],
+    rng: &mut R,
+) {
+    let mut stack = vec![start_idx];
+    while let Some(&curr_idx) = stack.last() {
+        let unvisited: Vec<usize> = grid
+            .neighbors(curr_idx)
+            .into_iter()
+            .filter(|idx| !visited[*idx])
+            .collect();
+        match unvisited.choose(rng) {
+            Some(&next_idx) => {
+                grid.link(curr_idx, next_idx);
+                visited[next_idx] = true;
+                stack.push(next_idx);
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use std::time::{Duration, Instant};
+
+use dadalus::binary_tree::{self, Bias};
+use dadalus::growing_tree::{self, Selection};
+use dadalus::maze::RenderOptions;
+use dadalus::mesh::MeshOptions;
+use dadalus::pdf::PdfOptions;
+use dadalus::plotter::Plot;
+use dadalus::schematic::SchematicOptions;
+use dadalus::svg::SvgOptions;
+use dadalus::tessellation::{Carving, Square};
+use dadalus::tilemap::TmxOptions;
+use dadalus::{aldous_broder, backtracker, hunt_and_kill, kruskal, wilsons};
+use dadalus::{fractal, origin_shift, weave};
+use dadalus::{Maze, MazeGenerator};
+use rand::Rng;
+
+const SIZES: [usize; 4] = [50, 100, 200, 400];
+const CELL_SIZE: u32 = 10;
+
+/// Generate and render mazes of several sizes, reporting throughput for each generation
+/// algorithm and for every output format the CLI writes, with default options. Results go to
+/// stderr, like everything else the CLI reports.
+pub fn run() {
+    let mut rng = rand::thread_rng();
+    eprintln!(
+        "{:<28} {:>10} {:>12} {:>16}",
+        "benchmark", "size", "time (ms)", "throughput"
+    );
+    for size in SIZES {
+        let cells = (size * size) as f64;
+
+        let maze = generate("wilsons", &wilsons::Generator, size, &mut rng);
+        generate("backtracker", &backtracker::Generator, size, &mut rng);
+        generate("kruskal", &kruskal::Generator, size, &mut rng);
+        let aldous_broder = aldous_broder::Generator::new();
+        generate("aldous-broder", &aldous_broder, size, &mut rng);
+        let hybrid = aldous_broder::Generator::hybrid(0.5);
+        generate("aldous-broder-wilsons", &hybrid, size, &mut rng);
+        generate("hunt-and-kill", &hunt_and_kill::Generator, size, &mut rng);
+        let binary_tree = binary_tree::Generator::new(Bias::NorthWest);
+        generate("binary-tree", &binary_tree, size, &mut rng);
+        let growing_tree = growing_tree::Generator::new(Selection::Newest);
+        generate("growing-tree", &growing_tree, size, &mut rng);
+        generate("fractal", &fractal::Generator, size, &mut rng);
+        let weave = weave::Generator::default();
+        generate("weave", &weave, size, &mut rng);
+        generate("origin-shift", &origin_shift::Generator, size, &mut rng);
+
+        let mut carving = Carving::new(square(size));
+        let (_, elapsed) = time(|| wilsons::carve(&mut carving, &mut rng));
+        report("generate/wilsons-carve", size, elapsed, cells, "cells");
+
+        let (pixmap, elapsed) = time(|| maze.draw(&RenderOptions::new(CELL_SIZE)));
+        let pixmap = pixmap.expect("benchmark mazes are never empty");
+        let pixels = f64::from(pixmap.width()) * f64::from(pixmap.height());
+        report("render/png", size, elapsed, pixels, "pixels");
+
+        write("render/plotter-svg", &maze, || {
+            Plot::new(&maze).to_svg(1.0).len()
+        });
+        write("render/svg", &maze, || {
+            maze.to_svg(&SvgOptions::default()).len()
+        });
+        write("render/pdf", &maze, || {
+            maze.to_pdf(&PdfOptions::default()).len()
+        });
+        write("render/stl", &maze, || {
+            maze.to_stl(&MeshOptions::default()).len()
+        });
+        write("render/obj", &maze, || {
+            maze.to_obj(&MeshOptions::default()).len()
+        });
+        write("render/dot", &maze, || maze.to_dot().len());
+        let schematic = SchematicOptions::default();
+        write("render/schem", &maze, || {
+            maze.to_schematic(&schematic).len()
+        });
+        write("render/csv", &maze, || maze.to_tile_csv().len());
+        write("render/tmx", &maze, || {
+            maze.to_tmx(&TmxOptions::default()).len()
+        });
+        write("render/maze", &maze, || {
+            let mut bytes = Vec::new();
+            maze.write_to(&mut bytes, None)
+                .expect("writing to memory never fails");
+            bytes.len()
+        });
+        #[cfg(feature = "serde")]
+        write("render/json", &maze, || maze.to_json().len());
+        write("render/text", &maze, || maze.to_string().len());
+    }
+}
+
+/// Time one of the writers that turn `maze` into a file, which returns the length it wrote so
+/// the work isn't optimized away.
+fn write(name: &str, maze: &Maze, writer: impl FnOnce() -> usize) {
+    let (written, elapsed) = time(writer);
+    std::hint::black_box(written);
+    let cells = f64::from(maze.width) * f64::from(maze.height);
+    report(name, maze.width as usize, elapsed, cells, "cells");
+}
+
+fn generate<G: MazeGenerator, R: Rng>(name: &str, generator: &G, size: usize, rng: &mut R) -> Maze {
+    let (maze, elapsed) = time(|| generator.generate(size, size, rng));
+    report(
+        &format!("generate/{name}"),
+        size,
+        elapsed,
+        (size * size) as f64,
+        "cells",
+    );
+    maze
+}
+
+fn square(size: usize) -> Square {
+    Square {
+        width: size,
+        height: size,
+    }
+}
+
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+fn report(name: &str, size: usize, elapsed: Duration, units: f64, unit: &str) {
+    let per_second = units / elapsed.as_secs_f64();
+    eprintln!(
+        "{:<28} {:>10} {:>12.1} {:>10.0} {unit}/s",
+        name,
+        format!("{size}x{size}"),
+        elapsed.as_secs_f64() * 1000.0,
+        per_second
+    );
+}
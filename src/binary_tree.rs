@@ -0,0 +1,65 @@
+use rand::prelude::*;
+
+use crate::generator::MazeGenerator;
+use crate::grid::{Direction, RectGrid};
+use crate::maze::Maze;
+
+/// The corner of the grid that every passage leads towards in a binary tree maze
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Bias {
+    NorthWest,
+    NorthEast,
+    SouthWest,
+    SouthEast,
+}
+
+impl Bias {
+    /// The two directions a cell may carve in
+    pub fn directions(self) -> [Direction; 2] {
+        use Direction::*;
+        match self {
+            Bias::NorthWest => [North, West],
+            Bias::NorthEast => [North, East],
+            Bias::SouthWest => [South, West],
+            Bias::SouthEast => [South, East],
+        }
+    }
+}
+
+/// Generates mazes with the binary tree algorithm: every cell independently opens the wall to
+/// one of the two directions of the bias. Cells do not depend on each other, so it is very fast,
+/// but the result has two long unbroken corridors along the edges of the biased corner and
+/// every path drifts diagonally towards it.
+#[derive(Clone, Copy, Debug)]
+pub struct Generator {
+    pub bias: Bias,
+}
+
+impl Generator {
+    pub fn new(bias: Bias) -> Self {
+        Self { bias }
+    }
+}
+
+impl MazeGenerator for Generator {
+    fn generate<R: Rng + ?Sized>(&self, width: usize, height: usize, rng: &mut R) -> Maze {
+        let grid = RectGrid::new(width, height);
+        let mut maze = Maze::new(width as u32, height as u32);
+        let [first, second] = self.bias.directions();
+        for idx in 0..grid.len() {
+            // Along the edges only one of the directions leads to another cell, and in the
+            // biased corner neither does
+            let direction = match (
+                grid.adjacent_index(idx, first),
+                grid.adjacent_index(idx, second),
+            ) {
+                (Some(_), Some(_)) => *[first, second].choose(rng).unwrap(),
+                (Some(_), None) => first,
+                (None, Some(_)) => second,
+                (None, None) => continue,
+            };
+            maze.open_wall(idx, direction);
+        }
+        maze
+    }
+}
@@ -0,0 +1,79 @@
+use alloc::vec::Vec;
+
+use rand::prelude::*;
+
+use crate::grid::Direction;
+use crate::maze::Maze;
+
+impl Maze {
+    /// Remove dead ends to make loops, turning a perfect maze into a braided one with more than
+    /// one route between cells. Each dead end is removed with probability `p` by opening one of
+    /// its closed walls, preferably one into another dead end so that both go at once. Walls of
+    /// cells with a tunnel under them stay closed, and so do the walls into cells without any
+    /// passages, like the cells that a [`Mask`](crate::mask::Mask) leaves out. A `p` that is
+    /// infinite or not a number is taken as 0.
+    pub fn braid<R: Rng + ?Sized>(&mut self, p: f64, rng: &mut R) {
+        let p = if p.is_finite() {
+            p.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let grid = self.grid();
+        let mut dead_ends: Vec<usize> = (0..grid.len())
+            .filter(|idx| self.passages(*idx).len() == 1)
+            .collect();
+        dead_ends.shuffle(rng);
+        for idx in dead_ends {
+            // Opening a neighboring dead end earlier may already have removed this one
+            if self.passages(idx).len() != 1 || !rng.gen_bool(p) {
+                continue;
+            }
+            let candidates: Vec<(Direction, usize)> = Direction::iter()
+                .filter_map(|d| grid.adjacent_index(idx, d).map(|n| (d, n)))
+                .filter(|(d, n)| {
+                    !self.is_open(idx, *d)
+                        && !self.cell_at_index(idx).tunnel()
+                        && !self.cell_at_index(*n).tunnel()
+                        && !self.passages(*n).is_empty()
+                })
+                .collect();
+            let dead_end_neighbors: Vec<(Direction, usize)> = candidates
+                .iter()
+                .copied()
+                .filter(|(_, n)| self.passages(*n).len() == 1)
+                .collect();
+            let choice = if dead_end_neighbors.is_empty() {
+                candidates.choose(rng)
+            } else {
+                dead_end_neighbors.choose(rng)
+            };
+            if let Some((direction, _)) = choice {
+                self.open_wall(idx, *direction);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mask::Mask;
+    use crate::maze::Maze;
+
+    #[test]
+    fn braiding_a_masked_maze_stays_inside_the_mask() {
+        let keep = |x: usize, y: usize| x.abs_diff(4) + y.abs_diff(4) <= 4 && (x, y) != (4, 4);
+        for seed in 0..20 {
+            let maze = Maze::builder()
+                .mask(Mask::new(9, 9, keep))
+                .braid(1.0)
+                .farthest_openings(true)
+                .seed(seed)
+                .build()
+                .unwrap();
+            let inside = |(x, y): (usize, usize)| keep(x, y);
+            assert!(maze.iter_passages().all(|(a, b)| inside(a) && inside(b)));
+            let openings = [maze.entrance(), maze.exit()];
+            assert!(openings.iter().flatten().all(|o| inside((o.x, o.y))));
+        }
+    }
+}
@@ -0,0 +1,357 @@
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+use crate::binary_tree::Bias;
+use crate::error::Error;
+use crate::generator::MazeGenerator;
+use crate::grid::Direction;
+use crate::growing_tree::Selection;
+use crate::mask::Mask;
+use crate::maze::{Maze, Opening};
+use crate::regional::{self, Region};
+use crate::tessellation::Carving;
+use crate::{aldous_broder, backtracker, binary_tree, fractal, growing_tree, hunt_and_kill};
+use crate::{kruskal, origin_shift, weave, wilsons};
+
+/// The algorithms that [`MazeBuilder`] can generate a maze with
+#[derive(Clone, Debug, PartialEq)]
+pub enum Algorithm {
+    Wilsons,
+    Backtracker,
+    Kruskal,
+    AldousBroder,
+    /// Aldous-Broder for the first half of the cells, then Wilson's
+    AldousBroderWilsons,
+    HuntAndKill,
+    BinaryTree(Bias),
+    GrowingTree(Selection),
+    Fractal,
+    OriginShift,
+    Weave,
+    /// Prim's algorithm in the center half of the grid and the backtracker around it
+    Regional,
+}
+
+impl Algorithm {
+    /// Name of the algorithm in lowercase words joined by hyphens, as the command line spells it
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Wilsons => "wilsons",
+            Algorithm::Backtracker => "backtracker",
+            Algorithm::Kruskal => "kruskal",
+            Algorithm::AldousBroder => "aldous-broder",
+            Algorithm::AldousBroderWilsons => "aldous-broder-wilsons",
+            Algorithm::HuntAndKill => "hunt-and-kill",
+            Algorithm::BinaryTree(_) => "binary-tree",
+            Algorithm::GrowingTree(_) => "growing-tree",
+            Algorithm::Fractal => "fractal",
+            Algorithm::OriginShift => "origin-shift",
+            Algorithm::Weave => "weave",
+            Algorithm::Regional => "regional",
+        }
+    }
+}
+
+/// Ready-made settings for [`MazeBuilder::difficulty`]. Harder mazes are larger, are carved with
+/// algorithms that leave more and shorter dead ends to wander into, and have fewer loops.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difficulty {
+    /// 12 by 9 cells from the backtracker, with half of the dead ends braided away
+    Easy,
+    /// 20 by 15 cells from the backtracker, with a tenth of the dead ends braided away
+    Medium,
+    /// 32 by 24 cells from Kruskal's algorithm, with no loops
+    Hard,
+    /// 48 by 36 cells from Wilson's algorithm, with no loops and the entrance and exit as far
+    /// apart as they can be
+    Expert,
+}
+
+impl Difficulty {
+    /// Name of the difficulty in lowercase, as the command line spells it
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+        }
+    }
+
+    /// Number of cells across and down
+    pub fn size(self) -> (usize, usize) {
+        match self {
+            Difficulty::Easy => (12, 9),
+            Difficulty::Medium => (20, 15),
+            Difficulty::Hard => (32, 24),
+            Difficulty::Expert => (48, 36),
+        }
+    }
+
+    pub fn algorithm(self) -> Algorithm {
+        match self {
+            Difficulty::Easy | Difficulty::Medium => Algorithm::Backtracker,
+            Difficulty::Hard => Algorithm::Kruskal,
+            Difficulty::Expert => Algorithm::Wilsons,
+        }
+    }
+
+    /// Fraction of the dead ends to braid away
+    pub fn braid(self) -> f64 {
+        match self {
+            Difficulty::Easy => 0.5,
+            Difficulty::Medium => 0.1,
+            Difficulty::Hard | Difficulty::Expert => 0.0,
+        }
+    }
+
+    /// Whether the entrance and exit go at the two border cells that are farthest apart, rather
+    /// than in the top left and bottom right corners
+    pub fn farthest_openings(self) -> bool {
+        self == Difficulty::Expert
+    }
+}
+
+/// Options for a maze, set one at a time and then generated with [`MazeBuilder::build`]
+///
+/// ```
+/// use dadalus::builder::Algorithm;
+/// use dadalus::grid::Direction;
+/// use dadalus::Maze;
+///
+/// let maze = Maze::builder()
+///     .size(80, 50)
+///     .algorithm(Algorithm::Kruskal)
+///     .seed(42)
+///     .entrance(Direction::West, 10)
+///     .build()?;
+/// assert_eq!(maze.entrance().unwrap().y, 10);
+/// # Ok::<(), dadalus::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct MazeBuilder {
+    width: usize,
+    height: usize,
+    algorithm: Algorithm,
+    seed: Option<u64>,
+    mask: Option<Mask>,
+    entrance: Option<(Direction, usize)>,
+    exit: Option<(Direction, usize)>,
+    braid: f64,
+    opening_width: f32,
+    farthest_openings: bool,
+}
+
+impl Default for MazeBuilder {
+    fn default() -> Self {
+        Self {
+            width: 20,
+            height: 20,
+            algorithm: Algorithm::Wilsons,
+            seed: None,
+            mask: None,
+            entrance: None,
+            exit: None,
+            braid: 0.0,
+            opening_width: 1.0,
+            farthest_openings: false,
+        }
+    }
+}
+
+impl Maze {
+    /// A [`MazeBuilder`] for a 20 by 20 maze generated with Wilson's algorithm
+    pub fn builder() -> MazeBuilder {
+        MazeBuilder::default()
+    }
+}
+
+impl MazeBuilder {
+    /// Number of cells across and down. A mask brings its own size instead.
+    pub fn size(mut self, width: usize, height: usize) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Seed for the random numbers, so that the same options always build the same maze. A
+    /// random seed is used if this is not set, or 0 without the `std` feature.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Carve the maze only in the cells that `mask` keeps, over the whole grid of the mask. Only
+    /// the Wilson's, backtracker and Kruskal algorithms can carve a mask.
+    pub fn mask(mut self, mask: Mask) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Put the entrance in the border wall on `side`, at `position` cells from the north or west
+    /// end of that side
+    pub fn entrance(mut self, side: Direction, position: usize) -> Self {
+        self.entrance = Some((side, position));
+        self
+    }
+
+    /// Put the exit in the border wall on `side`, at `position` cells from the north or west
+    /// end of that side
+    pub fn exit(mut self, side: Direction, position: usize) -> Self {
+        self.exit = Some((side, position));
+        self
+    }
+
+    /// Remove this fraction of the dead ends by opening a wall, which adds loops to the maze
+    pub fn braid(mut self, p: f64) -> Self {
+        self.braid = p;
+        self
+    }
+
+    /// Width of the entrance and exit, in cells
+    pub fn opening_width(mut self, width: f32) -> Self {
+        self.opening_width = width;
+        self
+    }
+
+    /// Move the entrance and exit to the two border cells that are farthest apart once the maze
+    /// is carved and braided, with [`Maze::place_openings_farthest`], instead of the entrance and
+    /// exit set here
+    pub fn farthest_openings(mut self, farthest: bool) -> Self {
+        self.farthest_openings = farthest;
+        self
+    }
+
+    /// Set the size, algorithm, braiding and openings that `difficulty` stands for. Options set
+    /// after this override the ones it chose.
+    ///
+    /// ```
+    /// use dadalus::builder::Difficulty;
+    /// use dadalus::Maze;
+    ///
+    /// let maze = Maze::builder().difficulty(Difficulty::Hard).seed(7).build()?;
+    /// assert_eq!((maze.width, maze.height), (32, 24));
+    /// # Ok::<(), dadalus::Error>(())
+    /// ```
+    pub fn difficulty(self, difficulty: Difficulty) -> Self {
+        let (width, height) = difficulty.size();
+        self.size(width, height)
+            .algorithm(difficulty.algorithm())
+            .braid(difficulty.braid())
+            .farthest_openings(difficulty.farthest_openings())
+    }
+
+    /// Generate the maze. Fails if the maze would be too large, if a mask is set and the
+    /// algorithm cannot carve one, or if the entrance or exit is past the end of its side.
+    pub fn build(self) -> Result<Maze, Error> {
+        self.build_with_progress(|_, _| {})
+    }
+
+    /// Generate the maze as [`MazeBuilder::build`] does, calling `progress` with the number of
+    /// cells in the maze so far and the number of cells in the grid as
+    /// [`wilsons::Generator::generate_with_progress`] does. Only Wilson's algorithm on the
+    /// rectangular grid reports its progress.
+    pub fn build_with_progress(self, progress: impl FnMut(usize, usize)) -> Result<Maze, Error> {
+        #[cfg(feature = "std")]
+        let seed = self.seed.unwrap_or_else(rand::random);
+        // Without std there is nothing to draw a random seed from
+        #[cfg(not(feature = "std"))]
+        let seed = self.seed.unwrap_or_default();
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut maze = match self.mask {
+            Some(mask) => {
+                let mut carving = Carving::new(mask);
+                match self.algorithm {
+                    Algorithm::Wilsons => wilsons::carve(&mut carving, &mut rng),
+                    Algorithm::Backtracker => backtracker::carve(&mut carving, &mut rng),
+                    Algorithm::Kruskal => kruskal::carve(&mut carving, &mut rng),
+                    other => return Err(Error::CannotCarveMask(other)),
+                }
+                carving.to_maze()
+            }
+            None => {
+                let fits = u32::try_from(self.width)
+                    .ok()
+                    .zip(u32::try_from(self.height).ok())
+                    .and_then(|(width, height)| width.checked_mul(height))
+                    .is_some_and(|len| usize::try_from(len).is_ok());
+                if !fits {
+                    return Err(Error::TooLarge {
+                        width: self.width as u64,
+                        height: self.height as u64,
+                    });
+                }
+                let (width, height) = (self.width, self.height);
+                match self.algorithm {
+                    Algorithm::Wilsons => {
+                        wilsons::Generator.generate_with_progress(width, height, &mut rng, progress)
+                    }
+                    ref algorithm => generate(algorithm, width, height, &mut rng),
+                }
+            }
+        };
+
+        let (width, height) = (maze.width as usize, maze.height as usize);
+        let border = |(side, position): (Direction, usize)| {
+            let (x, y) = match side {
+                Direction::North => (position, 0),
+                Direction::South => (position, height.saturating_sub(1)),
+                Direction::West => (0, position),
+                Direction::East => (width.saturating_sub(1), position),
+            };
+            if x < width && y < height {
+                Ok(Opening { x, y, side })
+            } else {
+                Err(Error::OpeningOutside { side, position })
+            }
+        };
+        if let Some(entrance) = self.entrance {
+            maze.set_entrance(Some(border(entrance)?))?;
+        }
+        if let Some(exit) = self.exit {
+            maze.set_exit(Some(border(exit)?))?;
+        }
+        maze.set_opening_width(self.opening_width);
+        if self.braid > 0.0 {
+            maze.braid(self.braid, &mut rng);
+        }
+        if self.farthest_openings {
+            maze.place_openings_farthest();
+        }
+        Ok(maze)
+    }
+}
+
+fn generate(algorithm: &Algorithm, width: usize, height: usize, rng: &mut ChaCha8Rng) -> Maze {
+    match algorithm {
+        Algorithm::Wilsons => wilsons::Generator.generate(width, height, rng),
+        Algorithm::Backtracker => backtracker::Generator.generate(width, height, rng),
+        Algorithm::Kruskal => kruskal::Generator.generate(width, height, rng),
+        Algorithm::AldousBroder => aldous_broder::Generator::new().generate(width, height, rng),
+        Algorithm::AldousBroderWilsons => {
+            aldous_broder::Generator::hybrid(0.5).generate(width, height, rng)
+        }
+        Algorithm::HuntAndKill => hunt_and_kill::Generator.generate(width, height, rng),
+        Algorithm::BinaryTree(bias) => {
+            binary_tree::Generator::new(*bias).generate(width, height, rng)
+        }
+        Algorithm::GrowingTree(selection) => {
+            growing_tree::Generator::new(selection.clone()).generate(width, height, rng)
+        }
+        Algorithm::Fractal => fractal::Generator.generate(width, height, rng),
+        Algorithm::OriginShift => origin_shift::Generator.generate(width, height, rng),
+        Algorithm::Weave => weave::Generator::default().generate(width, height, rng),
+        Algorithm::Regional => {
+            let center = Region::new(width / 4, height / 4, width / 2, height / 2);
+            let prims = growing_tree::Generator::new(Selection::Random);
+            regional::Generator::new(backtracker::Generator)
+                .region(center, prims)
+                .generate(width, height, rng)
+        }
+    }
+}
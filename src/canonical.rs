@@ -0,0 +1,102 @@
+use alloc::vec::Vec;
+
+use crate::grid::Direction;
+use crate::maze::Maze;
+
+/// One of the eight ways to turn or mirror a maze: optionally swap the axes, then optionally
+/// flip each of them
+#[derive(Clone, Copy)]
+struct Symmetry {
+    transpose: bool,
+    flip_x: bool,
+    flip_y: bool,
+}
+
+impl Symmetry {
+    fn all() -> impl Iterator<Item = Symmetry> {
+        (0..8).map(|bits| Symmetry {
+            transpose: bits & 1 != 0,
+            flip_x: bits & 2 != 0,
+            flip_y: bits & 4 != 0,
+        })
+    }
+
+    /// Width and height of a `width` by `height` maze after the symmetry
+    fn size(self, width: usize, height: usize) -> (usize, usize) {
+        if self.transpose {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Where the cell at `x, y` of a `width` by `height` maze goes
+    fn cell(self, (x, y): (usize, usize), width: usize, height: usize) -> (usize, usize) {
+        let (x, y) = if self.transpose { (y, x) } else { (x, y) };
+        let (width, height) = self.size(width, height);
+        let x = if self.flip_x { width - 1 - x } else { x };
+        let y = if self.flip_y { height - 1 - y } else { y };
+        (x, y)
+    }
+
+    fn direction(self, direction: Direction) -> Direction {
+        use Direction::*;
+        let direction = match (self.transpose, direction) {
+            (true, North) => West,
+            (true, West) => North,
+            (true, South) => East,
+            (true, East) => South,
+            (false, direction) => direction,
+        };
+        match direction {
+            East | West if self.flip_x => direction.opposite(),
+            North | South if self.flip_y => direction.opposite(),
+            direction => direction,
+        }
+    }
+}
+
+impl Maze {
+    /// The same layout turned or mirrored into a standard orientation, so that two mazes are
+    /// rotations or reflections of each other exactly when their canonical forms are equal.
+    /// This makes it possible to deduplicate generated mazes by hashing their canonical forms.
+    ///
+    /// Of the eight orientations, the canonical form is the one whose cells compare lowest row
+    /// by row. Only the walls and tunnels are part of the layout, so the canonical maze has the
+    /// entrance, exit and opening width of [`Maze::new`].
+    pub fn canonical(&self) -> Maze {
+        Symmetry::all()
+            .map(|symmetry| self.transform(symmetry))
+            .min_by(|a, b| {
+                let key = |maze: &Maze| -> (u32, u32, Vec<u8>) {
+                    let cells = maze.iter_cells().map(|cell| cell.bits()).collect();
+                    (maze.width, maze.height, cells)
+                };
+                key(a).cmp(&key(b))
+            })
+            .unwrap()
+    }
+
+    fn transform(&self, symmetry: Symmetry) -> Maze {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let (new_width, new_height) = symmetry.size(width, height);
+        let mut maze = Maze::new(new_width as u32, new_height as u32);
+        let grid = maze.grid();
+        for cell in self.iter_cells() {
+            let (x, y) = symmetry.cell((cell.x(), cell.y()), width, height);
+            let index = grid.index(x, y);
+            if cell.tunnel() {
+                maze.cell_at_index_mut(index).set_tunnel();
+            }
+            // Walls are owned by the cell to the south or east of them, so the north and west
+            // walls of every cell are every inner wall once
+            if cell.y() > 0 && cell.north_open() {
+                maze.open_wall(index, symmetry.direction(Direction::North));
+            }
+            if cell.x() > 0 && cell.west_open() {
+                maze.open_wall(index, symmetry.direction(Direction::West));
+            }
+        }
+        maze
+    }
+}
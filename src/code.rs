@@ -0,0 +1,130 @@
+use std::io;
+
+use miniz_oxide::deflate::compress_to_vec;
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+
+use crate::error::Error;
+use crate::maze::Maze;
+
+/// The URL-safe base64 alphabet from RFC 4648
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Largest saved maze that a code may expand to. Saved mazes pack three bits per cell and
+/// [`Maze::read_from`] reads all of them before allocating the maze, so this also caps the maze a
+/// code can make at a little under 180 million cells.
+const MAX_DECODED: usize = 64 << 20;
+
+impl Maze {
+    /// A short code for the maze that can be put in a URL or pasted into a message, and turned
+    /// back into the same maze with [`Maze::from_code`]. The code is the maze in the binary save
+    /// format, compressed with deflate and written in URL-safe base64 without padding.
+    pub fn to_code(&self) -> String {
+        let mut saved = Vec::new();
+        self.write_to(&mut saved, None).unwrap();
+        let bytes = compress_to_vec(&saved, 10);
+
+        let mut code = String::with_capacity(bytes.len().div_ceil(3) * 4);
+        for chunk in bytes.chunks(3) {
+            let group = chunk.iter().enumerate().fold(0u32, |group, (k, byte)| {
+                group | (*byte as u32) << (16 - 8 * k)
+            });
+            // Three bytes make four characters, and a shorter last chunk one more than its length
+            for k in 0..=chunk.len() {
+                code.push(ALPHABET[(group >> (18 - 6 * k) & 0x3f) as usize] as char);
+            }
+        }
+        code
+    }
+
+    /// Read a maze from a code made by [`Maze::to_code`]. Whitespace in the code is ignored, so
+    /// codes that were wrapped across lines still work.
+    pub fn from_code(code: &str) -> Result<Self, Error> {
+        let digits = code
+            .bytes()
+            .filter(|byte| !byte.is_ascii_whitespace())
+            .map(|byte| {
+                ALPHABET
+                    .iter()
+                    .position(|c| *c == byte)
+                    .map(|digit| digit as u32)
+                    .ok_or_else(|| invalid(format!("invalid character {:?}", byte as char)))
+            })
+            .collect::<io::Result<Vec<u32>>>()?;
+        if digits.len() % 4 == 1 {
+            return Err(invalid("code is cut off").into());
+        }
+
+        let mut bytes = Vec::with_capacity(digits.len() * 3 / 4);
+        for chunk in digits.chunks(4) {
+            let group = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |group, (k, digit)| group | digit << (18 - 6 * k));
+            for k in 0..chunk.len() - 1 {
+                bytes.push((group >> (16 - 8 * k)) as u8);
+            }
+        }
+        let saved = decompress_to_vec_with_limit(&bytes, MAX_DECODED)
+            .map_err(|err| invalid(format!("code does not decompress: {:?}", err.status)))?;
+        Self::read_from(saved.as_slice()).map(|(maze, _)| maze)
+    }
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::Algorithm;
+
+    fn maze() -> Maze {
+        Maze::builder()
+            .size(12, 8)
+            .algorithm(Algorithm::Weave)
+            .braid(0.3)
+            .seed(11)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn round_trips() {
+        let maze = maze();
+        let code = maze.to_code();
+        assert!(code.bytes().all(|byte| ALPHABET.contains(&byte)));
+        assert!(Maze::from_code(&code).unwrap() == maze);
+    }
+
+    #[test]
+    fn ignores_whitespace() {
+        let maze = maze();
+        let code = maze.to_code();
+        let wrapped: String = code
+            .chars()
+            .enumerate()
+            .flat_map(|(k, c)| [c].into_iter().chain(["\n", " ", "\t"][k % 3].chars()))
+            .collect();
+        assert!(Maze::from_code(&format!("  {wrapped}\r\n")).unwrap() == maze);
+    }
+
+    #[test]
+    fn rejects_cut_off_codes() {
+        let code = maze().to_code();
+        for len in [0, 1, 5, code.len() / 2, code.len() - 2, code.len() - 1] {
+            assert!(Maze::from_code(&code[..len]).is_err(), "{len} characters");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_characters() {
+        let code = maze().to_code();
+        for bad in ['+', '/', '=', 'é'] {
+            let mut broken = code.clone();
+            broken.insert(code.len() / 2, bad);
+            let err = Maze::from_code(&broken).err().unwrap();
+            assert!(err.to_string().contains("invalid character"), "{err}");
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use clap::error::ErrorKind;
+use clap::{Arg, Command, Error};
+use toml::{Table, Value};
+
+/// File in the current directory that defaults are read from when --config is not given
+const FILE_NAME: &str = "dadalus.toml";
+
+/// The command line `args` with the settings from the configuration file added in front of the
+/// flags that were given, so that the flags on the command line override them. Settings for
+/// flags that cannot be used together with a flag on the command line, like `width` with
+/// `--difficulty`, are left out as well.
+///
+/// Every key in the file is the long name of a flag, such as `cell-size = 20` for
+/// `--cell-size 20`, with `true` for flags that take no value. Settings for flags that the
+/// subcommand being run does not have are left out, so that one file can hold the defaults for
+/// all of them.
+pub fn apply(mut command: Command, mut args: Vec<OsString>) -> Result<Vec<OsString>, Error> {
+    command.build();
+    let Some(path) = path(&args) else {
+        return Ok(args);
+    };
+    let (_, first) = find_config(&args);
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(err) => {
+            let message = format!("{}: {err}", path.display());
+            return Err(error(&command, ErrorKind::Io, message));
+        }
+    };
+    let table: Table = match text.parse() {
+        Ok(table) => table,
+        Err(err) => {
+            let message = format!("{}: {err}", path.display());
+            return Err(error(&command, ErrorKind::InvalidValue, message));
+        }
+    };
+
+    // Settings go right after the subcommand, or after the program name if there is none
+    let subcommand = first.and_then(|first| {
+        let name = args[first].to_str()?;
+        Some((command.find_subcommand(name)?, first + 1))
+    });
+    let (target, at) = subcommand.unwrap_or((&command, 1));
+    let given: Vec<&Arg> = args[at..]
+        .iter()
+        .filter_map(|arg| {
+            let flag = arg.to_str()?.strip_prefix("--")?;
+            find_arg(target, flag.split('=').next()?)
+        })
+        .collect();
+
+    let mut settings = Vec::new();
+    for (key, value) in &table {
+        let long = key.replace('_', "-");
+        let Some(arg) = find_arg(target, &long) else {
+            let known = std::iter::once(&command)
+                .chain(command.get_subcommands())
+                .any(|command| find_arg(command, &long).is_some());
+            if known {
+                continue;
+            }
+            let message = format!("{}: unknown setting {key:?}", path.display());
+            return Err(error(&command, ErrorKind::UnknownArgument, message));
+        };
+        if long == "config" {
+            let message = format!(
+                "{}: the configuration file cannot set --config",
+                path.display()
+            );
+            return Err(error(&command, ErrorKind::InvalidValue, message));
+        }
+        if given.iter().any(|given| conflict(target, arg, given)) {
+            continue;
+        }
+
+        let values = match value {
+            Value::Array(values) => values.iter().collect(),
+            value => vec![value],
+        };
+        for value in values {
+            let value = match value {
+                Value::Boolean(set) if !arg.get_action().takes_values() => {
+                    if *set {
+                        settings.push(OsString::from(format!("--{long}")));
+                    }
+                    continue;
+                }
+                Value::String(text) => text.clone(),
+                Value::Integer(_) | Value::Float(_) | Value::Boolean(_) => value.to_string(),
+                _ => {
+                    let message = format!(
+                        "{}: {key} must be a string, number or boolean",
+                        path.display()
+                    );
+                    return Err(error(&command, ErrorKind::InvalidValue, message));
+                }
+            };
+            settings.push(OsString::from(format!("--{long}={value}")));
+        }
+    }
+
+    args.splice(at..at, settings);
+    Ok(args)
+}
+
+/// The configuration file that [`apply`] reads for the command line `args`, if there is one
+pub fn path(args: &[OsString]) -> Option<PathBuf> {
+    let (path, _) = find_config(args);
+    path.or_else(|| {
+        Path::new(FILE_NAME)
+            .exists()
+            .then(|| PathBuf::from(FILE_NAME))
+    })
+}
+
+/// The path given with --config, and the index of the first argument after the program name
+/// that is not part of it
+fn find_config(args: &[OsString]) -> (Option<PathBuf>, Option<usize>) {
+    let (mut path, mut first) = (None, None);
+    let mut k = 1;
+    while k < args.len() {
+        let text = args[k].to_string_lossy();
+        if text == "--config" {
+            path = args.get(k + 1).map(PathBuf::from);
+            k += 1;
+        } else if let Some(value) = text.strip_prefix("--config=") {
+            path = Some(PathBuf::from(value));
+        } else {
+            first.get_or_insert(k);
+        }
+        k += 1;
+    }
+    (path, first)
+}
+
+fn error(command: &Command, kind: ErrorKind, message: String) -> Error {
+    command.clone().error(kind, message)
+}
+
+fn find_arg<'a>(command: &'a Command, long: &str) -> Option<&'a Arg> {
+    command
+        .get_arguments()
+        .find(|arg| arg.get_long() == Some(long))
+}
+
+/// Whether `a` and `b` cannot be given together, whichever of them names the other
+fn conflict(command: &Command, a: &Arg, b: &Arg) -> bool {
+    let names = |a: &Arg, b: &Arg| {
+        command
+            .get_arg_conflicts_with(a)
+            .iter()
+            .any(|other| other.get_id() == b.get_id())
+    };
+    names(a, b) || names(b, a)
+}
@@ -0,0 +1,205 @@
+//! A fixed set of small mazes with their solutions, statistics and renders in every output
+//! format, for the regression tests of programs built on dadalus and for checking new exporters
+//! against known-good data.
+//!
+//! The mazes are generated from fixed seeds, so the corpus comes out the same every time it is
+//! made, and stays the same in later versions for as long as the seeds and output formats do.
+//!
+//! ```no_run
+//! dadalus::corpus::write("fixtures")?;
+//! # Ok::<(), dadalus::Error>(())
+//! ```
+
+use std::fmt::Write as _;
+use std::io;
+use std::path::Path;
+
+use tiny_skia::Color;
+
+use crate::binary_tree::Bias;
+use crate::builder::{Algorithm, MazeBuilder};
+use crate::error::Error;
+use crate::growing_tree::Selection;
+use crate::maze::{Maze, RenderOptions};
+use crate::mesh::MeshOptions;
+use crate::pdf::PdfOptions;
+use crate::plotter::Plot;
+use crate::schematic::SchematicOptions;
+use crate::svg::SvgOptions;
+use crate::tilemap::TmxOptions;
+
+/// Seed every maze of the corpus is generated from
+const SEED: u64 = 1;
+
+/// Size of a cell in the PNG renders, in pixels
+const CELL_SIZE: u32 = 10;
+
+/// One maze of the corpus
+pub struct Fixture {
+    /// Name that the files of the fixture start with, such as `kruskal-8x6`
+    pub name: String,
+    /// Seed the maze was built from with [`MazeBuilder::seed`]
+    pub seed: u64,
+    pub maze: Maze,
+}
+
+/// The mazes of the corpus: one for every algorithm that [`MazeBuilder`] has, and a few more for
+/// braiding, the smallest maze and openings placed with [`MazeBuilder::farthest_openings`]
+pub fn fixtures() -> Vec<Fixture> {
+    let algorithms = [
+        Algorithm::Wilsons,
+        Algorithm::Backtracker,
+        Algorithm::Kruskal,
+        Algorithm::AldousBroder,
+        Algorithm::AldousBroderWilsons,
+        Algorithm::HuntAndKill,
+        Algorithm::BinaryTree(Bias::NorthWest),
+        Algorithm::GrowingTree(Selection::Newest),
+        Algorithm::Fractal,
+        Algorithm::OriginShift,
+        Algorithm::Weave,
+        Algorithm::Regional,
+    ];
+    let mut fixtures: Vec<Fixture> = algorithms
+        .into_iter()
+        .map(|algorithm| {
+            fixture(
+                format!("{}-8x6", algorithm.name()),
+                Maze::builder().size(8, 6).algorithm(algorithm),
+            )
+        })
+        .collect();
+    fixtures.push(fixture(
+        "wilsons-8x6-braided".to_owned(),
+        Maze::builder().size(8, 6).braid(0.5),
+    ));
+    fixtures.push(fixture(
+        "wilsons-1x1".to_owned(),
+        Maze::builder().size(1, 1),
+    ));
+    fixtures.push(fixture(
+        "wilsons-12x9-farthest".to_owned(),
+        Maze::builder().size(12, 9).farthest_openings(true),
+    ));
+    fixtures
+}
+
+fn fixture(name: String, builder: MazeBuilder) -> Fixture {
+    let maze = builder
+        .seed(SEED)
+        .build()
+        .expect("corpus mazes are small enough to build");
+    Fixture {
+        name,
+        seed: SEED,
+        maze,
+    }
+}
+
+impl Fixture {
+    /// The files of the fixture as pairs of a file name and its contents. Each name is the name
+    /// of the fixture followed by what the file holds:
+    ///
+    /// - `.maze`, the maze and its seed in the binary save format, and `.code`, its
+    ///   [share code](Maze::to_code)
+    /// - `.txt`, the maze drawn in text
+    /// - `.solution.txt`, the cells of the [solution](Maze::solution) as `x,y`, one per line
+    /// - `.stats.txt`, the [stats](Maze::stats) and [texture](Maze::texture) of the maze, in the
+    ///   lines that `dadalus analyze` prints
+    /// - `.png` and `.solution.png`, drawings without and with the solution
+    /// - `.svg`, `.pdf`, `.dot`, `.stl`, `.obj`, `.schem`, `.csv`, `.tmx` and `.hpgl`, the other
+    ///   output formats with their default options
+    /// - with the `serde` feature, `.json`
+    pub fn files(&self) -> Result<Vec<(String, Vec<u8>)>, Error> {
+        let maze = &self.maze;
+        let mut saved = Vec::new();
+        maze.write_to(&mut saved, Some(self.seed))?;
+        let solution = maze
+            .solution()
+            .map(|path| {
+                let cells = path.cells().iter();
+                cells.map(|(x, y)| format!("{x},{y}\n")).collect::<String>()
+            })
+            .unwrap_or_default();
+        let options = RenderOptions {
+            background: Color::WHITE,
+            margin: 2,
+            ..RenderOptions::new(CELL_SIZE)
+        };
+        let png = |pixmap: tiny_skia::Pixmap| pixmap.encode_png().map_err(io::Error::other);
+        let mesh = MeshOptions::default();
+
+        #[cfg_attr(not(feature = "serde"), allow(unused_mut))]
+        let mut files = vec![
+            ("maze", saved),
+            ("code", maze.to_code().into_bytes()),
+            ("txt", maze.to_string().into_bytes()),
+            ("solution.txt", solution.into_bytes()),
+            ("stats.txt", stats(maze).into_bytes()),
+            ("png", png(maze.draw(&options)?)?),
+            ("solution.png", png(maze.draw_with_solution(&options)?)?),
+            ("svg", maze.to_svg(&SvgOptions::default()).into_bytes()),
+            ("pdf", maze.to_pdf(&PdfOptions::default())),
+            ("dot", maze.to_dot().into_bytes()),
+            ("stl", maze.to_stl(&mesh)),
+            ("obj", maze.to_obj(&mesh).into_bytes()),
+            ("schem", maze.to_schematic(&SchematicOptions::default())),
+            ("csv", maze.to_tile_csv().into_bytes()),
+            ("tmx", maze.to_tmx(&TmxOptions::default()).into_bytes()),
+            ("hpgl", Plot::new(maze).to_hpgl(2.0).into_bytes()),
+        ];
+        #[cfg(feature = "serde")]
+        files.push(("json", maze.to_json().into_bytes()));
+        Ok(files
+            .into_iter()
+            .map(|(extension, bytes)| (format!("{}.{extension}", self.name), bytes))
+            .collect())
+    }
+}
+
+/// Write the files of every fixture into the directory at `path`, creating it if it does not
+/// exist yet
+pub fn write<P: AsRef<Path>>(path: P) -> Result<(), Error> {
+    let dir = path.as_ref();
+    std::fs::create_dir_all(dir)?;
+    for fixture in fixtures() {
+        for (name, bytes) in fixture.files()? {
+            std::fs::write(dir.join(name), bytes)?;
+        }
+    }
+    Ok(())
+}
+
+/// The stats and texture of `maze` as `name: value` lines
+fn stats(maze: &Maze) -> String {
+    let stats = maze.stats();
+    let texture = maze.texture();
+    let optional = |value: Option<String>| value.unwrap_or_else(|| "none".to_owned());
+    let mut text = String::new();
+    let lines = [
+        ("size", format!("{}x{}", maze.width, maze.height)),
+        ("dead ends", stats.dead_ends.to_string()),
+        ("three-way junctions", stats.three_way_junctions.to_string()),
+        ("four-way junctions", stats.four_way_junctions.to_string()),
+        ("corridors", stats.corridors.to_string()),
+        (
+            "average corridor length",
+            format!("{:.2}", stats.average_corridor_length),
+        ),
+        ("longest corridor", stats.max_corridor_length.to_string()),
+        (
+            "solution length",
+            optional(stats.solution_length.map(|length| length.to_string())),
+        ),
+        ("river", format!("{:.2}", texture.river)),
+        ("twistiness", format!("{:.2}", texture.twistiness)),
+        (
+            "elitism",
+            optional(texture.elitism.map(|elitism| format!("{elitism:.2}"))),
+        ),
+    ];
+    for (name, value) in lines {
+        writeln!(text, "{name}: {value}").unwrap();
+    }
+    text
+}
@@ -0,0 +1,64 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// A union-find structure over the elements `0..len`, with path compression and union by rank
+#[derive(Clone, Debug)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+}
+
+impl DisjointSet {
+    /// Every element starts out in a set of its own
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            rank: vec![0; len],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// The representative element of the set containing `element`
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        // Point everything along the way directly at the root so later lookups are short
+        let mut curr = element;
+        while self.parent[curr] != root {
+            let next = self.parent[curr];
+            self.parent[curr] = root;
+            curr = next;
+        }
+        root
+    }
+
+    pub fn same_set(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Merge the sets containing `a` and `b`. Returns `false` if they were already the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        match self.rank[a].cmp(&self.rank[b]) {
+            core::cmp::Ordering::Less => self.parent[a] = b,
+            core::cmp::Ordering::Greater => self.parent[b] = a,
+            core::cmp::Ordering::Equal => {
+                self.parent[b] = a;
+                self.rank[a] += 1;
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,170 @@
+use alloc::string::String;
+use core::fmt::{self, Write};
+
+use crate::grid::Direction;
+use crate::maze::Maze;
+
+/// Draws the maze with Unicode box-drawing characters, two columns and one row per cell. The
+/// alternate form, `{:#}`, draws it in plain ASCII with `+`, `-` and `|` for terminals and files
+/// that cannot show box drawing. Entrance and exit openings are shown one cell wide; tunnels are
+/// not shown.
+impl fmt::Display for Maze {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (width, height) = (self.width as usize, self.height as usize);
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        let ascii = f.alternate();
+        for y in 0..=height {
+            for x in 0..=width {
+                let up = y > 0 && self.vertical_wall(x, y - 1);
+                let down = y < height && self.vertical_wall(x, y);
+                let left = x > 0 && self.horizontal_wall(x - 1, y);
+                let right = x < width && self.horizontal_wall(x, y);
+                f.write_char(corner(up, down, left, right, ascii))?;
+                if x < width {
+                    let wall = match (self.horizontal_wall(x, y), ascii) {
+                        (true, true) => "--",
+                        (true, false) => "──",
+                        (false, _) => "  ",
+                    };
+                    f.write_str(wall)?;
+                }
+            }
+            f.write_char('\n')?;
+            if y == height {
+                break;
+            }
+            for x in 0..=width {
+                let wall = match (self.vertical_wall(x, y), ascii) {
+                    (true, true) => '|',
+                    (true, false) => '│',
+                    (false, _) => ' ',
+                };
+                f.write_char(wall)?;
+                if x < width {
+                    f.write_str("  ")?;
+                }
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+impl Maze {
+    /// Draw the maze with Unicode braille patterns, one dot per wall, post or cell and each
+    /// character covering two by four dots. This fits about two cells by two into every
+    /// character, so large mazes fit on screen at a glance. Like [`Display`](fmt::Display),
+    /// openings are one cell wide and tunnels are not shown.
+    pub fn to_braille(&self) -> String {
+        let (width, height) = (self.width as usize, self.height as usize);
+        if width == 0 || height == 0 {
+            return String::new();
+        }
+        let (columns, rows) = (2 * width + 1, 2 * height + 1);
+        // The bit of each dot in a braille pattern, by row and then column
+        const DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+        let mut text = String::new();
+        for top in (0..rows).step_by(4) {
+            for left in (0..columns).step_by(2) {
+                let mut bits = 0;
+                for (dy, row) in DOTS.iter().enumerate() {
+                    for (dx, bit) in row.iter().enumerate() {
+                        let (column, row) = (left + dx, top + dy);
+                        if column < columns && row < rows && self.wall_at(column, row) {
+                            bits |= bit;
+                        }
+                    }
+                }
+                text.push(char::from_u32(0x2800 + bits).unwrap());
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Whether there is a wall in the square at `column, row` of a grid that alternates wall
+    /// lines and cells, with column and row `2k` on the west and north walls of cell `k`. Posts,
+    /// where both are even, are solid wherever a wall ends.
+    pub(crate) fn wall_at(&self, column: usize, row: usize) -> bool {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let (x, y) = (column / 2, row / 2);
+        match (column % 2, row % 2) {
+            (1, 0) => self.horizontal_wall(x, y),
+            (0, 1) => self.vertical_wall(x, y),
+            (0, 0) => {
+                (x < width && self.horizontal_wall(x, y))
+                    || (x > 0 && self.horizontal_wall(x - 1, y))
+                    || (y < height && self.vertical_wall(x, y))
+                    || (y > 0 && self.vertical_wall(x, y - 1))
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether there is a wall along the north edge of the cell at `x, y`, where a `y` of
+    /// `height` is the south border
+    pub(crate) fn horizontal_wall(&self, x: usize, y: usize) -> bool {
+        let height = self.height as usize;
+        if y == 0 || y == height {
+            let side = if y == 0 {
+                Direction::North
+            } else {
+                Direction::South
+            };
+            return !self.has_opening(x, y.min(height - 1), side);
+        }
+        !self.cell(x, y).north_open()
+    }
+
+    /// Whether there is a wall along the west edge of the cell at `x, y`, where an `x` of
+    /// `width` is the east border
+    pub(crate) fn vertical_wall(&self, x: usize, y: usize) -> bool {
+        let width = self.width as usize;
+        if x == 0 || x == width {
+            let side = if x == 0 {
+                Direction::West
+            } else {
+                Direction::East
+            };
+            return !self.has_opening(x.min(width - 1), y, side);
+        }
+        !self.cell(x, y).west_open()
+    }
+
+    fn has_opening(&self, x: usize, y: usize, side: Direction) -> bool {
+        self.openings()
+            .any(|opening| (opening.x, opening.y, opening.side) == (x, y, side))
+    }
+}
+
+/// The character where walls meet, given which of the four directions walls leave it in
+fn corner(up: bool, down: bool, left: bool, right: bool, ascii: bool) -> char {
+    if ascii {
+        return match (up || down, left || right) {
+            (false, false) => ' ',
+            (true, false) => '|',
+            (false, true) => '-',
+            (true, true) => '+',
+        };
+    }
+    match (up, down, left, right) {
+        (false, false, false, false) => ' ',
+        (true, false, false, false) => '╵',
+        (false, true, false, false) => '╷',
+        (false, false, true, false) => '╴',
+        (false, false, false, true) => '╶',
+        (true, true, false, false) => '│',
+        (false, false, true, true) => '─',
+        (false, true, false, true) => '┌',
+        (false, true, true, false) => '┐',
+        (true, false, false, true) => '└',
+        (true, false, true, false) => '┘',
+        (true, true, false, true) => '├',
+        (true, true, true, false) => '┤',
+        (false, true, true, true) => '┬',
+        (true, false, true, true) => '┴',
+        (true, true, true, true) => '┼',
+    }
+}
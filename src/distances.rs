@@ -0,0 +1,125 @@
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::grid::RectGrid;
+use crate::maze::Maze;
+use crate::solve::Path;
+
+/// The number of moves from a root cell to every cell of a maze, as computed by
+/// [`Maze::distances`]. Moves go through open walls and tunnels, as in [`Maze::solve`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DistanceMap {
+    grid: RectGrid,
+    root: (usize, usize),
+    distances: Vec<Option<usize>>,
+}
+
+impl DistanceMap {
+    pub fn width(&self) -> usize {
+        self.grid.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.grid.height
+    }
+
+    /// The cell that distances are measured from
+    pub fn root(&self) -> (usize, usize) {
+        self.root
+    }
+
+    /// The distance from the root to the cell at `x, y`, or `None` if it cannot be reached or is
+    /// outside of the maze
+    pub fn get(&self, x: usize, y: usize) -> Option<usize> {
+        if !self.grid.contains(x, y) {
+            return None;
+        }
+        self.distances[self.grid.index(x, y)]
+    }
+
+    /// The reachable cell farthest from the root and its distance. Ties go to the cell that
+    /// comes first row by row.
+    pub fn farthest(&self) -> ((usize, usize), usize) {
+        let (idx, distance) = self
+            .distances
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, d)| d.map(|d| (idx, d)))
+            .rev()
+            .max_by_key(|(_, d)| *d)
+            .expect("the root is always reachable");
+        (self.grid.coords(idx), distance)
+    }
+
+    /// The largest distance to any reachable cell
+    pub fn max(&self) -> usize {
+        self.farthest().1
+    }
+
+    /// Number of cells that can be reached from the root, including the root itself
+    pub fn reachable(&self) -> usize {
+        self.distances.iter().filter(|d| d.is_some()).count()
+    }
+
+    /// Every cell with its distance from the root, row by row
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), Option<usize>)> + '_ {
+        self.distances
+            .iter()
+            .enumerate()
+            .map(|(idx, d)| (self.grid.coords(idx), *d))
+    }
+
+    /// A shortest path from the root to the cell at `x, y`, found by stepping back from it to a
+    /// neighbor one move closer to the root until the root is reached. `maze` must be the maze
+    /// the distances were computed for.
+    pub fn path_to(&self, maze: &Maze, x: usize, y: usize) -> Option<Path> {
+        let mut distance = self.get(x, y)?;
+        let mut idx = self.grid.index(x, y);
+        let mut cells = vec![(x, y)];
+        while distance > 0 {
+            distance -= 1;
+            idx = maze
+                .passages(idx)
+                .into_iter()
+                .find(|next| self.distances[*next] == Some(distance))?;
+            cells.push(self.grid.coords(idx));
+        }
+        cells.reverse();
+        Some(Path::new(cells))
+    }
+}
+
+impl Maze {
+    /// The distance from the cell at `from` to every other cell. Every move costs the same, so a
+    /// breadth-first search finds the same distances as Dijkstra's algorithm would.
+    ///
+    /// # Panics
+    ///
+    /// If `from` is outside of the maze.
+    pub fn distances(&self, from: (usize, usize)) -> DistanceMap {
+        let grid = self.grid();
+        assert!(
+            grid.contains(from.0, from.1),
+            "root {from:?} is outside of the maze"
+        );
+        let mut distances = vec![None; grid.len()];
+        let start = grid.index(from.0, from.1);
+        distances[start] = Some(0);
+        let mut queue = VecDeque::from([start]);
+        while let Some(idx) = queue.pop_front() {
+            let next_distance = distances[idx].map(|d| d + 1);
+            for next in self.passages(idx) {
+                if distances[next].is_none() {
+                    distances[next] = next_distance;
+                    queue.push_back(next);
+                }
+            }
+        }
+        DistanceMap {
+            grid,
+            root: from,
+            distances,
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use std::fmt::Write;
+
+use crate::maze::Maze;
+
+impl Maze {
+    /// The passage graph as a Graphviz DOT document: one node per cell, named after its
+    /// coordinates, and an edge wherever two cells are joined by an open wall or a tunnel. Nodes
+    /// are pinned to their place on the grid an inch apart, so `neato` draws the graph with the
+    /// layout of the maze.
+    pub fn to_dot(&self) -> String {
+        let grid = self.grid();
+        let mut dot = String::from("graph maze {\n");
+        dot.push_str("  node [shape=point];\n");
+        for idx in 0..grid.len() {
+            let (x, y) = grid.coords(idx);
+            // Graphviz's y axis points up, so flip the maze to keep north at the top
+            writeln!(dot, "  c{x}_{y} [pos=\"{x},{}!\"];", grid.height - 1 - y).unwrap();
+        }
+        for idx in 0..grid.len() {
+            let (x, y) = grid.coords(idx);
+            for next in self.passages(idx).into_iter().filter(|next| *next > idx) {
+                let (nx, ny) = grid.coords(next);
+                writeln!(dot, "  c{x}_{y} -- c{nx}_{ny};").unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
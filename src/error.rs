@@ -0,0 +1,130 @@
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+use crate::builder::Algorithm;
+use crate::grid::Direction;
+use crate::mask::ParseMaskError;
+use crate::maze::{NotAdjacent, Opening};
+#[cfg(feature = "render")]
+use crate::text::FontError;
+
+/// Everything that can go wrong in dadalus
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// An image of this many pixels cannot be made, because it would be empty or too large
+    ImageSize {
+        width: u64,
+        height: u64,
+    },
+    /// A page of this many millimeters is too small to hold any of the maze inside the margins
+    /// of [`Maze::to_tiled_pdf`](crate::Maze::to_tiled_pdf)
+    #[cfg(feature = "render")]
+    PageSize {
+        width: f32,
+        height: f32,
+    },
+    /// A maze of this many cells would be too large to store
+    TooLarge {
+        width: u64,
+        height: u64,
+    },
+    /// The algorithm only generates mazes on the full rectangular grid, so it cannot carve a mask
+    CannotCarveMask(Algorithm),
+    /// An entrance or exit was placed past the end of its side of the maze
+    OpeningOutside {
+        side: Direction,
+        position: usize,
+    },
+    /// An entrance or exit was placed somewhere other than the outer wall of a border cell
+    NotOnBorder(Opening),
+    NotAdjacent(NotAdjacent),
+    #[cfg(feature = "render")]
+    Font(FontError),
+    Mask(ParseMaskError),
+    /// Reading or writing failed, or a saved maze could not be read
+    #[cfg(feature = "std")]
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ImageSize { width, height } => {
+                write!(f, "cannot make an image of {width}x{height} pixels")
+            }
+            #[cfg(feature = "render")]
+            Error::PageSize { width, height } => {
+                write!(
+                    f,
+                    "a page of {width}x{height} mm is too small to tile a maze onto"
+                )
+            }
+            Error::TooLarge { width, height } => {
+                write!(f, "a maze of {width}x{height} cells is too large")
+            }
+            Error::CannotCarveMask(algorithm) => {
+                write!(f, "the {algorithm:?} algorithm cannot carve a mask")
+            }
+            Error::OpeningOutside { side, position } => {
+                write!(
+                    f,
+                    "position {position} is past the end of the {side:?} side"
+                )
+            }
+            Error::NotOnBorder(Opening { x, y, side }) => {
+                write!(
+                    f,
+                    "the {side:?} wall of {x}, {y} is not on the border of the maze"
+                )
+            }
+            Error::NotAdjacent(err) => err.fmt(f),
+            #[cfg(feature = "render")]
+            Error::Font(err) => err.fmt(f),
+            Error::Mask(err) => err.fmt(f),
+            #[cfg(feature = "std")]
+            Error::Io(err) => err.fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Error::NotAdjacent(err) => Some(err),
+            #[cfg(feature = "render")]
+            Error::Font(err) => Some(err),
+            Error::Mask(err) => Some(err),
+            #[cfg(feature = "std")]
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<NotAdjacent> for Error {
+    fn from(err: NotAdjacent) -> Self {
+        Error::NotAdjacent(err)
+    }
+}
+
+#[cfg(feature = "render")]
+impl From<FontError> for Error {
+    fn from(err: FontError) -> Self {
+        Error::Font(err)
+    }
+}
+
+impl From<ParseMaskError> for Error {
+    fn from(err: ParseMaskError) -> Self {
+        Error::Mask(err)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
@@ -0,0 +1,266 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::error::Error;
+use crate::grid::Direction;
+use crate::maze::{cell_count, Cell, Maze, Opening};
+
+/// The first bytes of every saved maze
+const MAGIC: &[u8; 4] = b"DDLS";
+
+const VERSION: u8 = 1;
+
+/// Bits stored per cell: west open, north open and tunnel
+const CELL_BITS: usize = 3;
+
+/// Header flag set when a seed follows the dimensions
+const HAS_SEED: u8 = 1;
+
+/// Side byte of an entrance or exit that the maze does not have
+const NO_OPENING: u8 = 0xff;
+
+/// Mazes are saved in a small binary format, with all numbers little-endian:
+///
+/// - the magic bytes `DDLS` and a format version byte, currently 1
+/// - width and height as `u32`
+/// - a flags byte, with bit 0 set if the seed the maze was generated with follows as a `u64`
+/// - the opening width as an `f32`
+/// - the entrance and then the exit, each as `x` and `y` `u32`s and a side byte: 0 for north, 1
+///   for south, 2 for east and 3 for west, or 255 with `x` and `y` 0 if the maze does not have it
+/// - three bits per cell row by row, packed from the lowest bit of each byte up: west open,
+///   north open and tunnel
+impl Maze {
+    /// Save the maze to a file at `path`
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        self.write_to(BufWriter::new(File::create(path)?), None)
+    }
+
+    /// Save the maze to a file at `path` along with the seed it was generated with
+    pub fn save_with_seed<P: AsRef<Path>>(&self, path: P, seed: u64) -> Result<(), Error> {
+        self.write_to(BufWriter::new(File::create(path)?), Some(seed))
+    }
+
+    /// Load a maze saved with [`Maze::save`] or [`Maze::save_with_seed`]
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::load_with_seed(path).map(|(maze, _)| maze)
+    }
+
+    /// Load a maze along with its seed, if one was saved
+    pub fn load_with_seed<P: AsRef<Path>>(path: P) -> Result<(Self, Option<u64>), Error> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    /// Write the maze and optionally its seed to `writer`
+    pub fn write_to<W: Write>(&self, mut writer: W, seed: Option<u64>) -> Result<(), Error> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        writer.write_all(&self.width.to_le_bytes())?;
+        writer.write_all(&self.height.to_le_bytes())?;
+        writer.write_all(&[if seed.is_some() { HAS_SEED } else { 0 }])?;
+        if let Some(seed) = seed {
+            writer.write_all(&seed.to_le_bytes())?;
+        }
+        writer.write_all(&self.opening_width().to_le_bytes())?;
+        for opening in [self.entrance(), self.exit()] {
+            let (x, y, side) = opening.map_or((0, 0, NO_OPENING), |opening| {
+                (opening.x, opening.y, side_byte(opening.side))
+            });
+            writer.write_all(&(x as u32).to_le_bytes())?;
+            writer.write_all(&(y as u32).to_le_bytes())?;
+            writer.write_all(&[side])?;
+        }
+
+        let len = self.len();
+        let mut packed = vec![0u8; (len * CELL_BITS).div_ceil(8)];
+        for idx in 0..len {
+            let bits = self.cell_at_index(idx).bits();
+            for bit in 0..CELL_BITS {
+                if bits & (1 << bit) != 0 {
+                    let at = idx * CELL_BITS + bit;
+                    packed[at / 8] |= 1 << (at % 8);
+                }
+            }
+        }
+        writer.write_all(&packed)?;
+        Ok(writer.flush()?)
+    }
+
+    /// Read a maze and its seed, if one was saved, from `reader`
+    pub fn read_from<R: Read>(mut reader: R) -> Result<(Self, Option<u64>), Error> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a saved maze").into());
+        }
+        let version = read_u8(&mut reader)?;
+        if version != VERSION {
+            return Err(invalid(format!("unsupported maze file version {version}")).into());
+        }
+        let width = read_u32(&mut reader)?;
+        let height = read_u32(&mut reader)?;
+        let flags = read_u8(&mut reader)?;
+        let seed = if flags & HAS_SEED != 0 {
+            let mut bytes = [0u8; 8];
+            reader.read_exact(&mut bytes)?;
+            Some(u64::from_le_bytes(bytes))
+        } else {
+            None
+        };
+        let mut bytes = [0u8; 4];
+        reader.read_exact(&mut bytes)?;
+        let opening_width = f32::from_le_bytes(bytes);
+        let mut openings = [None; 2];
+        for opening in openings.iter_mut() {
+            let x = read_u32(&mut reader)? as usize;
+            let y = read_u32(&mut reader)? as usize;
+            let side = read_u8(&mut reader)?;
+            if side != NO_OPENING {
+                let side = side_from_byte(side)?;
+                *opening = Some(Opening { x, y, side });
+            }
+        }
+
+        // Read the cells before allocating anything sized by the header, so a short file with a
+        // huge width and height fails on its missing data instead of on the allocation
+        let len = cell_count(width, height)?;
+        let size = len
+            .checked_mul(CELL_BITS)
+            .map(|bits| bits.div_ceil(8))
+            .ok_or(Error::TooLarge {
+                width: width.into(),
+                height: height.into(),
+            })?;
+        let mut packed = Vec::new();
+        reader.by_ref().take(size as u64).read_to_end(&mut packed)?;
+        if packed.len() != size {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        let mut maze = Maze::try_new(width, height)?;
+        for idx in 0..len {
+            let bits = (0..CELL_BITS).fold(0u8, |bits, bit| {
+                let at = idx * CELL_BITS + bit;
+                bits | (((packed[at / 8] >> (at % 8)) & 1) << bit)
+            });
+            *maze.cell_at_index_mut(idx) = Cell::from_bits(bits).unwrap();
+        }
+        for opening in openings.iter().flatten() {
+            if opening.x >= width as usize || opening.y >= height as usize {
+                return Err(invalid("opening is outside of the maze").into());
+            }
+        }
+        maze.set_opening_width(opening_width);
+        maze.set_openings(openings[0], openings[1]);
+        Ok((maze, seed))
+    }
+}
+
+fn side_byte(side: Direction) -> u8 {
+    match side {
+        Direction::North => 0,
+        Direction::South => 1,
+        Direction::East => 2,
+        Direction::West => 3,
+    }
+}
+
+fn side_from_byte(byte: u8) -> io::Result<Direction> {
+    Direction::ALL
+        .get(usize::from(byte))
+        .copied()
+        .ok_or_else(|| invalid(format!("invalid opening side {byte}")))
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> io::Result<u8> {
+    let mut byte = [0u8; 1];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::Algorithm;
+
+    fn weave() -> Maze {
+        Maze::builder()
+            .size(9, 7)
+            .algorithm(Algorithm::Weave)
+            .opening_width(1.5)
+            .seed(5)
+            .build()
+            .unwrap()
+    }
+
+    fn saved(maze: &Maze, seed: Option<u64>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        maze.write_to(&mut bytes, seed).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn round_trips_with_and_without_a_seed() {
+        let maze = weave();
+        assert!(maze.iter_cells().any(|cell| cell.tunnel()));
+        for seed in [None, Some(0), Some(u64::MAX)] {
+            let (read, read_seed) = Maze::read_from(saved(&maze, seed).as_slice()).unwrap();
+            assert!(read == maze);
+            assert_eq!(read_seed, seed);
+        }
+    }
+
+    #[test]
+    fn round_trips_without_openings() {
+        let mut maze = weave();
+        maze.set_openings(None, None);
+        let (read, _) = Maze::read_from(saved(&maze, None).as_slice()).unwrap();
+        assert!(read == maze);
+        assert_eq!((read.entrance(), read.exit()), (None, None));
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let mut bytes = saved(&weave(), None);
+        bytes[0] = b'X';
+        assert!(Maze::read_from(bytes.as_slice()).is_err());
+
+        let mut bytes = saved(&weave(), None);
+        bytes[4] = VERSION + 1;
+        assert!(Maze::read_from(bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn rejects_missing_cells() {
+        let bytes = saved(&weave(), Some(1));
+        for len in [bytes.len() - 1, bytes.len() / 2, 10] {
+            assert!(Maze::read_from(&bytes[..len]).is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_huge_header_without_cells() {
+        let mut bytes = saved(&Maze::new(1, 1), None);
+        bytes[5..9].copy_from_slice(&u32::MAX.to_le_bytes());
+        bytes[9..13].copy_from_slice(&(u32::MAX / 2).to_le_bytes());
+        assert!(Maze::read_from(bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn rejects_openings_outside() {
+        let mut bytes = saved(&Maze::new(3, 3), None);
+        // The x of the exit, after the header, opening width and entrance
+        let at = 4 + 1 + 8 + 1 + 4 + 9;
+        bytes[at..at + 4].copy_from_slice(&7u32.to_le_bytes());
+        assert!(Maze::read_from(bytes.as_slice()).is_err());
+    }
+}
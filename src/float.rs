@@ -0,0 +1,28 @@
+//! The floating point functions that `core` leaves out, from `std` when it is there and from
+//! `libm` otherwise
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("dadalus needs either the std or the libm feature");
+
+#[cfg(not(feature = "std"))]
+pub(crate) use libm::{ceilf as ceil, cosf as cos, roundf as round, sinf as sin};
+
+#[cfg(feature = "std")]
+pub(crate) fn ceil(x: f32) -> f32 {
+    x.ceil()
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn round(x: f32) -> f32 {
+    x.round()
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn sin(x: f32) -> f32 {
+    x.sin()
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn cos(x: f32) -> f32 {
+    x.cos()
+}
@@ -0,0 +1,113 @@
+use alloc::vec::Vec;
+
+use rand::prelude::*;
+
+use crate::disjoint_set::DisjointSet;
+use crate::generator::MazeGenerator;
+use crate::grid::{Direction, RectGrid};
+use crate::kruskal;
+use crate::maze::Maze;
+
+/// Generates mazes by repeated doubling. Starting from a single cell, the maze so far is copied
+/// into the four quadrants of a maze twice its size, and three of the four walls between the
+/// quadrants get a random opening, which keeps it perfect. Copying is all the work there is, so
+/// this is by far the fastest way to make enormous mazes, but the result is visibly
+/// self-similar.
+///
+/// The dimensions grow independently, so a rectangular maze only doubles in one direction once
+/// the other has reached its size. Mazes whose sides are not powers of two are grown to the next
+/// power of two and cropped, and the pieces that the crop cuts off are reconnected Kruskal-style.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Generator;
+
+impl MazeGenerator for Generator {
+    fn generate<R: Rng + ?Sized>(&self, width: usize, height: usize, rng: &mut R) -> Maze {
+        if width == 0 || height == 0 {
+            return Maze::new(width as u32, height as u32);
+        }
+        let (full_width, full_height) = (width.next_power_of_two(), height.next_power_of_two());
+        let mut maze = Maze::new(1, 1);
+        while (maze.width as usize) < full_width || (maze.height as usize) < full_height {
+            maze = double(&maze, full_width, full_height, rng);
+        }
+        if (full_width, full_height) == (width, height) {
+            maze
+        } else {
+            crop(&maze, width, height, rng)
+        }
+    }
+}
+
+/// Copy `maze` into each half of a maze twice as wide and twice as high, stopping at the full
+/// size in each direction, and open the walls between the copies
+fn double<R: Rng + ?Sized>(
+    maze: &Maze,
+    full_width: usize,
+    full_height: usize,
+    rng: &mut R,
+) -> Maze {
+    let (width, height) = (maze.width as usize, maze.height as usize);
+    let copies_x = if width < full_width { 2 } else { 1 };
+    let copies_y = if height < full_height { 2 } else { 1 };
+    let old = RectGrid::new(width, height);
+    let new = RectGrid::new(width * copies_x, height * copies_y);
+    let mut doubled = Maze::new(new.width as u32, new.height as u32);
+    for idx in 0..old.len() {
+        let (x, y) = old.coords(idx);
+        for copy_y in 0..copies_y {
+            for copy_x in 0..copies_x {
+                let new_idx = new.index(x + copy_x * width, y + copy_y * height);
+                *doubled.cell_at_index_mut(new_idx) = *maze.cell_at_index(idx);
+            }
+        }
+    }
+
+    // Each wall between two copies, as the places it can be opened: a cell west or north of
+    // the wall and the direction to cross it
+    let mut walls: Vec<Vec<(usize, Direction)>> = Vec::new();
+    if copies_x == 2 {
+        for copy_y in 0..copies_y {
+            let cells =
+                (0..height).map(|y| (new.index(width - 1, copy_y * height + y), Direction::East));
+            walls.push(cells.collect());
+        }
+    }
+    if copies_y == 2 {
+        for copy_x in 0..copies_x {
+            let cells =
+                (0..width).map(|x| (new.index(copy_x * width + x, height - 1), Direction::South));
+            walls.push(cells.collect());
+        }
+    }
+    if walls.len() == 4 {
+        // Opening all four would make a loop around the center
+        walls.shuffle(rng);
+        walls.pop();
+    }
+    for wall in walls {
+        let (idx, direction) = *wall.choose(rng).unwrap();
+        doubled.open_wall(idx, direction);
+    }
+    doubled
+}
+
+/// The top left `width` by `height` cells of `maze`, reconnected into a perfect maze
+fn crop<R: Rng + ?Sized>(maze: &Maze, width: usize, height: usize, rng: &mut R) -> Maze {
+    let grid = RectGrid::new(width, height);
+    let mut cropped = Maze::new(width as u32, height as u32);
+    let mut sets = DisjointSet::new(grid.len());
+    for idx in 0..grid.len() {
+        let (x, y) = grid.coords(idx);
+        let cell = maze.cell(x, y);
+        if x > 0 && cell.west_open() {
+            cropped.open_wall(idx, Direction::West);
+            sets.union(idx, idx - 1);
+        }
+        if y > 0 && cell.north_open() {
+            cropped.open_wall(idx, Direction::North);
+            sets.union(idx, idx - width);
+        }
+    }
+    kruskal::knit(&mut cropped, &mut sets, rng);
+    cropped
+}
@@ -0,0 +1,71 @@
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+use crate::maze::Maze;
+
+/// An algorithm that carves a perfect maze out of a rectangular grid. Implement this to plug a
+/// custom algorithm into anything that is generic over generators.
+///
+/// # Reproducibility
+///
+/// Every generator takes its random numbers from the `rng` it is given, so the same generator,
+/// dimensions and random numbers always carve the same maze. Seeded mazes from
+/// [`MazeGenerator::generate_seeded`] and [`MazeGenerator::seeded`] use ChaCha8, whose output is
+/// fixed by its specification, and are the same on every platform. They also stay the same
+/// across versions of dadalus: changing the maze that a seed produces is a breaking change, made
+/// only in a release that bumps the version accordingly, which is the minor version before 1.0.
+pub trait MazeGenerator {
+    fn generate<R: Rng + ?Sized>(&self, width: usize, height: usize, rng: &mut R) -> Maze;
+
+    /// Generate a maze from a seed. The same seed, dimensions and generator always produce the
+    /// same maze on every platform because the random numbers come from ChaCha8, seeded with
+    /// `ChaCha8Rng::seed_from_u64`.
+    fn generate_seeded(&self, width: usize, height: usize, seed: u64) -> Maze {
+        self.generate(width, height, &mut ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    /// This generator with its own random numbers from `seed`, so that mazes can be generated
+    /// reproducibly without choosing a random number generator
+    fn seeded(self, seed: u64) -> Seeded<Self>
+    where
+        Self: Sized,
+    {
+        Seeded {
+            generator: self,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+/// A generator together with the random numbers it carves with, made by
+/// [`MazeGenerator::seeded`]. Each maze continues from where the random numbers of the last one
+/// left off, so the first maze is the one that [`MazeGenerator::generate_seeded`] makes with the
+/// same seed, and every maze after it is different but just as reproducible.
+///
+/// ```
+/// use dadalus::{Generator, MazeGenerator};
+///
+/// let mut first = Generator::with_seed(7);
+/// let mut second = Generator::with_seed(7);
+/// assert_eq!(first.generate(12, 8).to_code(), second.generate(12, 8).to_code());
+/// assert_eq!(
+///     Generator::with_seed(7).generate(12, 8).to_code(),
+///     Generator.generate_seeded(12, 8, 7).to_code(),
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Seeded<G> {
+    generator: G,
+    rng: ChaCha8Rng,
+}
+
+impl<G: MazeGenerator> Seeded<G> {
+    /// Generate the next maze, `width` by `height` cells
+    pub fn generate(&mut self, width: usize, height: usize) -> Maze {
+        self.generator.generate(width, height, &mut self.rng)
+    }
+
+    pub fn generator(&self) -> &G {
+        &self.generator
+    }
+}
@@ -0,0 +1,130 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::grid::{Direction, RectGrid};
+use crate::maze::Maze;
+
+/// Nodes numbered `0..len()` and the edges between them, which a maze can be carved from by
+/// choosing a spanning tree of the edges. Implement this to generate mazes over any adjacency
+/// structure, like the rooms of a building plan.
+///
+/// [`Tessellation`](crate::tessellation::Tessellation) adds the shapes of the walls for drawing.
+/// [`Grid`](crate::grid::Grid) adds the walls that the carvers open.
+pub trait Graph {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Nodes that share an edge with `node`
+    fn neighbors(&self, node: usize) -> Vec<usize>;
+}
+
+/// A graph stored as the list of neighbors of each node
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AdjacencyList {
+    neighbors: Vec<Vec<usize>>,
+}
+
+impl AdjacencyList {
+    /// `len` nodes without any edges
+    pub fn new(len: usize) -> Self {
+        Self {
+            neighbors: vec![Vec::new(); len],
+        }
+    }
+
+    /// `len` nodes joined by `edges`
+    pub fn from_edges(len: usize, edges: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        let mut graph = Self::new(len);
+        for (a, b) in edges {
+            graph.add_edge(a, b);
+        }
+        graph
+    }
+
+    /// Add a node without any edges and return its index
+    pub fn add_node(&mut self) -> usize {
+        self.neighbors.push(Vec::new());
+        self.neighbors.len() - 1
+    }
+
+    /// Join two nodes. Joining nodes that are already neighbors or a node to itself does
+    /// nothing.
+    pub fn add_edge(&mut self, a: usize, b: usize) {
+        if a != b && !self.neighbors[a].contains(&b) {
+            self.neighbors[a].push(b);
+            self.neighbors[b].push(a);
+        }
+    }
+}
+
+impl Graph for AdjacencyList {
+    fn len(&self) -> usize {
+        self.neighbors.len()
+    }
+
+    fn neighbors(&self, node: usize) -> Vec<usize> {
+        self.neighbors[node].clone()
+    }
+}
+
+impl Graph for RectGrid {
+    fn len(&self) -> usize {
+        RectGrid::len(self)
+    }
+
+    fn neighbors(&self, node: usize) -> Vec<usize> {
+        Direction::iter()
+            .filter_map(|direction| self.adjacent_index(node, direction))
+            .collect()
+    }
+}
+
+/// The cells of the maze in row-major order, each a neighbor of the cells beside it whether or
+/// not the wall between them is open
+impl Graph for Maze {
+    fn len(&self) -> usize {
+        Maze::len(self)
+    }
+
+    fn neighbors(&self, node: usize) -> Vec<usize> {
+        Graph::neighbors(&self.grid(), node)
+    }
+}
+
+impl Maze {
+    /// The passage graph of the maze, with a node for each cell numbered in row-major order and
+    /// an edge wherever two cells are joined by an open wall or a tunnel. Any algorithm written
+    /// for a [`Graph`] can run on it.
+    pub fn passage_graph(&self) -> AdjacencyList {
+        let len = self.len();
+        let edges = (0..len).flat_map(|idx| {
+            self.passages(idx)
+                .into_iter()
+                .filter(move |next| *next > idx)
+                .map(move |next| (idx, next))
+        });
+        AdjacencyList::from_edges(len, edges)
+    }
+
+    /// The passage graph of the maze as a petgraph graph, for its library of graph algorithms.
+    /// Node `i` is the cell at row-major index `i`, weighted with its coordinates.
+    #[cfg(feature = "petgraph")]
+    pub fn to_petgraph(&self) -> petgraph::graph::UnGraph<(usize, usize), ()> {
+        use petgraph::graph::{NodeIndex, UnGraph};
+
+        let grid = self.grid();
+        let mut graph = UnGraph::with_capacity(grid.len(), grid.len());
+        for idx in 0..grid.len() {
+            graph.add_node(grid.coords(idx));
+        }
+        for idx in 0..grid.len() {
+            for next in self.passages(idx).into_iter().filter(|next| *next > idx) {
+                graph.add_edge(NodeIndex::new(idx), NodeIndex::new(next), ());
+            }
+        }
+        graph
+    }
+}
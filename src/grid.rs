@@ -0,0 +1,207 @@
+use alloc::vec::Vec;
+
+use crate::graph::Graph;
+use crate::maze::Maze;
+use crate::tessellation::Carving;
+
+/// A direction from a cell to an adjacent cell in a rectangular grid. North is towards row 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    North,
+    South,
+    East,
+    West,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::South,
+        Direction::East,
+        Direction::West,
+    ];
+
+    pub fn iter() -> impl Iterator<Item = Direction> {
+        Self::ALL.into_iter()
+    }
+
+    pub fn opposite(self) -> Self {
+        use Direction::*;
+        match self {
+            North => South,
+            South => North,
+            East => West,
+            West => East,
+        }
+    }
+
+    /// The direction a quarter turn counterclockwise from this one
+    pub fn left(self) -> Self {
+        use Direction::*;
+        match self {
+            North => West,
+            West => South,
+            South => East,
+            East => North,
+        }
+    }
+
+    /// The direction a quarter turn clockwise from this one
+    pub fn right(self) -> Self {
+        self.left().opposite()
+    }
+
+    /// The `(x, y)` offset of a step in this direction. `y` increases to the south.
+    pub fn delta(self) -> (isize, isize) {
+        use Direction::*;
+        match self {
+            North => (0, -1),
+            South => (0, 1),
+            East => (1, 0),
+            West => (-1, 0),
+        }
+    }
+}
+
+/// A wall between two adjacent cells, identified by their indexes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    /// The direction from `from` to `to`
+    pub direction: Direction,
+}
+
+/// The dimensions of a rectangular grid of cells, with coordinate math for cells indexed in
+/// row-major order
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RectGrid {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl RectGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height }
+    }
+
+    pub fn len(&self) -> usize {
+        self.width * self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        y * self.width + x
+    }
+
+    pub fn coords(&self, index: usize) -> (usize, usize) {
+        (index % self.width, index / self.width)
+    }
+
+    /// Coordinates of the cell next to `x, y` in `direction`, or `None` if that would be outside
+    /// of the grid
+    pub fn neighbor(&self, x: usize, y: usize, direction: Direction) -> Option<(usize, usize)> {
+        let (dx, dy) = direction.delta();
+        let x = x.checked_add_signed(dx)?;
+        let y = y.checked_add_signed(dy)?;
+        self.contains(x, y).then_some((x, y))
+    }
+
+    /// Index of the cell next to `index` in `direction`, or `None` if that would be outside of
+    /// the grid
+    pub fn adjacent_index(&self, index: usize, direction: Direction) -> Option<usize> {
+        let (x, y) = self.coords(index);
+        self.neighbor(x, y, direction)
+            .map(|(x, y)| self.index(x, y))
+    }
+
+    /// Index of the cell next to `index` in `direction`, wrapping around to the opposite edge
+    /// of the grid as if it were drawn on a torus
+    pub fn wrapping_adjacent_index(&self, index: usize, direction: Direction) -> usize {
+        let (x, y) = self.coords(index);
+        let (dx, dy) = direction.delta();
+        let x = (x as isize + dx).rem_euclid(self.width as isize) as usize;
+        let y = (y as isize + dy).rem_euclid(self.height as isize) as usize;
+        self.index(x, y)
+    }
+
+    /// Every wall between two cells of the grid, each listed once going east or south
+    pub fn edges(&self) -> Vec<Edge> {
+        let mut edges = Vec::with_capacity(2 * self.len());
+        for from in 0..self.len() {
+            for direction in [Direction::East, Direction::South] {
+                if let Some(to) = self.adjacent_index(from, direction) {
+                    edges.push(Edge {
+                        from,
+                        to,
+                        direction,
+                    });
+                }
+            }
+        }
+        edges
+    }
+}
+
+/// A [`Graph`] of cells that a maze is carved in, together with the walls between neighboring
+/// cells. Every wall starts out closed, and carving opens some of them.
+///
+/// The carvers, such as [`backtracker::carve`](crate::backtracker::carve), work on any `Grid`:
+/// a [`Maze`] for a rectangular grid, or a [`Carving`] for the cells of any other graph, like a
+/// hexagonal, polar or masked one, or an [`AdjacencyList`](crate::graph::AdjacencyList).
+///
+/// ```
+/// use dadalus::{backtracker, Maze};
+///
+/// let mut maze = Maze::new(12, 8);
+/// backtracker::carve(&mut maze, &mut rand::thread_rng());
+/// assert!(maze.stats().solution_length.is_some());
+/// ```
+pub trait Grid: Graph {
+    /// Open the wall between two neighboring cells
+    fn link(&mut self, a: usize, b: usize);
+
+    /// Whether the wall between two neighboring cells is open
+    fn is_linked(&self, a: usize, b: usize) -> bool;
+}
+
+/// The cells of the maze in row-major order. Tunnels are not walls, so they are not links.
+impl Grid for Maze {
+    fn link(&mut self, a: usize, b: usize) {
+        self.open_wall(a, direction(self, a, b));
+    }
+
+    fn is_linked(&self, a: usize, b: usize) -> bool {
+        self.is_open(a, direction(self, a, b))
+    }
+}
+
+/// The direction from the cell at `a` to its neighbor at `b`
+///
+/// # Panics
+///
+/// If the cells are not neighbors.
+fn direction(maze: &Maze, a: usize, b: usize) -> Direction {
+    let grid = maze.grid();
+    Direction::iter()
+        .find(|direction| grid.adjacent_index(a, *direction) == Some(b))
+        .unwrap_or_else(|| panic!("cells {a} and {b} are not neighbors"))
+}
+
+impl<T: Graph> Grid for Carving<T> {
+    fn link(&mut self, a: usize, b: usize) {
+        Carving::link(self, a, b);
+    }
+
+    fn is_linked(&self, a: usize, b: usize) -> bool {
+        Carving::is_linked(self, a, b)
+    }
+}
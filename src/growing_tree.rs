@@ -0,0 +1,100 @@
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use rand::prelude::*;
+
+use crate::generator::MazeGenerator;
+use crate::grid::{Direction, RectGrid};
+use crate::maze::Maze;
+
+/// How the growing tree algorithm picks the active cell to grow from on each step
+#[derive(Clone, Debug, PartialEq)]
+pub enum Selection {
+    /// The most recently added cell, which behaves like the recursive backtracker
+    Newest,
+    /// The cell that has been active longest, which makes long straight corridors from the start
+    Oldest,
+    /// Any active cell, which behaves like Prim's algorithm
+    Random,
+    /// One of the policies picked at random on each step, in proportion to its weight
+    Mix(Vec<(Selection, f64)>),
+}
+
+impl Selection {
+    /// Index into the active list of `len` cells, ordered oldest to newest
+    fn choose<R: Rng + ?Sized>(&self, len: usize, rng: &mut R) -> usize {
+        match self {
+            Selection::Newest => len - 1,
+            Selection::Oldest => 0,
+            Selection::Random => rng.gen_range(0..len),
+            Selection::Mix(policies) => {
+                let total: f64 = policies.iter().map(|(_, weight)| weight.max(0.0)).sum();
+                if total <= 0.0 {
+                    return len - 1;
+                }
+                let mut pick = rng.gen_range(0.0..total);
+                for (policy, weight) in policies.iter() {
+                    let weight = weight.max(0.0);
+                    if pick < weight {
+                        return policy.choose(len, rng);
+                    }
+                    pick -= weight;
+                }
+                len - 1
+            }
+        }
+    }
+}
+
+/// Generates mazes with the growing tree algorithm. A list of active cells starts with one
+/// random cell. Each step selects an active cell and carves into a random unvisited neighbor,
+/// which becomes active, or retires the cell if it has none. The selection policy controls the
+/// texture of the maze.
+#[derive(Clone, Debug)]
+pub struct Generator {
+    pub selection: Selection,
+}
+
+impl Generator {
+    pub fn new(selection: Selection) -> Self {
+        Self { selection }
+    }
+}
+
+impl MazeGenerator for Generator {
+    fn generate<R: Rng + ?Sized>(&self, width: usize, height: usize, rng: &mut R) -> Maze {
+        let grid = RectGrid::new(width, height);
+        let mut maze = Maze::new(width as u32, height as u32);
+        if grid.is_empty() {
+            return maze;
+        }
+
+        let mut visited = vec![false; grid.len()];
+        let start_idx = rng.gen_range(0..grid.len());
+        visited[start_idx] = true;
+        let mut active = VecDeque::from([start_idx]);
+        let mut directions = Direction::ALL;
+        while !active.is_empty() {
+            let active_idx = self.selection.choose(active.len(), rng);
+            let curr_idx = active[active_idx];
+            directions.shuffle(rng);
+            let next = directions.iter().find_map(|direction| {
+                grid.adjacent_index(curr_idx, *direction)
+                    .filter(|idx| !visited[*idx])
+                    .map(|idx| (*direction, idx))
+            });
+            match next {
+                Some((direction, next_idx)) => {
+                    maze.open_wall(curr_idx, direction);
+                    visited[next_idx] = true;
+                    active.push_back(next_idx);
+                }
+                None => {
+                    active.remove(active_idx);
+                }
+            }
+        }
+        maze
+    }
+}
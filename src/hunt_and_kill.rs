@@ -0,0 +1,88 @@
+use alloc::vec;
+
+use rand::prelude::*;
+
+use crate::generator::MazeGenerator;
+use crate::grid::{Direction, RectGrid};
+use crate::maze::Maze;
+
+/// Generates mazes with the Hunt-and-Kill algorithm. A random walk carves through unvisited
+/// cells until it gets stuck, then the grid is scanned for an unvisited cell next to the maze to
+/// continue from. It makes long corridors like the backtracker without needing a stack.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Generator;
+
+impl MazeGenerator for Generator {
+    fn generate<R: Rng + ?Sized>(&self, width: usize, height: usize, rng: &mut R) -> Maze {
+        let grid = RectGrid::new(width, height);
+        let mut maze = Maze::new(width as u32, height as u32);
+        if grid.is_empty() {
+            return maze;
+        }
+
+        let mut visited = vec![false; grid.len()];
+        let mut curr_idx = rng.gen_range(0..grid.len());
+        visited[curr_idx] = true;
+        // Rows above this one have no unvisited cells left, so hunting can start here
+        let mut hunt_row = 0;
+        let mut directions = Direction::ALL;
+        loop {
+            // Kill: walk to random unvisited neighbors until there are none
+            directions.shuffle(rng);
+            let next = directions.iter().find_map(|direction| {
+                grid.adjacent_index(curr_idx, *direction)
+                    .filter(|idx| !visited[*idx])
+                    .map(|idx| (*direction, idx))
+            });
+            if let Some((direction, next_idx)) = next {
+                maze.open_wall(curr_idx, direction);
+                visited[next_idx] = true;
+                curr_idx = next_idx;
+                continue;
+            }
+
+            // Hunt: find the first unvisited cell that borders the maze and connect it
+            let Some((idx, direction)) = hunt(&grid, &visited, &mut hunt_row, &mut directions, rng)
+            else {
+                break;
+            };
+            maze.open_wall(idx, direction);
+            visited[idx] = true;
+            curr_idx = idx;
+        }
+        maze
+    }
+}
+
+/// Scan rows from `hunt_row` for an unvisited cell with a visited neighbor, returning it and the
+/// direction of a randomly chosen visited neighbor
+fn hunt<R: Rng + ?Sized>(
+    grid: &RectGrid,
+    visited: &[bool],
+    hunt_row: &mut usize,
+    directions: &mut [Direction],
+    rng: &mut R,
+) -> Option<(usize, Direction)> {
+    let mut rows_complete = true;
+    for y in *hunt_row..grid.height {
+        for x in 0..grid.width {
+            let idx = grid.index(x, y);
+            if visited[idx] {
+                continue;
+            }
+            rows_complete = false;
+            directions.shuffle(rng);
+            let direction = directions.iter().copied().find(|direction| {
+                grid.adjacent_index(idx, *direction)
+                    .is_some_and(|adj| visited[adj])
+            });
+            if let Some(direction) = direction {
+                return Some((idx, direction));
+            }
+        }
+        if rows_complete {
+            *hunt_row = y + 1;
+        }
+    }
+    None
+}
@@ -0,0 +1,138 @@
+use tiny_skia::{Color, FillRule, Paint, PathBuilder, Pixmap, Transform};
+
+use crate::error::Error;
+use crate::maze::{pixmap, Maze};
+
+/// Sizes and colors for [`Maze::draw_isometric`]. Heights and thicknesses are in cells.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IsometricOptions {
+    /// Width in pixels of a cell on the screen, where it is a diamond half as high as it is wide
+    pub cell_size: u32,
+    pub wall_height: f32,
+    pub wall_thickness: f32,
+    /// Color of the tops of the walls. Their sides are shaded darker.
+    pub wall_color: Color,
+    pub floor_color: Color,
+    pub background: Color,
+    /// Pixels of space around the drawing on every side
+    pub margin: u32,
+}
+
+impl Default for IsometricOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 40,
+            wall_height: 0.6,
+            wall_thickness: 0.15,
+            wall_color: Color::from_rgba8(190, 180, 160, 255),
+            floor_color: Color::from_rgba8(90, 80, 70, 255),
+            background: Color::TRANSPARENT,
+            margin: 4,
+        }
+    }
+}
+
+impl Maze {
+    /// Draw the maze in isometric projection, seen from above its south-east corner, with the
+    /// walls raised into blocks on a floor and shaded as if lit from the north-west. The walls
+    /// are the same [segments](Maze::wall_segments) that [`Maze::draw`] strokes, so tunnels and
+    /// the entrance and exit show as gaps in them. Fails with [`Error::ImageSize`] if the image
+    /// would be empty or too large.
+    pub fn draw_isometric(&self, options: &IsometricOptions) -> Result<Pixmap, Error> {
+        let (unit, height) = (options.cell_size as f32 / 2.0, options.wall_height);
+        let half = options.wall_thickness / 2.0;
+        let (width, depth) = (self.width as f32, self.height as f32);
+        let project = |x: f32, y: f32, z: f32| ((x - y) * unit, ((x + y) / 2.0 - z) * unit);
+
+        // The corners of the floor and the walls around it that stick out furthest
+        let margin = options.margin as f32;
+        let left = project(-half, depth + half, 0.0).0;
+        let right = project(width + half, -half, 0.0).0;
+        let top = project(-half, -half, height).1;
+        let bottom = project(width + half, depth + half, 0.0).1;
+        let mut image = pixmap(
+            (right - left + 2.0 * margin).ceil() as u64,
+            (bottom - top + 2.0 * margin).ceil() as u64,
+        )?;
+        image.fill(options.background);
+        let transform = Transform::from_translate(margin - left, margin - top);
+
+        let mut paint = Paint::default();
+        let mut fill = |corners: [(f32, f32, f32); 4], color: Color| {
+            let mut pb = PathBuilder::new();
+            for (k, (x, y, z)) in corners.into_iter().enumerate() {
+                let (px, py) = project(x, y, z);
+                if k == 0 {
+                    pb.move_to(px, py);
+                } else {
+                    pb.line_to(px, py);
+                }
+            }
+            pb.close();
+            if let Some(path) = pb.finish() {
+                paint.set_color(color);
+                image.fill_path(&path, &paint, FillRule::Winding, transform, None);
+            }
+        };
+
+        fill(
+            [
+                (0.0, 0.0, 0.0),
+                (width, 0.0, 0.0),
+                (width, depth, 0.0),
+                (0.0, depth, 0.0),
+            ],
+            options.floor_color,
+        );
+
+        // Painter's algorithm: the pieces of wall furthest from the viewer go first. The
+        // pieces are at most a cell long, so their centers put them in a good enough order.
+        let mut blocks: Vec<[f32; 4]> = self
+            .wall_segments()
+            .map(|segment| {
+                let (x0, x1) = (
+                    segment.from.0.min(segment.to.0),
+                    segment.from.0.max(segment.to.0),
+                );
+                let (y0, y1) = (
+                    segment.from.1.min(segment.to.1),
+                    segment.from.1.max(segment.to.1),
+                );
+                [x0 - half, y0 - half, x1 + half, y1 + half]
+            })
+            .collect();
+        blocks.sort_by(|a, b| (a[0] + a[1] + a[2] + a[3]).total_cmp(&(b[0] + b[1] + b[2] + b[3])));
+
+        let (south, east) = (
+            shade(options.wall_color, 0.55),
+            shade(options.wall_color, 0.75),
+        );
+        for [x0, y0, x1, y1] in blocks {
+            let h = height;
+            fill(
+                [(x0, y1, 0.0), (x1, y1, 0.0), (x1, y1, h), (x0, y1, h)],
+                south,
+            );
+            fill(
+                [(x1, y0, 0.0), (x1, y1, 0.0), (x1, y1, h), (x1, y0, h)],
+                east,
+            );
+            fill(
+                [(x0, y0, h), (x1, y0, h), (x1, y1, h), (x0, y1, h)],
+                options.wall_color,
+            );
+        }
+        Ok(image)
+    }
+}
+
+/// `color` with its red, green and blue scaled by `light`
+fn shade(color: Color, light: f32) -> Color {
+    Color::from_rgba(
+        color.red() * light,
+        color.green() * light,
+        color.blue() * light,
+        color.alpha(),
+    )
+    .unwrap_or(color)
+}
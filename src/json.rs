@@ -0,0 +1,146 @@
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::maze::{cell_count, Cell, Maze, Opening};
+
+/// The serialized form of a [`Maze`]. `cells` holds the flags of every cell row by row: 1 if
+/// its west wall is open, 2 if its north wall is open and 4 if a tunnel passes under it.
+#[derive(Serialize, Deserialize)]
+struct MazeData {
+    width: u32,
+    height: u32,
+    opening_width: f32,
+    entrance: Option<Opening>,
+    exit: Option<Opening>,
+    cells: Vec<u8>,
+}
+
+impl Serialize for Maze {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let len = self.len();
+        MazeData {
+            width: self.width,
+            height: self.height,
+            opening_width: self.opening_width(),
+            entrance: self.entrance(),
+            exit: self.exit(),
+            cells: (0..len).map(|idx| self.cell_at_index(idx).bits()).collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Maze {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = MazeData::deserialize(deserializer)?;
+        let len = cell_count(data.width, data.height).map_err(D::Error::custom)?;
+        if data.cells.len() != len {
+            return Err(D::Error::custom(format!(
+                "expected {len} cells for a {}x{} maze, found {}",
+                data.width,
+                data.height,
+                data.cells.len()
+            )));
+        }
+        let mut maze = Maze::new(data.width, data.height);
+        for (idx, bits) in data.cells.into_iter().enumerate() {
+            *maze.cell_at_index_mut(idx) = Cell::from_bits(bits)
+                .ok_or_else(|| D::Error::custom(format!("invalid flags {bits} for cell {idx}")))?;
+        }
+        // Openings can be inside the maze, as a masked maze has them, so check only that they
+        // are on a cell as the binary format does
+        for opening in [data.entrance, data.exit].iter().flatten() {
+            if opening.x >= data.width as usize || opening.y >= data.height as usize {
+                return Err(D::Error::custom("opening is outside of the maze"));
+            }
+        }
+        maze.set_openings(data.entrance, data.exit);
+        maze.set_opening_width(data.opening_width);
+        Ok(maze)
+    }
+}
+
+/// A cell is serialized as its flags, the same number that [`Maze`] serializes for it
+impl Serialize for Cell {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.bits())
+    }
+}
+
+impl<'de> Deserialize<'de> for Cell {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bits = u8::deserialize(deserializer)?;
+        Cell::from_bits(bits).ok_or_else(|| D::Error::custom(format!("invalid cell flags {bits}")))
+    }
+}
+
+impl Maze {
+    /// The maze as a JSON object with its dimensions, the flags of every cell, and where its
+    /// entrance and exit are
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    /// Read a maze written by [`Maze::to_json`]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::Algorithm;
+    use crate::mask::Mask;
+    use crate::solve::Path;
+
+    fn maze() -> Maze {
+        Maze::builder()
+            .size(7, 5)
+            .algorithm(Algorithm::Weave)
+            .farthest_openings(true)
+            .seed(3)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn round_trips() {
+        let maze = maze();
+        assert!(Maze::from_json(&maze.to_json()).unwrap() == maze);
+
+        let solution = maze.solution().unwrap();
+        let json = serde_json::to_string(&solution).unwrap();
+        assert_eq!(serde_json::from_str::<Path>(&json).unwrap(), solution);
+    }
+
+    #[test]
+    fn rejects_the_wrong_number_of_cells() {
+        let json = maze().to_json().replace("\"width\":7", "\"width\":8");
+        assert!(Maze::from_json(&json).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_cell_flags() {
+        let json = maze().to_json().replace("\"cells\":[", "\"cells\":[8,");
+        let json = json.replace("\"width\":7,\"height\":5", "\"width\":36,\"height\":1");
+        let err = Maze::from_json(&json).err().unwrap();
+        assert!(err.to_string().contains("invalid flags 8"), "{err}");
+    }
+
+    #[test]
+    fn round_trips_masked_mazes() {
+        let mask = Mask::new(6, 6, |_, y| y >= 1);
+        let maze = Maze::builder().mask(mask).seed(3).build().unwrap();
+        assert_eq!(maze.entrance().unwrap().y, 1);
+        assert!(Maze::from_json(&maze.to_json()).unwrap() == maze);
+    }
+
+    #[test]
+    fn rejects_openings_outside_the_maze() {
+        let mut value: serde_json::Value = serde_json::from_str(&maze().to_json()).unwrap();
+        value["entrance"] = serde_json::json!({ "x": 7, "y": 2, "side": "East" });
+        let err = Maze::from_json(&value.to_string()).err().unwrap();
+        assert!(err.to_string().contains("outside of the maze"), "{err}");
+    }
+}
@@ -0,0 +1,60 @@
+use alloc::vec::Vec;
+
+use rand::prelude::*;
+
+use crate::disjoint_set::DisjointSet;
+use crate::generator::MazeGenerator;
+use crate::grid::{Grid, RectGrid};
+use crate::maze::Maze;
+
+/// Generates mazes with randomized Kruskal's algorithm. Every wall between two cells is
+/// considered once in random order and removed if the cells on either side are not yet
+/// connected, which produces lots of short dead ends.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Generator;
+
+impl MazeGenerator for Generator {
+    fn generate<R: Rng + ?Sized>(&self, width: usize, height: usize, rng: &mut R) -> Maze {
+        let grid = RectGrid::new(width, height);
+        let mut maze = Maze::new(width as u32, height as u32);
+        knit(&mut maze, &mut DisjointSet::new(grid.len()), rng);
+        maze
+    }
+}
+
+/// Join the disconnected pieces of `maze` into a perfect maze by going through the walls in
+/// random order and opening those between cells in different sets. `sets` must already group
+/// the cells that are connected by open passages, and every piece must be free of loops.
+pub(crate) fn knit<R: Rng + ?Sized>(maze: &mut Maze, sets: &mut DisjointSet, rng: &mut R) {
+    let grid = RectGrid::new(maze.width as usize, maze.height as usize);
+    let mut edges = grid.edges();
+    edges.shuffle(rng);
+    for edge in edges {
+        if sets.union(edge.from, edge.to) {
+            maze.open_wall(edge.from, edge.direction);
+        }
+    }
+}
+
+/// Carve a maze into `grid` with randomized Kruskal's algorithm, opening walls between its
+/// cells. The walls should all be closed to begin with.
+pub fn carve<G: Grid, R: Rng + ?Sized>(grid: &mut G, rng: &mut R) {
+    let len = grid.len();
+    let mut edges: Vec<(usize, usize)> = (0..len)
+        .flat_map(|cell| {
+            let neighbors = grid.neighbors(cell);
+            neighbors
+                .into_iter()
+                .filter(move |neighbor| cell < *neighbor)
+                .map(move |neighbor| (cell, neighbor))
+        })
+        .collect();
+    edges.shuffle(rng);
+
+    let mut sets = DisjointSet::new(len);
+    for (a, b) in edges {
+        if sets.union(a, b) {
+            grid.link(a, b);
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use tiny_skia::PathBuilder;
+
+/// Width of a glyph in grid units, next to its height of [`GLYPH_HEIGHT`]
+const GLYPH_WIDTH: f32 = 4.0;
+const GLYPH_HEIGHT: f32 = 6.0;
+
+/// Distance from the start of one glyph to the start of the next, in grid units
+const ADVANCE: f32 = 6.0;
+
+/// Strokes of a glyph as polylines on a grid [`GLYPH_WIDTH`] wide and [`GLYPH_HEIGHT`] high, with
+/// y pointing down. Letters are drawn in capitals, and only letters, digits and the hyphen are
+/// included; anything else is drawn as a space.
+fn glyph(c: char) -> &'static [&'static [(f32, f32)]] {
+    match c.to_ascii_uppercase() {
+        'A' => &[
+            &[(0.0, 6.0), (0.0, 0.0), (4.0, 0.0), (4.0, 6.0)],
+            &[(0.0, 3.0), (4.0, 3.0)],
+        ],
+        'B' => &[
+            &[
+                (0.0, 0.0),
+                (3.0, 0.0),
+                (4.0, 1.0),
+                (4.0, 2.0),
+                (3.0, 3.0),
+                (0.0, 3.0),
+            ],
+            &[
+                (3.0, 3.0),
+                (4.0, 4.0),
+                (4.0, 5.0),
+                (3.0, 6.0),
+                (0.0, 6.0),
+                (0.0, 0.0),
+            ],
+        ],
+        'C' => &[&[(4.0, 0.0), (0.0, 0.0), (0.0, 6.0), (4.0, 6.0)]],
+        'D' => &[&[
+            (0.0, 0.0),
+            (3.0, 0.0),
+            (4.0, 1.0),
+            (4.0, 5.0),
+            (3.0, 6.0),
+            (0.0, 6.0),
+            (0.0, 0.0),
+        ]],
+        'E' => &[
+            &[(4.0, 0.0), (0.0, 0.0), (0.0, 6.0), (4.0, 6.0)],
+            &[(0.0, 3.0), (3.0, 3.0)],
+        ],
+        'F' => &[
+            &[(4.0, 0.0), (0.0, 0.0), (0.0, 6.0)],
+            &[(0.0, 3.0), (3.0, 3.0)],
+        ],
+        'G' => &[&[
+            (4.0, 0.0),
+            (0.0, 0.0),
+            (0.0, 6.0),
+            (4.0, 6.0),
+            (4.0, 3.0),
+            (2.0, 3.0),
+        ]],
+        'H' => &[
+            &[(0.0, 0.0), (0.0, 6.0)],
+            &[(4.0, 0.0), (4.0, 6.0)],
+            &[(0.0, 3.0), (4.0, 3.0)],
+        ],
+        'I' => &[
+            &[(1.0, 0.0), (3.0, 0.0)],
+            &[(2.0, 0.0), (2.0, 6.0)],
+            &[(1.0, 6.0), (3.0, 6.0)],
+        ],
+        'J' => &[&[(4.0, 0.0), (4.0, 6.0), (0.0, 6.0), (0.0, 4.0)]],
+        'K' => &[
+            &[(0.0, 0.0), (0.0, 6.0)],
+            &[(4.0, 0.0), (0.0, 3.0), (4.0, 6.0)],
+        ],
+        'L' => &[&[(0.0, 0.0), (0.0, 6.0), (4.0, 6.0)]],
+        'M' => &[&[(0.0, 6.0), (0.0, 0.0), (2.0, 3.0), (4.0, 0.0), (4.0, 6.0)]],
+        'N' => &[&[(0.0, 6.0), (0.0, 0.0), (4.0, 6.0), (4.0, 0.0)]],
+        'O' => &[&[(0.0, 0.0), (4.0, 0.0), (4.0, 6.0), (0.0, 6.0), (0.0, 0.0)]],
+        'P' => &[&[(0.0, 6.0), (0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (0.0, 3.0)]],
+        'Q' => &[
+            &[(0.0, 0.0), (4.0, 0.0), (4.0, 6.0), (0.0, 6.0), (0.0, 0.0)],
+            &[(2.0, 4.0), (4.0, 6.0)],
+        ],
+        'R' => &[
+            &[(0.0, 6.0), (0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (0.0, 3.0)],
+            &[(1.0, 3.0), (4.0, 6.0)],
+        ],
+        'S' => &[&[
+            (4.0, 1.0),
+            (4.0, 0.0),
+            (0.0, 0.0),
+            (0.0, 3.0),
+            (4.0, 3.0),
+            (4.0, 6.0),
+            (0.0, 6.0),
+            (0.0, 5.0),
+        ]],
+        'T' => &[&[(0.0, 0.0), (4.0, 0.0)], &[(2.0, 0.0), (2.0, 6.0)]],
+        'U' => &[&[(0.0, 0.0), (0.0, 6.0), (4.0, 6.0), (4.0, 0.0)]],
+        'V' => &[&[(0.0, 0.0), (2.0, 6.0), (4.0, 0.0)]],
+        'W' => &[&[(0.0, 0.0), (1.0, 6.0), (2.0, 3.0), (3.0, 6.0), (4.0, 0.0)]],
+        'X' => &[&[(0.0, 0.0), (4.0, 6.0)], &[(4.0, 0.0), (0.0, 6.0)]],
+        'Y' => &[
+            &[(0.0, 0.0), (2.0, 3.0), (4.0, 0.0)],
+            &[(2.0, 3.0), (2.0, 6.0)],
+        ],
+        'Z' => &[&[(0.0, 0.0), (4.0, 0.0), (0.0, 6.0), (4.0, 6.0)]],
+        '0' => &[
+            &[(0.0, 0.0), (4.0, 0.0), (4.0, 6.0), (0.0, 6.0), (0.0, 0.0)],
+            &[(4.0, 0.0), (0.0, 6.0)],
+        ],
+        '1' => &[
+            &[(1.0, 1.0), (2.0, 0.0), (2.0, 6.0)],
+            &[(1.0, 6.0), (3.0, 6.0)],
+        ],
+        '2' => &[&[
+            (0.0, 0.0),
+            (4.0, 0.0),
+            (4.0, 3.0),
+            (0.0, 3.0),
+            (0.0, 6.0),
+            (4.0, 6.0),
+        ]],
+        '3' => &[
+            &[(0.0, 0.0), (4.0, 0.0), (4.0, 6.0), (0.0, 6.0)],
+            &[(0.0, 3.0), (4.0, 3.0)],
+        ],
+        '4' => &[
+            &[(0.0, 0.0), (0.0, 3.0), (4.0, 3.0)],
+            &[(4.0, 0.0), (4.0, 6.0)],
+        ],
+        '5' => &[&[
+            (4.0, 0.0),
+            (0.0, 0.0),
+            (0.0, 3.0),
+            (4.0, 3.0),
+            (4.0, 6.0),
+            (0.0, 6.0),
+        ]],
+        '6' => &[&[
+            (4.0, 0.0),
+            (0.0, 0.0),
+            (0.0, 6.0),
+            (4.0, 6.0),
+            (4.0, 3.0),
+            (0.0, 3.0),
+        ]],
+        '7' => &[&[(0.0, 0.0), (4.0, 0.0), (1.0, 6.0)]],
+        '8' => &[
+            &[(0.0, 0.0), (4.0, 0.0), (4.0, 6.0), (0.0, 6.0), (0.0, 0.0)],
+            &[(0.0, 3.0), (4.0, 3.0)],
+        ],
+        '9' => &[&[
+            (4.0, 3.0),
+            (0.0, 3.0),
+            (0.0, 0.0),
+            (4.0, 0.0),
+            (4.0, 6.0),
+            (0.0, 6.0),
+        ]],
+        '-' => &[&[(1.0, 3.0), (3.0, 3.0)]],
+        _ => &[],
+    }
+}
+
+/// Width of `text` drawn `height` pixels high
+pub(crate) fn text_width(text: &str, height: f32) -> f32 {
+    let chars = text.chars().count() as f32;
+    let scale = height / GLYPH_HEIGHT;
+    ((chars - 1.0).max(0.0) * ADVANCE + GLYPH_WIDTH) * scale
+}
+
+/// Push the strokes of `text`, `height` pixels high, centered on `x, y`. The strokes are meant
+/// to be drawn with a pen about a tenth of `height` wide.
+pub(crate) fn push_text(pb: &mut PathBuilder, text: &str, x: f32, y: f32, height: f32) {
+    let scale = height / GLYPH_HEIGHT;
+    let left = x - text_width(text, height) / 2.0;
+    let top = y - height / 2.0;
+    for (k, c) in text.chars().enumerate() {
+        let offset = left + k as f32 * ADVANCE * scale;
+        for line in glyph(c) {
+            let mut points = line
+                .iter()
+                .map(|(gx, gy)| (offset + gx * scale, top + gy * scale));
+            if let Some((px, py)) = points.next() {
+                pb.move_to(px, py);
+            }
+            for (px, py) in points {
+                pb.line_to(px, py);
+            }
+        }
+    }
+}
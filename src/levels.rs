@@ -0,0 +1,240 @@
+use alloc::collections::VecDeque;
+#[cfg(feature = "render")]
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use rand::prelude::*;
+#[cfg(feature = "render")]
+use tiny_skia::{Paint, PathBuilder, Pixmap, Rect, Stroke, Transform};
+
+#[cfg(feature = "render")]
+use crate::error::Error;
+use crate::grid::RectGrid;
+#[cfg(feature = "render")]
+use crate::label;
+use crate::maze::Maze;
+#[cfg(feature = "render")]
+use crate::maze::{merge_collinear, pixmap, Segment};
+use crate::solve::trace;
+
+/// A staircase between the cells at `x, y` on level `lower` and on the level above it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Stair {
+    pub x: usize,
+    pub y: usize,
+    pub lower: usize,
+}
+
+/// A cell on one level of a [`MultiLevel`] maze
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub level: usize,
+    pub x: usize,
+    pub y: usize,
+}
+
+/// Several 2D mazes of the same size stacked on top of each other and joined by stairs. The
+/// entrance is the [entrance](Maze::entrance) of level 0 and the exit the [exit](Maze::exit) of
+/// the last level; the other openings of the levels are closed when they are drawn.
+pub struct MultiLevel {
+    levels: Vec<Maze>,
+    stairs: Vec<Stair>,
+}
+
+impl MultiLevel {
+    /// Join `levels` with `stairs`. All levels must have the same size, and every stair must be
+    /// inside the grid and lead up from a level that has one above it.
+    pub fn new(levels: Vec<Maze>, stairs: Vec<Stair>) -> Self {
+        if let Some(first) = levels.first() {
+            assert!(
+                levels
+                    .iter()
+                    .all(|maze| (maze.width, maze.height) == (first.width, first.height)),
+                "levels must all have the same size"
+            );
+            for stair in &stairs {
+                assert!(
+                    stair.x < first.width as usize
+                        && stair.y < first.height as usize
+                        && stair.lower + 1 < levels.len(),
+                    "stair {stair:?} is outside of the maze"
+                );
+            }
+        }
+        Self { levels, stairs }
+    }
+
+    /// Join `levels` with a single stair at a random cell between each pair of neighboring
+    /// levels. If every level is a perfect maze, so is the result. Levels without any cells get
+    /// no stairs.
+    pub fn connect<R: Rng + ?Sized>(levels: Vec<Maze>, rng: &mut R) -> Self {
+        let stairs = match levels.first() {
+            Some(first) if first.width > 0 && first.height > 0 => (0..levels.len() - 1)
+                .map(|lower| Stair {
+                    x: rng.gen_range(0..first.width as usize),
+                    y: rng.gen_range(0..first.height as usize),
+                    lower,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        Self::new(levels, stairs)
+    }
+
+    pub fn levels(&self) -> &[Maze] {
+        &self.levels
+    }
+
+    pub fn stairs(&self) -> &[Stair] {
+        &self.stairs
+    }
+
+    /// The shortest route from the entrance to the exit, from cell to cell and taking the stairs
+    /// between levels, or `None` if there is no entrance or exit or the exit cannot be reached
+    pub fn solve(&self) -> Option<Vec<Position>> {
+        let first = self.levels.first()?;
+        let (entrance, exit) = (first.entrance()?, self.levels.last()?.exit()?);
+        let grid = RectGrid::new(first.width as usize, first.height as usize);
+        if grid.is_empty() {
+            return None;
+        }
+        let index = |p: Position| p.level * grid.len() + grid.index(p.x, p.y);
+        let position = |idx: usize| {
+            let (x, y) = grid.coords(idx % grid.len());
+            Position {
+                level: idx / grid.len(),
+                x,
+                y,
+            }
+        };
+
+        let start = index(Position {
+            level: 0,
+            x: entrance.x,
+            y: entrance.y,
+        });
+        let end = index(Position {
+            level: self.levels.len() - 1,
+            x: exit.x,
+            y: exit.y,
+        });
+        let mut previous = vec![usize::MAX; self.levels.len() * grid.len()];
+        previous[start] = start;
+        let mut queue = VecDeque::from([start]);
+        while let Some(idx) = queue.pop_front() {
+            if idx == end {
+                break;
+            }
+            let here = position(idx);
+            let cell = idx % grid.len();
+            let maze = &self.levels[here.level];
+            let passages = maze
+                .passages(cell)
+                .into_iter()
+                .map(|neighbor| here.level * grid.len() + neighbor);
+            let stairs = self
+                .stairs
+                .iter()
+                .filter(|s| (s.x, s.y) == (here.x, here.y))
+                .filter_map(|s| {
+                    let level = if s.lower == here.level {
+                        s.lower + 1
+                    } else if s.lower + 1 == here.level {
+                        s.lower
+                    } else {
+                        return None;
+                    };
+                    Some(index(Position { level, ..here }))
+                });
+            for next in passages.chain(stairs).collect::<Vec<_>>() {
+                if previous[next] == usize::MAX {
+                    previous[next] = idx;
+                    queue.push_back(next);
+                }
+            }
+        }
+        trace(&previous, start, end).map(|cells| cells.into_iter().map(position).collect())
+    }
+}
+
+#[cfg(feature = "render")]
+impl MultiLevel {
+    /// Draw every level as its own page, from level 0 up
+    pub fn draw_levels(&self, cell_size: u32) -> Result<Vec<Pixmap>, Error> {
+        (0..self.levels.len())
+            .map(|level| self.draw_level(level, cell_size))
+            .collect()
+    }
+
+    /// Draw the walls of one level onto a transparent image with `cell_size` pixels per cell.
+    /// Stair cells are shaded and labeled with the level they lead to, counting levels from 1 as
+    /// the pages are numbered.
+    pub fn draw_level(&self, level: usize, cell_size: u32) -> Result<Pixmap, Error> {
+        let maze = &self.levels[level];
+        let mut paint = Paint::default();
+        paint.set_color_rgba8(0, 0, 0, 200);
+        paint.anti_alias = true;
+
+        let width = u64::from(maze.width) * u64::from(cell_size);
+        let height = u64::from(maze.height) * u64::from(cell_size);
+        let mut pixmap = pixmap(width, height)?;
+
+        // Close the openings that belong to other levels
+        let [entrance, exit] = maze.opening_segments();
+        let mut segments: Vec<Segment> = maze.wall_segments().collect();
+        if level > 0 {
+            segments.extend(entrance);
+        }
+        if level + 1 < self.levels.len() {
+            segments.extend(exit);
+        }
+
+        let scale = cell_size as f32;
+        let mut pb = PathBuilder::new();
+        for segment in merge_collinear(segments) {
+            pb.move_to(segment.from.0 * scale, segment.from.1 * scale);
+            pb.line_to(segment.to.0 * scale, segment.to.1 * scale);
+        }
+        if let Some(path) = pb.finish() {
+            pixmap.stroke_path(
+                &path,
+                &paint,
+                &Stroke::default(),
+                Transform::identity(),
+                None,
+            );
+        }
+
+        let mut shade = Paint::default();
+        shade.set_color_rgba8(0, 0, 0, 40);
+        let text_height = 0.12 * scale;
+        let pen = Stroke {
+            width: (text_height / 8.0).max(1.0),
+            ..Stroke::default()
+        };
+        let mut pb = PathBuilder::new();
+        for stair in &self.stairs {
+            let to = if stair.lower == level {
+                stair.lower + 1
+            } else if stair.lower + 1 == level {
+                stair.lower
+            } else {
+                continue;
+            };
+            let (fx, fy) = (stair.x as f32 * scale, stair.y as f32 * scale);
+            if let Some(rect) = Rect::from_xywh(fx, fy, scale, scale) {
+                pixmap.fill_rect(rect, &shade, Transform::identity(), None);
+            }
+            // Shrink the label to fit inside the cell
+            let text = format!("TO LEVEL {}", to + 1);
+            let fit = 0.9 * scale / label::text_width(&text, 1.0);
+            let size = text_height.min(fit);
+            label::push_text(&mut pb, &text, fx + scale / 2.0, fy + scale / 2.0, size);
+        }
+        if let Some(path) = pb.finish() {
+            pixmap.stroke_path(&path, &paint, &pen, Transform::identity(), None);
+        }
+        Ok(pixmap)
+    }
+}
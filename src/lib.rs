@@ -0,0 +1,105 @@
+//! Maze generation and rendering.
+//!
+//! Mazes are generated on a rectangular grid by any [`MazeGenerator`]. [`Generator`] implements
+//! Wilson's algorithm and produces a uniformly random perfect maze; the other algorithms live in
+//! their own modules.
+//!
+//! ```
+//! use dadalus::MazeGenerator;
+//!
+//! let mut rng = rand::thread_rng();
+//! let maze = dadalus::Generator.generate(20, 10, &mut rng);
+//! let pixmap = maze.draw(&dadalus::maze::RenderOptions::new(25))?;
+//! assert_eq!(pixmap.width(), 20 * 25);
+//! # Ok::<(), dadalus::Error>(())
+//! ```
+//!
+//! For the same maze every time, give the generator a seed with [`Generator::with_seed`] or
+//! [`MazeGenerator::seeded`]. A seed keeps producing the same maze in later versions of dadalus.
+//!
+//! A [`Maze`] stores one [`Cell`] per grid square, and each cell records whether its west and
+//! north walls are open. The east and south walls of a cell belong to its neighbors, or to the
+//! outer border.
+//!
+//! Without the `std` feature, which is on by default, the crate is `no_std` and needs only
+//! `alloc`. It then builds just the generators and the maze itself, so that mazes can be
+//! generated on devices without an operating system, and needs the `libm` feature for the
+//! floating point functions that `core` leaves out. Drawing and the other output formats are
+//! behind the `render` feature.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod aldous_broder;
+pub mod analysis;
+pub mod backtracker;
+pub mod binary_tree;
+mod braid;
+pub mod builder;
+mod canonical;
+#[cfg(feature = "std")]
+mod code;
+#[cfg(feature = "render")]
+pub mod corpus;
+pub mod disjoint_set;
+mod display;
+pub mod distances;
+#[cfg(feature = "render")]
+mod dot;
+mod error;
+#[cfg(feature = "std")]
+mod file;
+mod float;
+pub mod fractal;
+pub mod generator;
+pub mod graph;
+pub mod grid;
+pub mod growing_tree;
+pub mod hunt_and_kill;
+#[cfg(feature = "render")]
+pub mod isometric;
+#[cfg(feature = "serde")]
+mod json;
+pub mod kruskal;
+#[cfg(feature = "render")]
+mod label;
+pub mod levels;
+pub mod mask;
+pub mod maze;
+pub mod maze3d;
+#[cfg(feature = "render")]
+pub mod mesh;
+pub mod origin_shift;
+#[cfg(feature = "render")]
+pub mod pdf;
+#[cfg(feature = "render")]
+pub mod plotter;
+#[cfg(feature = "render")]
+pub mod png;
+pub mod regional;
+#[cfg(feature = "render")]
+pub mod rgba;
+#[cfg(feature = "render")]
+pub mod schematic;
+pub mod solve;
+#[cfg(feature = "render")]
+pub mod svg;
+pub mod tessellation;
+#[cfg(feature = "render")]
+pub mod text;
+#[cfg(feature = "render")]
+pub mod tilemap;
+#[cfg(feature = "render")]
+pub mod video;
+#[cfg(feature = "std")]
+pub mod voronoi;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod weave;
+pub mod wilsons;
+
+pub use error::Error;
+pub use generator::MazeGenerator;
+pub use maze::{Cell, CellRef, Maze};
+pub use wilsons::Generator;
@@ -1,16 +1,1699 @@
-mod maze;
-mod wilsons;
+mod bench;
+mod config;
 
-use wilsons::Generator;
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use dadalus::builder::{self, Difficulty, MazeBuilder};
+use dadalus::corpus;
+use dadalus::isometric::IsometricOptions;
+use dadalus::levels::MultiLevel;
+use dadalus::mask::Mask;
+use dadalus::maze::{
+    AccessiblePrint, Legend, Marker, Opening, RenderOptions, Theme, Watermark, WatermarkContent,
+};
+use dadalus::mesh::MeshOptions;
+use dadalus::pdf::PdfOptions;
+use dadalus::plotter::Plot;
+use dadalus::regional::Region;
+use dadalus::schematic::SchematicOptions;
+use dadalus::svg::{Edge, LaserOptions, SvgOptions};
+use dadalus::tessellation::{Brick, Carving, Cylinder, Polar, Square, Tessellation, Torus};
+use dadalus::tilemap::TmxOptions;
+use dadalus::video::RawVideo;
+use dadalus::voronoi::Voronoi;
+use dadalus::{backtracker, binary_tree, growing_tree, kruskal, maze3d, wilsons};
+use dadalus::{Generator, Maze};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use tiny_skia::{Color, LineCap, LineJoin, Pixmap};
+
+#[derive(Parser, Clone, Debug)]
+#[command(
+    about = "Generate mazes and render them as images",
+    args_conflicts_with_subcommands = true,
+    // Flags on the command line come after the defaults from the configuration file
+    args_override_self = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Read defaults for any of the flags from this TOML file instead of dadalus.toml in the
+    /// current directory, as `flag-name = value` for each. Flags given on the command line
+    /// override them.
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Directory to write output files under, unless their paths are absolute. It is created if
+    /// it does not exist.
+    #[arg(long, global = true)]
+    output_dir: Option<PathBuf>,
+
+    /// Keep running after writing the maze, and write a new one with a random seed each time
+    /// Enter is pressed, or again with the same seed each time the configuration file changes.
+    /// Type a seed and press Enter to go back to that maze, or q to quit.
+    #[arg(long)]
+    watch: bool,
+
+    /// Report how long generation took on standard error, and given twice also draw each maze
+    /// there in text
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    #[command(flatten)]
+    maze: MazeArgs,
+
+    #[command(flatten)]
+    render: RenderArgs,
+
+    #[command(flatten)]
+    batch: BatchArgs,
+
+    /// Black and white PNG stencil with one pixel per cell, or a text file with one character
+    /// per cell. Black pixels and '#' are left out of the maze, while white pixels and '.' are
+    /// kept. The size of the maze comes from the mask instead of --width and --height.
+    #[arg(long)]
+    mask: Option<PathBuf>,
+
+    /// Shape the maze like this text, --height cells high. Needs --font.
+    #[arg(long, requires = "font")]
+    text: Option<String>,
+
+    /// TrueType or OpenType font file for --text
+    #[arg(long)]
+    font: Option<PathBuf>,
+
+    /// Number of layers. Mazes with more than one layer are always generated with Wilson's
+    /// algorithm, and each layer is written to its own numbered image.
+    #[arg(long, default_value_t = 1)]
+    depth: usize,
+
+    /// Number of separate 2D levels joined by stairs. Each level is generated with --algorithm
+    /// and written to its own numbered image, with the stairs labeled.
+    #[arg(long, default_value_t = 1, conflicts_with = "depth")]
+    levels: usize,
+
+    /// Write the generation of the maze as a stream of raw RGBA video frames to this file or
+    /// named pipe, for encoding with ffmpeg. Only Wilson's algorithm supports this.
+    #[arg(long)]
+    video: Option<PathBuf>,
+
+    /// Number of frames to split the generation into for --video
+    #[arg(long, default_value_t = 300)]
+    frames: usize,
+
+    /// Carve the maze out of a tessellation instead of using the grid generators. Only the
+    /// wilsons, backtracker and kruskal algorithms support this.
+    ///
+    /// Polar mazes use --height as the number of rings.
+    #[arg(long, value_enum)]
+    tessellation: Option<TessellationKind>,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+enum Command {
+    /// Generate a maze and save it to a file, to be solved, rendered or analyzed later
+    Generate {
+        #[command(flatten)]
+        maze: MazeArgs,
+
+        /// Path of the file to write, or - for standard output. The maze is written in the
+        /// binary save format or, with the serde feature and a path ending in .json, as JSON.
+        /// {seed}, {width}, {height}, {algo} and {index} in the path are filled in for each maze.
+        #[arg(short, long, default_value = "maze.maze")]
+        output: PathBuf,
+
+        #[command(flatten)]
+        batch: BatchArgs,
+    },
+    /// Print the shortest route through a saved maze, one cell per line as x,y
+    Solve {
+        /// Saved maze to solve
+        input: PathBuf,
+
+        /// Cell to start from as x,y, instead of the entrance
+        #[arg(long, value_parser = parse_cell)]
+        from: Option<(usize, usize)>,
+
+        /// Cell to end at as x,y, instead of the exit
+        #[arg(long, value_parser = parse_cell)]
+        to: Option<(usize, usize)>,
+    },
+    /// Render a saved maze to an image or another format
+    Render {
+        /// Saved maze to render
+        input: PathBuf,
+
+        #[command(flatten)]
+        render: Box<RenderArgs>,
+    },
+    /// Print statistics about the shape and texture of a saved maze
+    Analyze {
+        /// Saved maze to analyze
+        input: PathBuf,
+    },
+    /// Report generation and rendering throughput
+    Bench,
+    /// Write a fixed set of small mazes with their solutions, statistics and renders in every
+    /// output format, which are the same every time, for regression tests
+    Corpus {
+        /// Directory to write the files into, which is created if it does not exist
+        dir: PathBuf,
+    },
+}
+
+/// How to generate a maze on the rectangular grid
+#[derive(Args, Clone, Debug)]
+struct MazeArgs {
+    /// Ready-made size, algorithm, braiding and openings for a maze of this difficulty
+    #[arg(long, value_enum, conflicts_with_all = [
+        "width", "height", "algorithm", "bias", "selection", "braid", "farthest_openings",
+    ])]
+    difficulty: Option<DifficultyName>,
+
+    /// Width of the maze in cells
+    #[arg(long, default_value_t = 100)]
+    width: usize,
+
+    /// Height of the maze in cells
+    #[arg(long, default_value_t = 100)]
+    height: usize,
+
+    /// Algorithm used to generate the maze
+    #[arg(long, value_enum, default_value_t = Algorithm::Wilsons)]
+    algorithm: Algorithm,
+
+    /// Corner that passages lead towards with the binary tree algorithm
+    #[arg(long, value_enum, default_value_t = Bias::NorthWest)]
+    bias: Bias,
+
+    /// How the growing tree algorithm picks the cell to grow from
+    #[arg(long, value_enum, default_value_t = Selection::Newest)]
+    selection: Selection,
+
+    /// Width of the entrance and exit openings, in cells
+    #[arg(long)]
+    opening_width: Option<f32>,
+
+    /// Put the entrance and exit at the two border cells that are farthest apart instead of the
+    /// top left and bottom right corners
+    #[arg(long)]
+    farthest_openings: bool,
+
+    /// Fraction of dead ends to remove by opening a wall, which adds loops to the maze
+    #[arg(long, value_parser = parse_fraction)]
+    braid: Option<f64>,
+
+    /// Show a progress bar on standard error while the maze is generated. Only the wilsons
+    /// algorithm supports this.
+    #[arg(long)]
+    progress: bool,
+
+    /// Seed for the random number generator. The same seed and options always produce the same
+    /// maze. A random seed is chosen and printed if this is not given.
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Cells to leave out of the maze, for the --watermark-region of the image
+    #[arg(skip)]
+    reserved: Option<Region>,
+}
+
+/// How many mazes to generate in one invocation
+#[derive(Args, Clone, Debug)]
+struct BatchArgs {
+    /// Number of mazes to generate. Each maze gets the seed after the one before it, and with
+    /// more than one the output path is numbered, as in image-001.png, image-002.png and so on.
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    count: u64,
+
+    /// Also write the path and seed of every maze to this file, one per line
+    #[arg(long)]
+    manifest: Option<PathBuf>,
+}
+
+/// Where and how to write a maze
+#[derive(Args, Clone, Debug)]
+struct RenderArgs {
+    /// Path of the image to write, or - for standard output. Grid mazes are written as SVG or
+    /// PDF if the path ends in .svg or .pdf, as a 3D mesh if it ends in .stl or .obj, as text if
+    /// it ends in .txt, and as PNG otherwise. Paths ending in .maze get the maze itself in the
+    /// binary save format, .dot its passage graph for Graphviz, .schem a Minecraft schematic,
+    /// .csv or .tmx a tile layer for Tiled with the open sides of each cell, and with the serde
+    /// feature .json the maze as JSON. {seed}, {width}, {height}, {algo} and {index} in the path
+    /// are filled in for each maze that is generated.
+    #[arg(short, long, default_value = "image.png")]
+    output: PathBuf,
+
+    /// Format to write grid mazes in, instead of the one chosen by the extension of --output
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+
+    /// Size of each cell in pixels
+    #[arg(long, default_value_t = 25)]
+    cell_size: u32,
+
+    /// Ready-made colors, wall thickness and background for PNG output, which the flags for each
+    /// of them override
+    #[arg(long, value_enum)]
+    theme: Option<ThemeName>,
+
+    /// Color of the walls in PNG and SVG output, as #rgb, #rrggbb or #rrggbbaa
+    #[arg(long, value_parser = parse_color)]
+    wall_color: Option<Color>,
+
+    /// Color to fill PNG and SVG output with behind the walls, as #rgb, #rrggbb or #rrggbbaa.
+    /// Images are transparent without it, or with `transparent`.
+    #[arg(long, value_parser = parse_color)]
+    background: Option<Color>,
+
+    /// Thickness of the walls in pixels, or in SVG units for SVG output
+    #[arg(long)]
+    stroke_width: Option<f32>,
+
+    /// Shape of the loose ends of walls in PNG output
+    #[arg(long, value_enum)]
+    line_cap: Option<Cap>,
+
+    /// Shape of the corners where walls turn in PNG output
+    #[arg(long, value_enum)]
+    line_join: Option<Join>,
+
+    /// Space around the maze in pixels, or in SVG units for SVG output, so that the outer walls
+    /// are not cut off at the edges of the image
+    #[arg(long)]
+    margin: Option<u32>,
+
+    /// Pixels of empty space to leave above the maze in PNG output for a title
+    #[arg(long, default_value_t = 0)]
+    title_height: u32,
+
+    /// Pixels of empty space to leave below the maze in PNG output for a caption
+    #[arg(long, default_value_t = 0)]
+    caption_height: u32,
+
+    /// Size of each cell in millimeters for PDF output
+    #[arg(long, default_value_t = 5.0)]
+    cell_mm: f32,
+
+    /// Page width in millimeters for PDF output
+    #[arg(long, default_value_t = 210.0)]
+    page_width_mm: f32,
+
+    /// Page height in millimeters for PDF output
+    #[arg(long, default_value_t = 297.0)]
+    page_height_mm: f32,
+
+    /// Split PDF output across as many pages as the maze needs at --cell-mm, repeating this many
+    /// millimeters of the maze where neighboring pages meet so that they can be taped together
+    #[arg(long)]
+    tile_overlap_mm: Option<f32>,
+
+    /// Height of the walls in millimeters for mesh output. The cell size is --cell-mm.
+    #[arg(long, default_value_t = 8.0)]
+    wall_height_mm: f32,
+
+    /// Thickness of the walls in millimeters for mesh output
+    #[arg(long, default_value_t = 1.5)]
+    wall_thickness_mm: f32,
+
+    /// Thickness of the floor plate in millimeters for mesh output, or 0 for no floor
+    #[arg(long, default_value_t = 2.0)]
+    base_mm: f32,
+
+    /// Block that walls are built from in .schem output
+    #[arg(long, default_value = "minecraft:stone_bricks")]
+    block: String,
+
+    /// Height of the walls in blocks for .schem output
+    #[arg(long, default_value_t = 3)]
+    block_height: u16,
+
+    /// Block to lay a floor of under the maze in .schem output
+    #[arg(long)]
+    floor_block: Option<String>,
+
+    /// Lay out SVG output for a laser cutter, in millimeters with --cell-mm per cell: the walls
+    /// are engraved and a board around them is cut out
+    #[arg(long)]
+    laser: bool,
+
+    /// Width of the laser kerf in millimeters, which the cut outline is compensated for
+    #[arg(long, default_value_t = 0.1)]
+    kerf_mm: f32,
+
+    /// Give the laser-cut board finger joints about this many millimeters wide instead of plain
+    /// edges
+    #[arg(long, requires = "laser")]
+    finger_mm: Option<f32>,
+
+    /// Thickness of the material in millimeters, which is how far finger joints stick out
+    #[arg(long, default_value_t = 3.0)]
+    material_mm: f32,
+
+    /// Draw the shortest route from the entrance to the exit over the maze
+    #[arg(long)]
+    solution: bool,
+
+    /// Point arrows along the route drawn by --solution to show which way it goes
+    #[arg(long, requires = "solution")]
+    arrows: bool,
+
+    /// Mark the entrance cell in green in PNG output
+    #[arg(long, value_enum)]
+    start_marker: Option<MarkerShape>,
+
+    /// Mark the exit cell in orange in PNG output
+    #[arg(long, value_enum)]
+    finish_marker: Option<MarkerShape>,
+
+    /// Write this text, such as START, beside the entrance in PNG output. The margin is widened
+    /// to fit it unless --margin is given.
+    #[arg(long)]
+    start_label: Option<String>,
+
+    /// Write this text, such as FINISH, beside the exit in PNG output
+    #[arg(long)]
+    finish_label: Option<String>,
+
+    /// Rectangle of cells to draw --watermark-text or --watermark-image into in PNG output,
+    /// given as X,Y,WIDTH,HEIGHT from the top left cell. Mazes generated with it leave these
+    /// cells out, and the walls inside it are not drawn.
+    #[arg(long, value_parser = parse_region, requires = "watermark",
+        conflicts_with_all = ["isometric", "accessible"])]
+    watermark_region: Option<Region>,
+
+    /// Text to write across --watermark-region, such as the name of a puzzle book
+    #[arg(long, group = "watermark", requires = "watermark_region")]
+    watermark_text: Option<String>,
+
+    /// PNG image to draw into --watermark-region, such as a logo, scaled to fit
+    #[arg(long, group = "watermark", requires = "watermark_region")]
+    watermark_image: Option<PathBuf>,
+
+    /// Draw a box below the maze in PNG output with the seed, the --difficulty and today's date.
+    /// The caption band is made tall enough for it.
+    #[arg(long)]
+    legend: bool,
+
+    /// Preset the maze was generated with, for the --legend
+    #[arg(skip)]
+    difficulty: Option<Difficulty>,
+
+    /// Fill the cells in rainbow colors by their distance from the entrance, or from the top left
+    /// cell if there is no entrance
+    #[arg(long, conflicts_with = "solution")]
+    heatmap: bool,
+
+    /// Tint dead ends red and junctions blue
+    #[arg(long, conflicts_with_all = ["solution", "heatmap"])]
+    branching: bool,
+
+    /// PNG image to draw the maze over, such as a photo, scaled to cover the maze
+    #[arg(long, conflicts_with_all = ["solution", "heatmap", "branching", "accessible"])]
+    background_image: Option<PathBuf>,
+
+    /// Draw the maze in isometric projection with raised, shaded walls, at --cell-size pixels
+    /// across each cell
+    #[arg(long, conflicts_with_all = ["solution", "heatmap", "branching", "background_image"])]
+    isometric: bool,
+
+    /// Render with thick, high-contrast walls sized for printing instead of --cell-size, in PNG,
+    /// SVG or PDF output
+    #[arg(long, conflicts_with_all = ["laser", "isometric"])]
+    accessible: bool,
+
+    /// Resolution that --accessible PNG and SVG output will be printed at, in dots per inch
+    /// [default: 150]
+    #[arg(long, value_parser = parse_positive)]
+    dpi: Option<f32>,
+
+    /// Thinnest wall to print with --accessible, in millimeters [default: 1.5]
+    #[arg(long, value_parser = parse_positive)]
+    min_stroke_mm: Option<f32>,
+
+    /// Narrowest corridor between walls to print with --accessible, in millimeters
+    /// [default: 8]
+    #[arg(long, value_parser = parse_positive)]
+    min_corridor_mm: Option<f32>,
+
+    /// Print the maze to the terminal in braille characters instead of writing a file
+    #[arg(long)]
+    braille: bool,
+
+    /// Print a short code for sharing the maze instead of writing a file
+    #[arg(long, conflicts_with = "braille")]
+    code: bool,
+
+    /// Also write pen-plotter SVG and HPGL files next to the image
+    #[arg(long)]
+    plotter: bool,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Algorithm {
+    Wilsons,
+    Backtracker,
+    Kruskal,
+    AldousBroder,
+    /// Aldous-Broder for the first half of the cells, then Wilson's
+    AldousBroderWilsons,
+    HuntAndKill,
+    BinaryTree,
+    GrowingTree,
+    /// Copies of a smaller maze joined together, repeatedly
+    Fractal,
+    /// Origin shift from a maze of straight corridors until every cell has been the origin
+    OriginShift,
+    /// Kruskal's algorithm with passages that tunnel under each other
+    Weave,
+    /// Prim's algorithm in the center half of the grid and the backtracker around it
+    Regional,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum DifficultyName {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl From<DifficultyName> for Difficulty {
+    fn from(difficulty: DifficultyName) -> Self {
+        match difficulty {
+            DifficultyName::Easy => Difficulty::Easy,
+            DifficultyName::Medium => Difficulty::Medium,
+            DifficultyName::Hard => Difficulty::Hard,
+            DifficultyName::Expert => Difficulty::Expert,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Bias {
+    NorthWest,
+    NorthEast,
+    SouthWest,
+    SouthEast,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Selection {
+    Newest,
+    Oldest,
+    Random,
+    /// Newest half of the time and random otherwise
+    Mix,
+}
+
+impl From<Selection> for growing_tree::Selection {
+    fn from(selection: Selection) -> Self {
+        match selection {
+            Selection::Newest => growing_tree::Selection::Newest,
+            Selection::Oldest => growing_tree::Selection::Oldest,
+            Selection::Random => growing_tree::Selection::Random,
+            Selection::Mix => growing_tree::Selection::Mix(vec![
+                (growing_tree::Selection::Newest, 0.5),
+                (growing_tree::Selection::Random, 0.5),
+            ]),
+        }
+    }
+}
+
+impl From<Bias> for binary_tree::Bias {
+    fn from(bias: Bias) -> Self {
+        match bias {
+            Bias::NorthWest => binary_tree::Bias::NorthWest,
+            Bias::NorthEast => binary_tree::Bias::NorthEast,
+            Bias::SouthWest => binary_tree::Bias::SouthWest,
+            Bias::SouthEast => binary_tree::Bias::SouthEast,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Cap {
+    Butt,
+    Round,
+    /// Carried on past the end by half the stroke width
+    Square,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Join {
+    Miter,
+    Round,
+    Bevel,
+}
+
+impl From<Cap> for LineCap {
+    fn from(cap: Cap) -> Self {
+        match cap {
+            Cap::Butt => LineCap::Butt,
+            Cap::Round => LineCap::Round,
+            Cap::Square => LineCap::Square,
+        }
+    }
+}
+
+impl From<Join> for LineJoin {
+    fn from(join: Join) -> Self {
+        match join {
+            Join::Miter => LineJoin::Miter,
+            Join::Round => LineJoin::Round,
+            Join::Bevel => LineJoin::Bevel,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ThemeName {
+    /// Black walls on white paper
+    Classic,
+    /// White walls on blue graph paper
+    Blueprint,
+    /// Bright green walls on a dark background
+    Neon,
+    /// Black walls on newsprint with a halftone screen
+    Newspaper,
+}
+
+impl From<ThemeName> for Theme {
+    fn from(theme: ThemeName) -> Self {
+        match theme {
+            ThemeName::Classic => Theme::Classic,
+            ThemeName::Blueprint => Theme::Blueprint,
+            ThemeName::Neon => Theme::Neon,
+            ThemeName::Newspaper => Theme::Newspaper,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum MarkerShape {
+    Dot,
+    Flag,
+}
+
+impl MarkerShape {
+    fn marker(self, color: Color) -> Marker {
+        match self {
+            MarkerShape::Dot => Marker::Dot(color),
+            MarkerShape::Flag => Marker::Flag(color),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum TessellationKind {
+    Square,
+    Brick,
+    Polar,
+    Torus,
+    Cylinder,
+    /// Organic cells around random points, one per square of the grid
+    Voronoi,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Png,
+    Svg,
+    Pdf,
+    Stl,
+    Obj,
+    /// Passage graph for Graphviz
+    Dot,
+    /// Minecraft schematic
+    Schem,
+    /// Tile layer for Tiled with the open sides of each cell
+    Csv,
+    /// Tiled map with the same tile layer
+    Tmx,
+    /// Binary save format
+    Maze,
+    #[cfg(feature = "serde")]
+    Json,
+    /// Lines and corners drawn with text characters
+    Text,
+}
+
+impl Format {
+    /// The format for the extension of `path`, and PNG if there is none or it is not known
+    fn of(path: &Path) -> Self {
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+        match extension {
+            "svg" => Format::Svg,
+            "pdf" => Format::Pdf,
+            "stl" => Format::Stl,
+            "obj" => Format::Obj,
+            "dot" => Format::Dot,
+            "schem" => Format::Schem,
+            "csv" => Format::Csv,
+            "tmx" => Format::Tmx,
+            "maze" => Format::Maze,
+            #[cfg(feature = "serde")]
+            "json" => Format::Json,
+            "txt" => Format::Text,
+            _ => Format::Png,
+        }
+    }
+}
 
 fn main() {
-    let size = (100, 100);
+    let args: Vec<OsString> = std::env::args_os().collect();
+    let cli = parse(&args).unwrap_or_else(|err| err.exit());
+    if cli.watch {
+        watch(cli, &args);
+    } else {
+        run(cli);
+    }
+}
+
+/// Parse the command line `args` with the defaults from the configuration file
+fn parse(args: &[OsString]) -> Result<Cli, clap::Error> {
+    let args = config::apply(Cli::command(), args.to_vec())?;
+    let mut cli = Cli::try_parse_from(args)?;
+    if let Some(dir) = &cli.output_dir {
+        std::fs::create_dir_all(dir).map_err(|err| {
+            Cli::command().error(ErrorKind::Io, format!("{}: {err}", dir.display()))
+        })?;
+        let (output, manifest) = match &mut cli.command {
+            Some(Command::Generate { output, batch, .. }) => (output, &mut batch.manifest),
+            Some(Command::Render { render, .. }) => (&mut render.output, &mut cli.batch.manifest),
+            _ => (&mut cli.render.output, &mut cli.batch.manifest),
+        };
+        for path in std::iter::once(output).chain(manifest.as_mut()) {
+            if !is_stdio(path) {
+                *path = dir.join(&*path);
+            }
+        }
+    }
+    Ok(cli)
+}
+
+/// Write a maze as usual, and then again with a new random seed each time Enter is pressed or
+/// with the same seed each time the configuration file changes, until q is entered
+fn watch(mut cli: Cli, args: &[OsString]) {
+    let (send, lines) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lines() {
+            let Ok(line) = line else { break };
+            if send.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    let config = config::path(args);
+    let modified = || {
+        let path = config.as_ref()?;
+        std::fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .ok()
+    };
+    let mut last_modified = modified();
+
+    let mut seed = cli.maze.seed.unwrap_or_else(rand::random);
+    loop {
+        cli.maze.seed = Some(seed);
+        run(cli.clone());
+        eprintln!("press Enter for a new maze, type a seed to go back to one, or q to quit");
+        seed = loop {
+            match lines.recv_timeout(Duration::from_millis(250)) {
+                Ok(line) => match line.trim() {
+                    "" => break rand::random(),
+                    "q" => return,
+                    text => match text.parse() {
+                        Ok(seed) => break seed,
+                        Err(_) => eprintln!("{text:?} is not a seed"),
+                    },
+                },
+                Err(RecvTimeoutError::Disconnected) => return,
+                Err(RecvTimeoutError::Timeout) => {
+                    if modified() == last_modified {
+                        continue;
+                    }
+                    last_modified = modified();
+                    // Keep the last settings that worked if the file does not parse
+                    match parse(args) {
+                        Ok(reloaded) => {
+                            cli = reloaded;
+                            break seed;
+                        }
+                        Err(err) => err.print().unwrap(),
+                    }
+                }
+            }
+        };
+    }
+}
+
+/// Generate, solve, render or analyze mazes as `cli` asks
+fn run(mut cli: Cli) {
+    match &cli.command {
+        None => {}
+        Some(Command::Generate {
+            maze,
+            output,
+            batch,
+        }) => {
+            let seed = maze.seed.unwrap_or_else(rand::random);
+            eprintln!("seed: {seed}");
+            for (path, seed) in batch.run(output, maze, seed) {
+                save(&maze.build(seed, cli.verbose), &path, Some(seed));
+            }
+            return;
+        }
+        Some(Command::Solve { input, from, to }) => {
+            let (maze, _) = load(input);
+            let cell = |opening: Option<Opening>| opening.map(|opening| (opening.x, opening.y));
+            let from = from.or(cell(maze.entrance())).unwrap_or_else(|| {
+                fail(
+                    ErrorKind::MissingRequiredArgument,
+                    "the maze has no entrance, so --from is needed",
+                )
+            });
+            let to = to.or(cell(maze.exit())).unwrap_or_else(|| {
+                fail(
+                    ErrorKind::MissingRequiredArgument,
+                    "the maze has no exit, so --to is needed",
+                )
+            });
+            let Some(path) = maze.solve(from, to) else {
+                Cli::command()
+                    .error(
+                        ErrorKind::InvalidValue,
+                        "there is no route between the cells",
+                    )
+                    .exit()
+            };
+            for (x, y) in path.cells() {
+                println!("{x},{y}");
+            }
+            return;
+        }
+        Some(Command::Render { input, render }) => {
+            let (maze, seed) = load(input);
+            let mut metadata = vec![software()];
+            metadata.extend(seed.map(|seed| ("Seed", seed.to_string())));
+            metadata.push(("Width", maze.width.to_string()));
+            metadata.push(("Height", maze.height.to_string()));
+            write(&maze, &render.output, render, seed, &metadata);
+            return;
+        }
+        Some(Command::Analyze { input }) => {
+            let (maze, _) = load(input);
+            analyze(&maze);
+            return;
+        }
+        Some(Command::Bench) => {
+            bench::run();
+            return;
+        }
+        Some(Command::Corpus { dir }) => {
+            corpus::write(dir).or_exit(dir);
+            return;
+        }
+    }
+
+    let special = cli.depth > 1
+        || cli.video.is_some()
+        || cli.levels > 1
+        || cli.mask.is_some()
+        || cli.text.is_some()
+        || cli.tessellation.is_some();
+    if cli.batch.count > 1 && special {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "only mazes on the rectangular grid can be generated with --count",
+            )
+            .exit()
+    }
+    if let Some(region) = cli.render.watermark_region {
+        if special {
+            Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "a watermark can only be drawn on mazes on the rectangular grid",
+                )
+                .exit()
+        }
+        let (width, height) = cli.maze.size();
+        if region.x + region.width > width || region.y + region.height > height {
+            Cli::command()
+                .error(
+                    ErrorKind::InvalidValue,
+                    format!("--watermark-region does not fit in the {width}x{height} maze"),
+                )
+                .exit()
+        }
+        cli.maze.reserved = Some(region);
+    }
+    cli.render.difficulty = cli.maze.difficulty.map(Into::into);
+    let seed = cli.maze.seed.unwrap_or_else(rand::random);
+    eprintln!("seed: {seed}");
+    if special {
+        cli.render.output = expand(&cli.render.output, &cli.maze, seed, 1);
+    }
+    let render = &cli.render;
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let metadata = metadata(&cli.maze, seed);
+    let (width, height) = cli.maze.size();
+    let algorithm = cli.maze.algorithm();
+
+    if cli.depth > 1 {
+        let maze = maze3d::generate(width as u32, height as u32, cli.depth as u32, &mut rng);
+        let layers = maze.draw_layers(render.cell_size).or_exit(&render.output);
+        for (layer, pixmap) in layers.iter().enumerate() {
+            let stem = render.output.file_stem().unwrap().to_string_lossy();
+            let path = render
+                .output
+                .with_file_name(format!("{stem}-{}.png", layer + 1));
+            save_png(pixmap, path, &metadata);
+        }
+        return;
+    }
+
+    if let Some(path) = &cli.video {
+        if algorithm != builder::Algorithm::Wilsons {
+            Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "only the wilsons algorithm can be recorded with --video",
+                )
+                .exit()
+        }
+        let file = std::fs::File::create(path).or_exit(path);
+        let mut video = RawVideo::new(
+            std::io::BufWriter::new(file),
+            (width as u32).saturating_mul(render.cell_size).max(1),
+            (height as u32).saturating_mul(render.cell_size).max(1),
+        )
+        .or_exit(path);
+        eprintln!(
+            "ffmpeg -f rawvideo -pixel_format rgba -video_size {}x{} -framerate 30 -i {} out.mp4",
+            video.width(),
+            video.height(),
+            path.display()
+        );
+        let cells_per_frame = width * height / cli.frames.max(1);
+        Generator.animate(width, height, &mut rng, cells_per_frame, |maze| {
+            let frame = maze.draw(&render.options(Some(seed))).or_exit(path);
+            video.write_frame(&frame).or_exit(path);
+        });
+        video.finish().or_exit(path);
+        return;
+    }
+
+    if cli.levels > 1 {
+        // Every level gets its own seed, derived from the one that was printed
+        let levels = (0..cli.levels as u64)
+            .map(|level| cli.maze.build(seed.wrapping_add(level), cli.verbose))
+            .collect();
+        let maze = MultiLevel::connect(levels, &mut rng);
+        let levels = maze.draw_levels(render.cell_size).or_exit(&render.output);
+        for (level, pixmap) in levels.iter().enumerate() {
+            let stem = render.output.file_stem().unwrap().to_string_lossy();
+            let path = render
+                .output
+                .with_file_name(format!("{stem}-{}.png", level + 1));
+            save_png(pixmap, path, &metadata);
+        }
+        return;
+    }
+
+    if let Some(path) = &cli.mask {
+        let mask = if path.extension().is_some_and(|ext| ext == "png") {
+            let stencil = Pixmap::load_png(path).or_exit(path);
+            Mask::from_pixmap(&stencil)
+        } else {
+            let template = std::fs::read_to_string(path).or_exit(path);
+            template.parse::<Mask>().or_exit(path)
+        };
+        let pixmap = carve(mask, &algorithm, &mut rng).draw(render.cell_size);
+        save_png(&pixmap.or_exit(&render.output), &render.output, &metadata);
+        return;
+    }
+
+    if let (Some(text), Some(font)) = (&cli.text, &cli.font) {
+        let font = std::fs::read(font).or_exit(font);
+        let mask = dadalus::text::mask(text, &font, height)
+            .unwrap_or_else(|err| fail(ErrorKind::InvalidValue, err));
+        let pixmap = carve(mask, &algorithm, &mut rng).draw(render.cell_size);
+        save_png(&pixmap.or_exit(&render.output), &render.output, &metadata);
+        return;
+    }
+
+    let pixmap = match cli.tessellation {
+        None => {
+            for (path, seed) in cli.batch.run(&render.output, &cli.maze, seed) {
+                let metadata = self::metadata(&cli.maze, seed);
+                write(
+                    &cli.maze.build(seed, cli.verbose),
+                    &path,
+                    render,
+                    Some(seed),
+                    &metadata,
+                );
+            }
+            return;
+        }
+        Some(TessellationKind::Square) => {
+            let maze = carve(Square { width, height }, &algorithm, &mut rng).to_maze();
+            write(&maze, &render.output, render, Some(seed), &metadata);
+            return;
+        }
+        Some(TessellationKind::Brick) => {
+            carve(Brick { width, height }, &algorithm, &mut rng).draw(render.cell_size)
+        }
+        Some(TessellationKind::Torus) => {
+            carve(Torus { width, height }, &algorithm, &mut rng).draw(render.cell_size)
+        }
+        Some(TessellationKind::Cylinder) => {
+            carve(Cylinder { width, height }, &algorithm, &mut rng).draw(render.cell_size)
+        }
+        Some(TessellationKind::Voronoi) => {
+            let voronoi = Voronoi::new(width, height, &mut rng);
+            carve(voronoi, &algorithm, &mut rng).draw(render.cell_size)
+        }
+        Some(TessellationKind::Polar) => {
+            carve(Polar::new(height), &algorithm, &mut rng).draw(render.cell_size)
+        }
+    };
+    save_png(&pixmap.or_exit(&render.output), &render.output, &metadata);
+}
+
+/// Write `maze` to `output` in the chosen format, or print it if asked to. `seed` is saved
+/// along with the maze in the binary save format if it is known.
+fn write(
+    maze: &Maze,
+    output: &Path,
+    render: &RenderArgs,
+    seed: Option<u64>,
+    metadata: &[(&str, String)],
+) {
+    if render.plotter && is_stdio(output) {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--plotter writes files next to the output, so it needs an output path",
+            )
+            .exit()
+    }
+    if render.braille {
+        print!("{}", maze.to_braille());
+        return;
+    }
+    if render.code {
+        println!("{}", maze.to_code());
+        return;
+    }
+
+    let format = render.format.unwrap_or_else(|| Format::of(output));
+    if render.accessible && !matches!(format, Format::Png | Format::Svg | Format::Pdf) {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--accessible only applies to PNG, SVG and PDF output",
+            )
+            .exit()
+    }
+    if render.watermark_region.is_some() && format != Format::Png {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--watermark-region only applies to PNG output",
+            )
+            .exit()
+    }
+    if render.legend && format != Format::Png {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--legend only applies to PNG output",
+            )
+            .exit()
+    }
+    let bytes = match format {
+        Format::Text => maze.to_string().into_bytes(),
+        Format::Dot => maze.to_dot().into_bytes(),
+        Format::Schem => {
+            let options = SchematicOptions {
+                material: render.block.clone(),
+                wall_height: render.block_height,
+                floor: render.floor_block.clone(),
+                ..SchematicOptions::default()
+            };
+            maze.to_schematic(&options)
+        }
+        Format::Csv => maze.to_tile_csv().into_bytes(),
+        Format::Tmx => {
+            let options = TmxOptions {
+                tile_width: render.cell_size,
+                tile_height: render.cell_size,
+                ..TmxOptions::default()
+            };
+            maze.to_tmx(&options).into_bytes()
+        }
+        Format::Maze => {
+            let mut bytes = Vec::new();
+            maze.write_to(&mut bytes, seed).or_exit(output);
+            bytes
+        }
+        #[cfg(feature = "serde")]
+        Format::Json => maze.to_json().into_bytes(),
+        Format::Pdf => {
+            let options = if render.accessible {
+                PdfOptions {
+                    page_width: render.page_width_mm,
+                    page_height: render.page_height_mm,
+                    ..PdfOptions::accessible(&render.accessible_print())
+                }
+            } else {
+                PdfOptions {
+                    page_width: render.page_width_mm,
+                    page_height: render.page_height_mm,
+                    cell_size: render.cell_mm,
+                    ..PdfOptions::default()
+                }
+            };
+            match render.tile_overlap_mm {
+                Some(overlap) => maze
+                    .to_tiled_pdf(&options, overlap)
+                    .unwrap_or_else(|err| fail(ErrorKind::InvalidValue, err)),
+                None => maze.to_pdf(&options),
+            }
+        }
+        format @ (Format::Stl | Format::Obj) => {
+            let options = MeshOptions {
+                cell_size: render.cell_mm,
+                wall_height: render.wall_height_mm,
+                wall_thickness: render.wall_thickness_mm,
+                base_thickness: render.base_mm,
+            };
+            if format == Format::Obj {
+                maze.to_obj(&options).into_bytes()
+            } else {
+                maze.to_stl(&options)
+            }
+        }
+        Format::Svg if render.laser => {
+            let edge = match render.finger_mm {
+                Some(finger_width) => Edge::FingerJoints {
+                    finger_width,
+                    depth: render.material_mm,
+                },
+                None => Edge::Plain,
+            };
+            let options = LaserOptions {
+                cell_size: render.cell_mm,
+                kerf: render.kerf_mm,
+                edge,
+                ..LaserOptions::default()
+            };
+            maze.to_laser_svg(&options).into_bytes()
+        }
+        Format::Svg if render.accessible => maze
+            .to_svg(&SvgOptions::accessible(&render.accessible_print()))
+            .into_bytes(),
+        Format::Svg => {
+            let defaults = SvgOptions::default();
+            let options = SvgOptions {
+                cell_size: render.cell_size as f32,
+                stroke_width: render.stroke_width.unwrap_or(defaults.stroke_width),
+                margin: render
+                    .margin
+                    .map_or(defaults.margin, |margin| margin as f32),
+                wall_color: render.wall_color.map_or(defaults.wall_color, hex),
+                background: render.background.filter(|c| c.alpha() > 0.0).map(hex),
+            };
+            maze.to_svg(&options).into_bytes()
+        }
+        Format::Png => {
+            let pixmap = if render.accessible {
+                maze.draw_accessible(&render.accessible_print())
+            } else if render.isometric {
+                let defaults = IsometricOptions::default();
+                maze.draw_isometric(&IsometricOptions {
+                    cell_size: render.cell_size,
+                    wall_color: render.wall_color.unwrap_or(defaults.wall_color),
+                    background: render.background.unwrap_or(defaults.background),
+                    margin: render.margin.unwrap_or(defaults.margin),
+                    ..defaults
+                })
+            } else if render.branching {
+                maze.draw_branching(&render.options(seed))
+            } else if render.heatmap {
+                let root = maze
+                    .entrance()
+                    .map_or((0, 0), |opening| (opening.x, opening.y));
+                maze.draw_heatmap(&render.options(seed), root)
+            } else if render.solution {
+                maze.draw_with_solution(&render.options(seed))
+            } else if let Some(path) = &render.background_image {
+                let image = Pixmap::load_png(path).or_exit(path);
+                maze.draw_over(&image, &render.options(seed))
+            } else {
+                maze.draw(&render.options(seed))
+            };
+            encode_png(&pixmap.or_exit(output), metadata)
+        }
+    };
+    emit(output, &bytes);
+
+    if render.plotter {
+        let plot = Plot::new(maze);
+        eprintln!(
+            "{} strokes, {:.0} cells of pen-up travel",
+            plot.strokes().len(),
+            plot.pen_up_distance()
+        );
+        emit(&output.with_extension("svg"), plot.to_svg(2.0).as_bytes());
+        emit(&output.with_extension("hpgl"), plot.to_hpgl(2.0).as_bytes());
+    }
+}
+
+/// Save `maze` as JSON if the path ends in .json and the serde feature is on, and in the binary
+/// save format with `seed` otherwise
+fn save(maze: &Maze, path: &Path, seed: Option<u64>) {
+    #[cfg(feature = "serde")]
+    if Format::of(path) == Format::Json {
+        emit(path, maze.to_json().as_bytes());
+        return;
+    }
+    let mut bytes = Vec::new();
+    maze.write_to(&mut bytes, seed).or_exit(path);
+    emit(path, &bytes);
+}
+
+/// Load a maze written by `generate`, or read it from standard input if the path is `-`, and
+/// the seed it was generated with if that was saved. The format is told from the contents, so
+/// that it does not matter what the file is called.
+fn load(path: &Path) -> (Maze, Option<u64>) {
+    let bytes = if is_stdio(path) {
+        let mut bytes = Vec::new();
+        std::io::stdin().read_to_end(&mut bytes).or_exit(path);
+        bytes
+    } else {
+        std::fs::read(path).or_exit(path)
+    };
+    #[cfg(feature = "serde")]
+    if bytes.trim_ascii_start().starts_with(b"{") {
+        let json = String::from_utf8_lossy(&bytes);
+        return (Maze::from_json(&json).or_exit(path), None);
+    }
+    Maze::read_from(bytes.as_slice()).or_exit(path)
+}
+
+/// Whether `path` is `-`, which stands for standard input or output
+fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Write `bytes` to the file at `path`, or to standard output if the path is `-`
+fn emit(path: &Path, bytes: &[u8]) {
+    if is_stdio(path) {
+        std::io::stdout().lock().write_all(bytes).or_exit(path);
+    } else {
+        std::fs::write(path, bytes).or_exit(path);
+    }
+}
+
+/// Exit with `message`, reported the way clap reports bad arguments
+fn fail(kind: ErrorKind, message: impl Display) -> ! {
+    Cli::command().error(kind, message).exit()
+}
+
+trait OrExit<T> {
+    /// The value, or exit with the error and the path of the file it is about rather than
+    /// panicking with a backtrace
+    fn or_exit(self, path: &Path) -> T;
+}
+
+impl<T, E: Display> OrExit<T> for Result<T, E> {
+    fn or_exit(self, path: &Path) -> T {
+        self.unwrap_or_else(|err| fail(ErrorKind::Io, format!("{}: {err}", path.display())))
+    }
+}
+
+fn analyze(maze: &Maze) {
+    let stats = maze.stats();
+    let texture = maze.texture();
+    println!("size: {}x{}", maze.width, maze.height);
+    println!("dead ends: {}", stats.dead_ends);
+    println!("three-way junctions: {}", stats.three_way_junctions);
+    println!("four-way junctions: {}", stats.four_way_junctions);
+    println!("corridors: {}", stats.corridors);
+    println!(
+        "average corridor length: {:.2}",
+        stats.average_corridor_length
+    );
+    println!("longest corridor: {}", stats.max_corridor_length);
+    match stats.solution_length {
+        Some(length) => println!("solution length: {length}"),
+        None => println!("solution length: none"),
+    }
+    println!("river: {:.2}", texture.river);
+    println!("twistiness: {:.2}", texture.twistiness);
+    match texture.elitism {
+        Some(elitism) => println!("elitism: {elitism:.2}"),
+        None => println!("elitism: none"),
+    }
+}
+
+/// Parse the coordinates of a cell written as `x,y`
+fn parse_cell(text: &str) -> Result<(usize, usize), String> {
+    let (x, y) = text
+        .split_once(',')
+        .ok_or_else(|| format!("expected x,y but found {text:?}"))?;
+    let coordinate = |n: &str| n.trim().parse::<usize>().map_err(|err| err.to_string());
+    Ok((coordinate(x)?, coordinate(y)?))
+}
+
+/// Parse a number from 0 to 1
+fn parse_fraction(text: &str) -> Result<f64, String> {
+    let fraction: f64 = text.parse().map_err(|err| format!("{err}"))?;
+    if (0.0..=1.0).contains(&fraction) {
+        Ok(fraction)
+    } else {
+        Err(format!("expected a number from 0 to 1 but found {text:?}"))
+    }
+}
+
+/// Parse a number greater than 0
+fn parse_positive(text: &str) -> Result<f32, String> {
+    let number: f32 = text.parse().map_err(|err| format!("{err}"))?;
+    if number > 0.0 && number.is_finite() {
+        Ok(number)
+    } else {
+        Err(format!(
+            "expected a number greater than 0 but found {text:?}"
+        ))
+    }
+}
+
+/// Today's date in UTC, written as year-month-day
+fn today() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    // Count days in 400-year eras of 146097 days that start on the 1st of March, so that leap
+    // days fall at the end of each year
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let (era, day_of_era) = (days.div_euclid(146_097), days.rem_euclid(146_097));
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = (month_from_march + 2) % 12 + 1;
+    let year = era * 400 + year_of_era + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Parse a rectangle of cells written as `x,y,width,height`
+fn parse_region(text: &str) -> Result<Region, String> {
+    let numbers = text
+        .split(',')
+        .map(|part| part.trim().parse::<usize>().map_err(|err| format!("{err}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    match numbers[..] {
+        [x, y, width, height] if width > 0 && height > 0 => Ok(Region::new(x, y, width, height)),
+        _ => Err(format!(
+            "expected X,Y,WIDTH,HEIGHT with a width and height of at least 1 but found {text:?}"
+        )),
+    }
+}
+
+/// Parse a color written as `#rgb`, `#rrggbb` or `#rrggbbaa`, or the word `transparent`
+fn parse_color(text: &str) -> Result<Color, String> {
+    if text.eq_ignore_ascii_case("transparent") {
+        return Ok(Color::TRANSPARENT);
+    }
+    let digits = text
+        .strip_prefix('#')
+        .filter(|digits| digits.is_ascii())
+        .ok_or_else(|| format!("expected a color such as #ff8800 but found {text:?}"))?;
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).map_err(|err| err.to_string());
+    let [r, g, b, a] = match digits.len() {
+        3 => {
+            let mut rgb = [0; 3];
+            for (k, digit) in digits.char_indices() {
+                rgb[k] = channel(&digit.to_string())? * 17;
+            }
+            [rgb[0], rgb[1], rgb[2], 255]
+        }
+        6 | 8 => {
+            let mut rgba = [255; 4];
+            for k in 0..digits.len() / 2 {
+                rgba[k] = channel(&digits[2 * k..2 * k + 2])?;
+            }
+            rgba
+        }
+        _ => return Err(format!("expected 3, 6 or 8 hex digits but found {text:?}")),
+    };
+    Ok(Color::from_rgba8(r, g, b, a))
+}
+
+/// A color in the `#rrggbb` form, or `#rrggbbaa` if it is not opaque
+fn hex(color: Color) -> String {
+    let color = color.to_color_u8();
+    let mut text = format!(
+        "#{:02x}{:02x}{:02x}",
+        color.red(),
+        color.green(),
+        color.blue()
+    );
+    if color.alpha() < 255 {
+        text += &format!("{:02x}", color.alpha());
+    }
+    text
+}
 
-    let mut rng = rand::thread_rng();
+fn software() -> (&'static str, String) {
+    ("Software", format!("dadalus {}", env!("CARGO_PKG_VERSION")))
+}
+
+/// The `tEXt` entries written into every PNG, enough to generate the same maze again
+fn metadata(maze: &MazeArgs, seed: u64) -> Vec<(&'static str, String)> {
+    let (width, height) = maze.size();
+    vec![
+        software(),
+        ("Seed", seed.to_string()),
+        ("Algorithm", maze.algorithm().name().to_owned()),
+        ("Width", width.to_string()),
+        ("Height", height.to_string()),
+    ]
+}
+
+fn encode_png(pixmap: &Pixmap, metadata: &[(&str, String)]) -> Vec<u8> {
+    let entries: Vec<(&str, &str)> = metadata.iter().map(|(k, v)| (*k, v.as_str())).collect();
+    let png = pixmap
+        .encode_png()
+        .unwrap_or_else(|err| fail(ErrorKind::Io, err));
+    dadalus::png::with_text(&png, &entries)
+}
+
+fn save_png(pixmap: &Pixmap, path: impl AsRef<Path>, metadata: &[(&str, String)]) {
+    emit(path.as_ref(), &encode_png(pixmap, metadata));
+}
+
+/// A bar on standard error for --progress, redrawn only when it changes so that drawing it does
+/// not slow generation down
+#[derive(Default)]
+struct ProgressBar {
+    drawn: Option<usize>,
+}
+
+impl ProgressBar {
+    const WIDTH: usize = 40;
+
+    fn update(&mut self, done: usize, total: usize) {
+        let permille = (done * 1000).checked_div(total).unwrap_or(1000);
+        if self.drawn == Some(permille) {
+            return;
+        }
+        self.drawn = Some(permille);
+        let filled = permille * Self::WIDTH / 1000;
+        eprint!(
+            "\r[{}{}] {:>5.1}% ({done}/{total} cells)",
+            "#".repeat(filled),
+            "-".repeat(Self::WIDTH - filled),
+            permille as f64 / 10.0
+        );
+    }
+
+    fn finish(&self) {
+        if self.drawn.is_some() {
+            eprintln!();
+        }
+    }
+}
 
-    let generator = Generator::new(size.0, size.1);
-    let maze = generator.generate(&mut rng);
+impl RenderArgs {
+    /// The print sizes for --accessible, with the defaults for those that are not given
+    fn accessible_print(&self) -> AccessiblePrint {
+        let defaults = AccessiblePrint::default();
+        AccessiblePrint {
+            dpi: self.dpi.unwrap_or(defaults.dpi),
+            min_stroke_mm: self.min_stroke_mm.unwrap_or(defaults.min_stroke_mm),
+            min_corridor_mm: self.min_corridor_mm.unwrap_or(defaults.min_corridor_mm),
+        }
+    }
+
+    /// The watermark given with --watermark-region, with the image loaded if it is one
+    fn watermark(&self) -> Option<Watermark> {
+        let region = self.watermark_region?;
+        let content = match (&self.watermark_text, &self.watermark_image) {
+            (Some(text), _) => WatermarkContent::Text(text.clone()),
+            (None, Some(path)) => WatermarkContent::Image(Pixmap::load_png(path).or_exit(path)),
+            (None, None) => return None,
+        };
+        Some(Watermark { region, content })
+    }
+
+    /// How to draw PNG images of the maze, with `seed` in the --legend if it is known
+    fn options(&self, seed: Option<u64>) -> RenderOptions {
+        let defaults = match self.theme {
+            Some(theme) => Theme::from(theme).options(self.cell_size),
+            None => RenderOptions::new(self.cell_size),
+        };
+        let mut options = RenderOptions {
+            wall_color: self.wall_color.unwrap_or(defaults.wall_color),
+            background: self.background.unwrap_or(defaults.background),
+            stroke_width: self.stroke_width.unwrap_or(defaults.stroke_width),
+            line_cap: self.line_cap.map_or(defaults.line_cap, Into::into),
+            line_join: self.line_join.map_or(defaults.line_join, Into::into),
+            margin: self.margin.unwrap_or(defaults.margin),
+            title_height: self.title_height,
+            caption_height: self.caption_height,
+            start_marker: (self.start_marker)
+                .map(|shape| shape.marker(Color::from_rgba8(40, 160, 60, 255))),
+            finish_marker: (self.finish_marker)
+                .map(|shape| shape.marker(Color::from_rgba8(240, 130, 20, 255))),
+            start_label: self.start_label.clone(),
+            finish_label: self.finish_label.clone(),
+            solution_arrows: self.arrows,
+            watermark: self.watermark(),
+            legend: self.legend.then(|| Legend {
+                seed,
+                difficulty: self.difficulty,
+                date: Some(today()),
+            }),
+            ..defaults
+        };
+        if self.margin.is_none() {
+            options.margin = options.margin.max(options.label_room());
+        }
+        options.caption_height = options.caption_height.max(options.legend_room());
+        options
+    }
+}
+
+impl BatchArgs {
+    /// The path and seed of every maze in the batch, the first of them generated from `seed`.
+    /// Tokens in `output` are filled in for each maze, and the paths are numbered unless the
+    /// tokens already tell them apart. The manifest is written before any of the mazes, so that
+    /// it lists every seed even if generation is interrupted.
+    fn run(&self, output: &Path, maze: &MazeArgs, seed: u64) -> Vec<(PathBuf, u64)> {
+        let template = output.to_string_lossy();
+        let distinct = template.contains("{seed}") || template.contains("{index}");
+        let batch: Vec<(PathBuf, u64)> = (0..self.count)
+            .map(|k| {
+                let seed = seed.wrapping_add(k);
+                let output = expand(output, maze, seed, k + 1);
+                // Every maze goes to standard output one after another
+                if self.count == 1 || distinct || is_stdio(&output) {
+                    return (output, seed);
+                }
+                let digits = self.count.to_string().len().max(3);
+                let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+                let name = match output.extension() {
+                    Some(ext) => format!("{stem}-{:0digits$}.{}", k + 1, ext.to_string_lossy()),
+                    None => format!("{stem}-{:0digits$}", k + 1),
+                };
+                (output.with_file_name(name), seed)
+            })
+            .collect();
+        if let Some(manifest) = &self.manifest {
+            let lines: String = batch
+                .iter()
+                .map(|(path, seed)| format!("{}\t{seed}\n", path.display()))
+                .collect();
+            std::fs::write(manifest, lines).or_exit(manifest);
+        }
+        batch
+    }
+}
+
+/// Fill in the tokens in an --output path: {seed}, {width}, {height}, {algo} and {index}, the
+/// number of the maze in a batch counting from 1. {{ and }} stand for literal braces.
+fn expand(template: &Path, maze: &MazeArgs, seed: u64, index: u64) -> PathBuf {
+    let text = template.to_string_lossy();
+    if !text.contains(['{', '}']) {
+        return template.to_owned();
+    }
+    let fail = |message: String| -> ! {
+        Cli::command()
+            .error(ErrorKind::InvalidValue, format!("{text}: {message}"))
+            .exit()
+    };
+    let mut path = String::with_capacity(text.len());
+    let mut rest = text.as_ref();
+    while let Some(at) = rest.find(['{', '}']) {
+        path.push_str(&rest[..at]);
+        rest = &rest[at..];
+        for escape in ["{{", "}}"] {
+            if let Some(after) = rest.strip_prefix(escape) {
+                path.push_str(&escape[..1]);
+                rest = after;
+            }
+        }
+        if !rest.starts_with(['{', '}']) {
+            continue;
+        }
+        let Some(end) = rest.find('}').filter(|_| rest.starts_with('{')) else {
+            fail("unmatched brace; write {{ or }} for a literal one".to_owned())
+        };
+        let value = match &rest[1..end] {
+            "seed" => seed.to_string(),
+            "width" => maze.size().0.to_string(),
+            "height" => maze.size().1.to_string(),
+            "algo" => maze.algorithm().name().to_owned(),
+            "index" => index.to_string(),
+            token => fail(format!(
+                "unknown token {{{token}}}; use {{seed}}, {{width}}, {{height}}, {{algo}} or {{index}}"
+            )),
+        };
+        path.push_str(&value);
+        rest = &rest[end + 1..];
+    }
+    path.push_str(rest);
+    PathBuf::from(path)
+}
+
+impl MazeArgs {
+    /// Number of cells across and down, from the --difficulty preset if there is one
+    fn size(&self) -> (usize, usize) {
+        match self.difficulty {
+            Some(difficulty) => Difficulty::from(difficulty).size(),
+            None => (self.width, self.height),
+        }
+    }
+
+    /// The algorithm of the --difficulty preset, or the one chosen with --algorithm and its
+    /// options
+    fn algorithm(&self) -> builder::Algorithm {
+        if let Some(difficulty) = self.difficulty {
+            return Difficulty::from(difficulty).algorithm();
+        }
+        match self.algorithm {
+            Algorithm::Wilsons => builder::Algorithm::Wilsons,
+            Algorithm::Backtracker => builder::Algorithm::Backtracker,
+            Algorithm::Kruskal => builder::Algorithm::Kruskal,
+            Algorithm::AldousBroder => builder::Algorithm::AldousBroder,
+            Algorithm::AldousBroderWilsons => builder::Algorithm::AldousBroderWilsons,
+            Algorithm::HuntAndKill => builder::Algorithm::HuntAndKill,
+            Algorithm::BinaryTree => builder::Algorithm::BinaryTree(self.bias.into()),
+            Algorithm::GrowingTree => builder::Algorithm::GrowingTree(self.selection.into()),
+            Algorithm::Fractal => builder::Algorithm::Fractal,
+            Algorithm::OriginShift => builder::Algorithm::OriginShift,
+            Algorithm::Weave => builder::Algorithm::Weave,
+            Algorithm::Regional => builder::Algorithm::Regional,
+        }
+    }
+
+    /// A builder for the maze these options describe, with the --difficulty preset in place of
+    /// the size, algorithm, braiding and openings if there is one
+    fn builder(&self) -> MazeBuilder {
+        let builder = match self.difficulty {
+            Some(difficulty) => Maze::builder().difficulty(difficulty.into()),
+            None => Maze::builder()
+                .size(self.width, self.height)
+                .algorithm(self.algorithm())
+                .braid(self.braid.unwrap_or(0.0))
+                .farthest_openings(self.farthest_openings),
+        };
+        let builder = match self.opening_width {
+            Some(width) => builder.opening_width(width),
+            None => builder,
+        };
+        match self.reserved {
+            Some(region) => {
+                let (width, height) = self.size();
+                builder.mask(Mask::reserving(width, height, region))
+            }
+            None => builder,
+        }
+    }
+
+    /// Generate the maze from `seed`, reporting on it at the `verbose` level
+    fn build(&self, seed: u64, verbose: u8) -> Maze {
+        let algorithm = self.algorithm();
+        if self.progress && algorithm != builder::Algorithm::Wilsons {
+            Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "only the wilsons algorithm can report --progress",
+                )
+                .exit()
+        }
+        let start = Instant::now();
+        let builder = self.builder().seed(seed);
+        let maze = if self.progress {
+            let mut bar = ProgressBar::default();
+            let maze = builder.build_with_progress(|done, total| bar.update(done, total));
+            bar.finish();
+            maze
+        } else {
+            builder.build()
+        };
+        let maze = maze.unwrap_or_else(|err| fail(ErrorKind::InvalidValue, err));
+        if verbose >= 1 {
+            eprintln!(
+                "generated a {}x{} maze with {} in {:.1?}",
+                maze.width,
+                maze.height,
+                algorithm.name(),
+                start.elapsed()
+            );
+        }
+        if verbose >= 2 {
+            eprint!("{maze}");
+        }
+        maze
+    }
+}
 
-    let pixmap = maze.draw(25);
-    pixmap.save_png("image.png").unwrap();
+/// Carve a tessellation with one of the algorithms that are not tied to the rectangular grid
+fn carve<T: Tessellation>(
+    tessellation: T,
+    algorithm: &builder::Algorithm,
+    rng: &mut ChaCha8Rng,
+) -> Carving<T> {
+    let mut carving = Carving::new(tessellation);
+    match algorithm {
+        builder::Algorithm::Wilsons => wilsons::carve(&mut carving, rng),
+        builder::Algorithm::Backtracker => backtracker::carve(&mut carving, rng),
+        builder::Algorithm::Kruskal => kruskal::carve(&mut carving, rng),
+        other => Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                format!(
+                    "the {} algorithm cannot carve a --tessellation",
+                    other.name()
+                ),
+            )
+            .exit(),
+    }
+    carving
 }
@@ -0,0 +1,258 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+#[cfg(feature = "render")]
+use tiny_skia::Pixmap;
+
+use crate::graph::Graph;
+use crate::grid::{Direction, RectGrid};
+use crate::maze::{Maze, Opening};
+use crate::regional::Region;
+use crate::tessellation::{wall, Carving, Tessellation, Wall};
+
+/// A rectangular grid of square cells with some of the cells left out, for mazes in the shape of
+/// a heart, a logo or anything else. Cells that are left out have no walls and are never entered,
+/// so they stay blank when drawn.
+///
+/// The maze cells are numbered in row-major order, skipping the left out cells. Every separate
+/// piece of the mask is its own maze, with the entrance in the north wall of its first cell and
+/// the exit in the south wall of its last one.
+#[derive(Clone, Debug)]
+pub struct Mask {
+    grid: RectGrid,
+    /// The grid index of each maze cell
+    cells: Vec<usize>,
+    /// The maze cell at each grid index, if it is part of the maze
+    lookup: Vec<Option<usize>>,
+    /// Whether each maze cell is the first or the last cell of its piece
+    entrances: Vec<bool>,
+    exits: Vec<bool>,
+}
+
+impl Mask {
+    /// A mask of `width` by `height` grid cells that keeps the cells for which `keep(x, y)` is
+    /// true
+    pub fn new(width: usize, height: usize, keep: impl Fn(usize, usize) -> bool) -> Self {
+        let grid = RectGrid::new(width, height);
+        let mut cells = Vec::new();
+        let mut lookup = vec![None; grid.len()];
+        for (idx, cell) in lookup.iter_mut().enumerate() {
+            let (x, y) = grid.coords(idx);
+            if keep(x, y) {
+                *cell = Some(cells.len());
+                cells.push(idx);
+            }
+        }
+        let mut mask = Self {
+            grid,
+            entrances: vec![false; cells.len()],
+            exits: vec![false; cells.len()],
+            cells,
+            lookup,
+        };
+        let mut reached = vec![false; mask.cells.len()];
+        for cell in 0..mask.cells.len() {
+            if !reached[cell] {
+                let last = mask.flood(cell, &mut reached);
+                mask.entrances[cell] = true;
+                mask.exits[last] = true;
+            }
+        }
+        mask
+    }
+
+    /// Mark the piece containing `start` and return its last cell
+    fn flood(&self, start: usize, reached: &mut [bool]) -> usize {
+        reached[start] = true;
+        let mut last = start;
+        let mut stack = vec![start];
+        while let Some(cell) = stack.pop() {
+            last = last.max(cell);
+            for neighbor in self.neighbors(cell) {
+                if !reached[neighbor] {
+                    reached[neighbor] = true;
+                    stack.push(neighbor);
+                }
+            }
+        }
+        last
+    }
+
+    /// A mask of `width` by `height` grid cells that leaves out the cells of `region`, to keep
+    /// them for something else such as a [watermark](crate::maze::Watermark)
+    pub fn reserving(width: usize, height: usize, region: Region) -> Self {
+        Self::new(width, height, |x, y| !region.contains(x, y))
+    }
+
+    /// A mask with one cell per pixel of a stencil image, leaving out the cells whose pixels are
+    /// dark. Transparent pixels count as light.
+    #[cfg(feature = "render")]
+    pub fn from_pixmap(stencil: &Pixmap) -> Self {
+        let width = stencil.width() as usize;
+        Self::new(width, stencil.height() as usize, |x, y| {
+            let pixel = stencil.pixels()[y * width + x].demultiply();
+            let luma = 0.299 * f32::from(pixel.red())
+                + 0.587 * f32::from(pixel.green())
+                + 0.114 * f32::from(pixel.blue());
+            pixel.alpha() < 128 || luma >= 128.0
+        })
+    }
+
+    /// A mask drawn as text, one line per row of cells, with `.` for the cells in the maze and
+    /// `#` for the cells left out. Shorter lines are padded with left out cells.
+    pub fn from_ascii(template: &str) -> Result<Self, ParseMaskError> {
+        let rows: Vec<&[u8]> = template
+            .lines()
+            .map(|line| line.trim_end().as_bytes())
+            .collect();
+        for (y, row) in rows.iter().enumerate() {
+            if let Some(x) = row.iter().position(|c| !matches!(c, b'.' | b'#')) {
+                return Err(ParseMaskError {
+                    line: y + 1,
+                    column: x + 1,
+                });
+            }
+        }
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        Ok(Self::new(width, rows.len(), |x, y| {
+            rows[y].get(x) == Some(&b'.')
+        }))
+    }
+
+    pub fn width(&self) -> usize {
+        self.grid.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.grid.height
+    }
+
+    /// The maze cell at `x, y`, or `None` if that grid cell is left out
+    pub fn cell_at(&self, x: usize, y: usize) -> Option<usize> {
+        self.grid
+            .contains(x, y)
+            .then(|| self.lookup[self.grid.index(x, y)])
+            .flatten()
+    }
+
+    /// The grid coordinates of a maze cell
+    pub fn coords(&self, cell: usize) -> (usize, usize) {
+        self.grid.coords(self.cells[cell])
+    }
+}
+
+impl Graph for Mask {
+    fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn neighbors(&self, cell: usize) -> Vec<usize> {
+        let idx = self.cells[cell];
+        Direction::iter()
+            .filter_map(|direction| self.grid.adjacent_index(idx, direction))
+            .filter_map(|idx| self.lookup[idx])
+            .collect()
+    }
+}
+
+impl Tessellation for Mask {
+    fn walls(&self, cell: usize) -> Vec<Wall> {
+        let (x, y) = self.coords(cell);
+        let (fx, fy) = (x as f32, y as f32);
+        let neighbor = |direction| {
+            self.grid
+                .adjacent_index(self.cells[cell], direction)
+                .and_then(|idx| self.lookup[idx])
+        };
+        let mut walls = Vec::with_capacity(4);
+        if !self.entrances[cell] {
+            walls.push(wall(neighbor(Direction::North), (fx, fy), (fx + 1.0, fy)));
+        }
+        if !self.exits[cell] {
+            let south = neighbor(Direction::South);
+            walls.push(wall(south, (fx, fy + 1.0), (fx + 1.0, fy + 1.0)));
+        }
+        walls.push(wall(neighbor(Direction::West), (fx, fy), (fx, fy + 1.0)));
+        walls.push(wall(
+            neighbor(Direction::East),
+            (fx + 1.0, fy),
+            (fx + 1.0, fy + 1.0),
+        ));
+        walls
+    }
+
+    fn has_openings(&self) -> bool {
+        true
+    }
+}
+
+impl Carving<Mask> {
+    /// Convert a carved mask into a [`Maze`] the size of the whole grid, with the cells that are
+    /// left out closed on every side. The entrance is in the north wall of the first cell and the
+    /// exit in the south wall of the last one, as the mask draws them for its first and last
+    /// pieces.
+    pub fn to_maze(&self) -> Maze {
+        let mask = self.tessellation();
+        let mut maze = Maze::new(mask.width() as u32, mask.height() as u32);
+        for cell in 0..mask.len() {
+            let (x, y) = mask.coords(cell);
+            let linked = |x, y| {
+                mask.cell_at(x, y)
+                    .is_some_and(|other| self.is_linked(cell, other))
+            };
+            if x > 0 && linked(x - 1, y) {
+                maze.cell_mut(x, y).set_west_open();
+            }
+            if y > 0 && linked(x, y - 1) {
+                maze.cell_mut(x, y).set_north_open();
+            }
+        }
+        if let (Some(first), Some(last)) = (mask.cells.first(), mask.cells.last()) {
+            let (x, y) = mask.grid.coords(*first);
+            let entrance = Opening {
+                x,
+                y,
+                side: Direction::North,
+            };
+            let (x, y) = mask.grid.coords(*last);
+            let exit = Opening {
+                x,
+                y,
+                side: Direction::South,
+            };
+            maze.set_openings(Some(entrance), Some(exit));
+        }
+        maze
+    }
+}
+
+impl FromStr for Mask {
+    type Err = ParseMaskError;
+
+    fn from_str(template: &str) -> Result<Self, Self::Err> {
+        Self::from_ascii(template)
+    }
+}
+
+/// A character other than `.` or `#` in a mask template
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseMaskError {
+    /// 1-based line of the character
+    pub line: usize,
+    /// 1-based byte column of the character
+    pub column: usize,
+}
+
+impl fmt::Display for ParseMaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unexpected character at line {}, column {}: masks may only contain '.' and '#'",
+            self.line, self.column
+        )
+    }
+}
+
+impl core::error::Error for ParseMaskError {}
@@ -1,102 +1,1962 @@
-use tiny_skia::{Paint, PathBuilder, Pixmap, Stroke, Transform};
+use alloc::boxed::Box;
+#[cfg(feature = "render")]
+use alloc::format;
+#[cfg(feature = "render")]
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
 
+#[cfg(feature = "render")]
+use tiny_skia::{
+    Color, FillRule, FilterQuality, LineCap, LineJoin, Paint, PathBuilder, Pattern, Pixmap,
+    PixmapMut, Rect, SpreadMode, Stroke, Transform,
+};
+
+#[cfg(feature = "render")]
+use crate::builder::Difficulty;
+use crate::error::Error;
+use crate::float;
+use crate::grid::{Direction, RectGrid};
+#[cfg(feature = "render")]
+use crate::label;
+#[cfg(feature = "render")]
+use crate::regional::Region;
+#[cfg(feature = "render")]
+use crate::solve::Path;
+
+/// How far walls beside a tunnel stop short of the passage above it, in cells
+const TUNNEL_GAP: f32 = 0.15;
+
+/// Height of the start and finish labels, in cells
+#[cfg(feature = "render")]
+const LABEL_HEIGHT: f32 = 0.4;
+
+/// Space between the border of the maze and the start and finish labels, in cells
+#[cfg(feature = "render")]
+const LABEL_GAP: f32 = 0.25;
+
+/// Height of each line of the legend, in cells
+#[cfg(feature = "render")]
+const LEGEND_HEIGHT: f32 = 0.5;
+
+/// Space left between a watermark and the walls around its region, in cells
+#[cfg(feature = "render")]
+const WATERMARK_PADDING: f32 = 0.25;
+
+/// A maze on a rectangular grid. Each cell owns its north and west walls, so
+/// the wall between two cells is stored on the cell to the south or east of it.
 pub struct Maze {
     // TODO make this bit-packed booleans that represent the walls
     cells: Box<[Cell]>,
     pub width: u32,
     pub height: u32,
+    opening_width: f32,
+    entrance: Option<Opening>,
+    exit: Option<Opening>,
+}
+
+/// The number of cells in a `width` by `height` maze, or [`Error::TooLarge`] if that does not fit
+/// in memory
+pub(crate) fn cell_count(width: u32, height: u32) -> Result<usize, Error> {
+    width
+        .checked_mul(height)
+        .and_then(|len| usize::try_from(len).ok())
+        .ok_or(Error::TooLarge {
+            width: width.into(),
+            height: height.into(),
+        })
 }
 
 impl Maze {
+    /// A maze with every wall closed
+    ///
+    /// # Panics
+    ///
+    /// If the maze would have more cells than can be stored. [`Maze::try_new`] returns an error
+    /// instead.
     pub fn new(width: u32, height: u32) -> Self {
-        let len = width * height;
-        let cells = vec![Cell::new(false, false); usize::try_from(len).unwrap()].into_boxed_slice();
-        Self {
+        match Self::try_new(width, height) {
+            Ok(maze) => maze,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// A maze with every wall closed, or [`Error::TooLarge`] if it would have more cells than
+    /// can be stored
+    pub fn try_new(width: u32, height: u32) -> Result<Self, Error> {
+        let len = cell_count(width, height)?;
+        let cells = vec![Cell::new(false, false); len].into_boxed_slice();
+        Ok(Self {
             cells,
             width,
             height,
-        }
+            opening_width: 1.0,
+            entrance: Some(Opening {
+                x: 0,
+                y: 0,
+                side: Direction::North,
+            }),
+            exit: Some(Opening {
+                x: width.saturating_sub(1) as usize,
+                y: height.saturating_sub(1) as usize,
+                side: Direction::South,
+            }),
+        })
+    }
+
+    /// The number of cells in the maze
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Whether the maze has no cells, because it is 0 cells wide or high
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The cell at a row-major `index`
+    pub fn cell_at_index(&self, index: usize) -> &Cell {
+        &self.cells[index]
+    }
+
+    /// The cell at a row-major `index`
+    pub fn cell_at_index_mut(&mut self, index: usize) -> &mut Cell {
+        &mut self.cells[index]
+    }
+
+    /// The cell at `x, y`
+    ///
+    /// # Panics
+    ///
+    /// If the cell is outside of the maze.
+    pub fn cell(&self, x: usize, y: usize) -> &Cell {
+        &self.cells[self.expect_index(x, y)]
     }
 
-    pub fn cell_mut(&mut self, index: usize) -> &mut Cell {
+    /// The cell at `x, y`, to open or close its west and north walls
+    ///
+    /// # Panics
+    ///
+    /// If the cell is outside of the maze.
+    pub fn cell_mut(&mut self, x: usize, y: usize) -> &mut Cell {
+        let index = self.expect_index(x, y);
         &mut self.cells[index]
     }
 
-    pub fn draw(&self, cell_size: u32) -> Pixmap {
+    /// The cell at `x, y`, or `None` if that is outside of the maze
+    pub fn get_cell(&self, x: usize, y: usize) -> Option<&Cell> {
+        let index = self.index_of(x, y)?;
+        Some(&self.cells[index])
+    }
+
+    /// The cell at `x, y`, or `None` if that is outside of the maze
+    pub fn get_cell_mut(&mut self, x: usize, y: usize) -> Option<&mut Cell> {
+        let index = self.index_of(x, y)?;
+        Some(&mut self.cells[index])
+    }
+
+    /// The cell at `x, y` along with its coordinates, which can tell about all four of its walls
+    /// rather than only the two it owns, or `None` if that is outside of the maze
+    pub fn cell_ref(&self, x: usize, y: usize) -> Option<CellRef<'_>> {
+        self.index_of(x, y)?;
+        Some(CellRef { maze: self, x, y })
+    }
+
+    /// Every cell with its coordinates, row by row from the north west corner
+    pub fn iter_cells(&self) -> impl Iterator<Item = CellRef<'_>> {
+        let width = self.width as usize;
+        (0..self.len()).map(move |index| CellRef {
+            maze: self,
+            x: index % width,
+            y: index / width,
+        })
+    }
+
+    /// The cells of each row, from north to south
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[Cell]> {
+        let width = self.width as usize;
+        (0..self.height as usize).map(move |y| &self.cells[y * width..(y + 1) * width])
+    }
+
+    /// The two cells on either side of every open wall inside the maze, each pair once with the
+    /// north or west cell first. Tunnels and the entrance and exit are not included.
+    pub fn iter_passages(&self) -> impl Iterator<Item = ((usize, usize), (usize, usize))> + '_ {
+        self.iter_cells().flat_map(|cell| {
+            let (x, y) = (cell.x, cell.y);
+            let west = (x > 0 && cell.west_open()).then(|| ((x - 1, y), (x, y)));
+            let north = (y > 0 && cell.north_open()).then(|| ((x, y - 1), (x, y)));
+            west.into_iter().chain(north)
+        })
+    }
+
+    /// The cells next to the one at `x, y` in each direction that stays inside the maze, and
+    /// whether the wall to each of them is open
+    ///
+    /// # Panics
+    ///
+    /// If the cell is outside of the maze.
+    pub fn neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = Neighbor> + '_ {
+        let index = self.expect_index(x, y);
+        let grid = self.grid();
+        Direction::iter().filter_map(move |direction| {
+            let (x, y) = grid.coords(grid.adjacent_index(index, direction)?);
+            let open = self.is_open(index, direction);
+            Some(Neighbor {
+                x,
+                y,
+                direction,
+                open,
+            })
+        })
+    }
+
+    /// The cells that can be reached in one move from the one at `x, y`: its neighbors through
+    /// open walls, and the cell on the far side of any tunnel that starts next to it
+    ///
+    /// # Panics
+    ///
+    /// If the cell is outside of the maze.
+    pub fn open_neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let index = self.expect_index(x, y);
+        let grid = self.grid();
+        Direction::iter()
+            .filter_map(move |direction| self.step(index, direction))
+            .map(move |neighbor| grid.coords(neighbor))
+    }
+
+    /// The open sides of the cell at `x, y` as a number from 0 to 15, adding 1 for north, 2 for
+    /// east, 4 for south and 8 for west. Entrance and exit openings count as open; tunnels do
+    /// not. Skinning a maze with a tileset means drawing a tile for each of the sixteen masks.
+    ///
+    /// # Panics
+    ///
+    /// If the cell is outside of the maze.
+    pub fn tile_mask(&self, x: usize, y: usize) -> u8 {
+        assert!(
+            x < self.width as usize && y < self.height as usize,
+            "cell {x}, {y} is outside of the maze"
+        );
+        [
+            !self.horizontal_wall(x, y),
+            !self.vertical_wall(x + 1, y),
+            !self.horizontal_wall(x, y + 1),
+            !self.vertical_wall(x, y),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(bit, open)| (*open as u8) << bit)
+        .sum()
+    }
+
+    /// Open the wall between the cells at `a` and `b`, whichever of them owns it
+    pub fn open_between(
+        &mut self,
+        a: (usize, usize),
+        b: (usize, usize),
+    ) -> Result<(), NotAdjacent> {
+        let (index, direction) = self.between(a, b)?;
+        self.open_wall(index, direction);
+        Ok(())
+    }
+
+    /// Close the wall between the cells at `a` and `b`, whichever of them owns it
+    pub fn close_between(
+        &mut self,
+        a: (usize, usize),
+        b: (usize, usize),
+    ) -> Result<(), NotAdjacent> {
+        let (index, direction) = self.between(a, b)?;
+        self.close_wall(index, direction);
+        Ok(())
+    }
+
+    /// The index of the cell at `a` and the direction from it to `b`, if both are in the maze
+    /// and next to each other
+    fn between(
+        &self,
+        a: (usize, usize),
+        b: (usize, usize),
+    ) -> Result<(usize, Direction), NotAdjacent> {
+        let error = NotAdjacent { a, b };
+        let index = self.index_of(a.0, a.1).ok_or(error)?;
+        self.index_of(b.0, b.1).ok_or(error)?;
+        let direction = match (b.0 as isize - a.0 as isize, b.1 as isize - a.1 as isize) {
+            (0, -1) => Direction::North,
+            (0, 1) => Direction::South,
+            (1, 0) => Direction::East,
+            (-1, 0) => Direction::West,
+            _ => return Err(error),
+        };
+        Ok((index, direction))
+    }
+
+    fn expect_index(&self, x: usize, y: usize) -> usize {
+        self.index_of(x, y)
+            .unwrap_or_else(|| panic!("cell {x}, {y} is outside of the maze"))
+    }
+
+    /// The row-major index of the cell at `x, y`, or `None` if that is outside of the maze
+    fn index_of(&self, x: usize, y: usize) -> Option<usize> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        (x < width && y < height).then(|| y * width + x)
+    }
+
+    /// Open the wall between the cell at `index` and its neighbor in `direction`, updating
+    /// whichever of the two cells owns that wall. The neighbor must exist.
+    pub(crate) fn open_wall(&mut self, index: usize, direction: Direction) {
+        let width = usize::try_from(self.width).unwrap();
+        match direction {
+            Direction::North => self.cells[index].set_north_open(),
+            Direction::West => self.cells[index].set_west_open(),
+            Direction::South => self.cells[index + width].set_north_open(),
+            Direction::East => self.cells[index + 1].set_west_open(),
+        }
+    }
+
+    /// Close the wall between the cell at `index` and its neighbor in `direction`. The neighbor
+    /// must exist.
+    pub(crate) fn close_wall(&mut self, index: usize, direction: Direction) {
+        let width = usize::try_from(self.width).unwrap();
+        match direction {
+            Direction::North => self.cells[index].set_north_closed(),
+            Direction::West => self.cells[index].set_west_closed(),
+            Direction::South => self.cells[index + width].set_north_closed(),
+            Direction::East => self.cells[index + 1].set_west_closed(),
+        }
+    }
+
+    /// Whether the wall between the cell at `index` and its neighbor in `direction` is open. The
+    /// neighbor must exist.
+    pub(crate) fn is_open(&self, index: usize, direction: Direction) -> bool {
+        let width = usize::try_from(self.width).unwrap();
+        match direction {
+            Direction::North => self.cells[index].north_open(),
+            Direction::West => self.cells[index].west_open(),
+            Direction::South => self.cells[index + width].north_open(),
+            Direction::East => self.cells[index + 1].west_open(),
+        }
+    }
+
+    pub(crate) fn grid(&self) -> RectGrid {
+        RectGrid::new(self.width as usize, self.height as usize)
+    }
+
+    /// Indices of the cells that can be reached in one move from the cell at `index`: its
+    /// neighbors through open walls, and the cell on the far side of any tunnel that starts next
+    /// to it
+    pub(crate) fn passages(&self, index: usize) -> Vec<usize> {
+        Direction::iter()
+            .filter_map(|direction| self.step(index, direction))
+            .collect()
+    }
+
+    /// The cell reached by moving from `index` in `direction`, either through an open wall or
+    /// under the neighbor through its tunnel, or `None` if the way is blocked
+    pub(crate) fn step(&self, index: usize, direction: Direction) -> Option<usize> {
+        let grid = self.grid();
+        let neighbor = grid.adjacent_index(index, direction)?;
+        if self.is_open(index, direction) {
+            return Some(neighbor);
+        }
+        let (x, y) = grid.coords(neighbor);
+        let east_west = matches!(direction, Direction::East | Direction::West);
+        if self.tunnel_at(x as isize, y as isize) == Some(east_west) {
+            grid.adjacent_index(neighbor, direction)
+        } else {
+            None
+        }
+    }
+
+    /// The direction of the tunnel under the cell at `x, y`: `Some(true)` if it runs east to
+    /// west, `Some(false)` if it runs north to south, and `None` if there is no tunnel or the
+    /// coordinates are outside of the maze
+    fn tunnel_at(&self, x: isize, y: isize) -> Option<bool> {
+        let (x, y) = (u32::try_from(x).ok()?, u32::try_from(y).ok()?);
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let cell = &self.cells[usize::try_from(y * self.width + x).unwrap()];
+        // The passage over the cell is whichever of its own walls is open
+        cell.tunnel().then(|| cell.north_open())
+    }
+
+    /// Width of the entrance and exit openings, in cells
+    pub fn opening_width(&self) -> f32 {
+        self.opening_width
+    }
+
+    /// Set the width of the entrance and exit openings, in cells. Widths below one leave a gap
+    /// centered on the border wall of the entrance (or exit) cell; wider openings span several
+    /// neighboring border cells. The width is clamped to the width of the maze.
+    pub fn set_opening_width(&mut self, width: f32) {
+        self.opening_width = width.clamp(f32::EPSILON, self.width.max(1) as f32);
+    }
+
+    /// Where the maze is entered, in the north wall of the top left cell unless it has been
+    /// moved, or `None` if the maze has no entrance
+    pub fn entrance(&self) -> Option<Opening> {
+        self.entrance
+    }
+
+    /// Where the maze is left, in the south wall of the bottom right cell unless it has been
+    /// moved, or `None` if the maze has no exit
+    pub fn exit(&self) -> Option<Opening> {
+        self.exit
+    }
+
+    /// Move the entrance to `entrance`, or close it with `None`. Fails with
+    /// [`Error::NotOnBorder`] if the opening is not in the outer wall of a cell on the border.
+    pub fn set_entrance(&mut self, entrance: Option<Opening>) -> Result<(), Error> {
+        self.entrance = self.check_opening(entrance)?;
+        Ok(())
+    }
+
+    /// Move the exit to `exit`, or close it with `None`. Fails with [`Error::NotOnBorder`] if
+    /// the opening is not in the outer wall of a cell on the border.
+    pub fn set_exit(&mut self, exit: Option<Opening>) -> Result<(), Error> {
+        self.exit = self.check_opening(exit)?;
+        Ok(())
+    }
+
+    fn check_opening(&self, opening: Option<Opening>) -> Result<Option<Opening>, Error> {
+        match opening {
+            Some(opening) if !self.is_on_border(opening) => Err(Error::NotOnBorder(opening)),
+            _ => Ok(opening),
+        }
+    }
+
+    /// Whether `opening` is in the wall of a cell on its side of the border
+    pub(crate) fn is_on_border(&self, opening: Opening) -> bool {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let (x, y) = (opening.x, opening.y);
+        x < width
+            && y < height
+            && match opening.side {
+                Direction::North => y == 0,
+                Direction::South => y + 1 == height,
+                Direction::West => x == 0,
+                Direction::East => x + 1 == width,
+            }
+    }
+
+    /// Move the entrance and exit without checking that they are on the border, for mazes
+    /// carved from a mask, whose openings are in the border of the mask instead
+    pub(crate) fn set_openings(&mut self, entrance: Option<Opening>, exit: Option<Opening>) {
+        self.entrance = entrance;
+        self.exit = exit;
+    }
+
+    /// The entrance and then the exit, leaving out those that the maze does not have
+    pub(crate) fn openings(&self) -> impl Iterator<Item = Opening> {
+        self.entrance.into_iter().chain(self.exit)
+    }
+
+    /// Move the entrance and exit to the two border cells that are farthest apart, so that the
+    /// route through the maze is as long as it can be. In a perfect maze the pair is found
+    /// exactly with two distance maps: the border cell farthest from any border cell is one end
+    /// of the longest route between border cells. Border cells without any passages, like the
+    /// cells that a [`Mask`](crate::mask::Mask) leaves out, are passed over.
+    pub fn place_openings_farthest(&mut self) {
+        let grid = self.grid();
+        let border: Vec<(usize, usize)> = (0..grid.len())
+            .filter(|idx| grid.len() == 1 || !self.passages(*idx).is_empty())
+            .map(|idx| grid.coords(idx))
+            .filter(|(x, y)| *x == 0 || *y == 0 || x + 1 == grid.width || y + 1 == grid.height)
+            .collect();
+        let farthest = |from: (usize, usize)| {
+            let distances = self.distances(from);
+            border
+                .iter()
+                .copied()
+                .filter_map(|(x, y)| distances.get(x, y).map(|d| ((x, y), d)))
+                .rev()
+                .max_by_key(|(_, d)| *d)
+                .map(|(cell, _)| cell)
+        };
+        let Some(first) = border.first().copied() else {
+            return;
+        };
+        let Some(start) = farthest(first) else {
+            return;
+        };
+        let Some(end) = farthest(start) else {
+            return;
+        };
+        self.entrance = Some(Opening::on_border(&grid, start));
+        self.exit = Some(Opening::on_border(&grid, end));
+    }
+
+    /// The span of `opening` along its side of the border. Openings wider than a cell extend
+    /// towards the far end of the side, or back from it if there is not enough room.
+    fn opening_span(&self, opening: Opening) -> (f32, f32) {
+        let (along, side_len) = match opening.side {
+            Direction::North | Direction::South => (opening.x, self.width),
+            Direction::East | Direction::West => (opening.y, self.height),
+        };
+        let side_len = side_len as f32;
+        let width = self.opening_width.min(side_len);
+        let cells = float::ceil(width).max(1.0);
+        let first = (along as f32).min(side_len - cells).max(0.0);
+        let start = first + (cells - width) / 2.0;
+        (start, start + width)
+    }
+
+    /// The border walls left out for the entrance and the exit, in that order, or `None` for
+    /// those that the maze does not have
+    #[cfg(feature = "render")]
+    pub(crate) fn opening_segments(&self) -> [Option<Segment>; 2] {
+        [self.entrance, self.exit].map(|opening| {
+            opening.map(|opening| {
+                let (from, to) = self.opening_span(opening);
+                Segment::new(
+                    self.border_point(opening.side, from),
+                    self.border_point(opening.side, to),
+                )
+            })
+        })
+    }
+
+    /// The point `along` cells from the north or west end of the border on `side`
+    fn border_point(&self, side: Direction, along: f32) -> (f32, f32) {
+        let (width, height) = (self.width as f32, self.height as f32);
+        match side {
+            Direction::North => (along, 0.0),
+            Direction::South => (along, height),
+            Direction::West => (0.0, along),
+            Direction::East => (width, along),
+        }
+    }
+
+    /// Every wall as line segments in cell coordinates, with the top left corner of the maze at
+    /// `0, 0` and each cell one unit wide. Each wall shared by two cells appears once, and no
+    /// segment is longer than a cell. The outer border is included, leaving gaps for the
+    /// entrance and exit and stopping short of tunnels as [`Maze::draw`] does, so the segments
+    /// are all it takes to draw the maze with another renderer.
+    pub fn wall_segments(&self) -> impl Iterator<Item = Segment> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        self.wall_segments_in((0, 0), (width, height)).into_iter()
+    }
+
+    /// The [wall segments](Maze::wall_segments) around the cells from `start` up to but not
+    /// including `end`, found without going through the rest of the maze
+    pub(crate) fn wall_segments_in(
+        &self,
+        (x0, y0): (usize, usize),
+        (x1, y1): (usize, usize),
+    ) -> Vec<Segment> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let mut segments = Vec::new();
+        // The east and south edges of the region are the west and north walls of the cells past
+        // it, so those cells are visited for just that wall
+        for y in y0..(y1 + 1).min(height) {
+            for x in x0..(x1 + 1).min(width) {
+                let cell = self.cell(x, y);
+                let (north, west) = (x < x1, y < y1);
+                let (fx, fy) = (x as f32, y as f32);
+                // Walls beside a tunnel stop short of the cell it passes under, so that the
+                // tunnel shows up as a gap on either side of the passage above it
+                let (x, y) = (x as isize, y as isize);
+                let gap = |offsets: [(isize, isize); 2], east_west: bool| {
+                    let tunnel = offsets
+                        .iter()
+                        .any(|(dx, dy)| self.tunnel_at(x + dx, y + dy) == Some(east_west));
+                    if tunnel {
+                        TUNNEL_GAP
+                    } else {
+                        0.0
+                    }
+                };
+                if north && !cell.north_open() && y > 0 {
+                    let west = gap([(-1, 0), (-1, -1)], true);
+                    let east = gap([(1, 0), (1, -1)], true);
+                    segments.push(Segment::new((fx + west, fy), (fx + 1.0 - east, fy)));
+                }
+                if west && !cell.west_open() && x > 0 {
+                    let north = gap([(0, -1), (-1, -1)], false);
+                    let south = gap([(0, 1), (-1, 1)], false);
+                    segments.push(Segment::new((fx, fy + north), (fx, fy + 1.0 - south)));
+                }
+            }
+        }
+        for side in Direction::ALL {
+            let (on_side, range) = match side {
+                Direction::North => (y0 == 0, x0..x1),
+                Direction::South => (y1 == height, x0..x1),
+                Direction::West => (x0 == 0, y0..y1),
+                Direction::East => (x1 == width, y0..y1),
+            };
+            if !on_side {
+                continue;
+            }
+            let gaps: Vec<(f32, f32)> = self
+                .openings()
+                .filter(|opening| opening.side == side)
+                .map(|opening| self.opening_span(opening))
+                .collect();
+            for along in range {
+                let along = along as f32;
+                for (from, to) in border_pieces(along, &gaps) {
+                    segments.push(Segment::new(
+                        self.border_point(side, from),
+                        self.border_point(side, to),
+                    ));
+                }
+            }
+        }
+        segments
+    }
+}
+
+/// Mazes are equal when they have the same walls, tunnels and openings. Mazes that are only
+/// rotations or reflections of each other are not; compare their [canonical forms](Maze::canonical)
+/// for that.
+impl PartialEq for Maze {
+    fn eq(&self, other: &Self) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && self.cells == other.cells
+            && self.opening_width.to_bits() == other.opening_width.to_bits()
+            && self.entrance == other.entrance
+            && self.exit == other.exit
+    }
+}
+
+impl Eq for Maze {}
+
+impl Hash for Maze {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.width.hash(state);
+        self.height.hash(state);
+        self.cells.hash(state);
+        self.opening_width.to_bits().hash(state);
+        self.entrance.hash(state);
+        self.exit.hash(state);
+    }
+}
+
+#[cfg(feature = "render")]
+impl Maze {
+    /// Draw the walls as `options` says, leaving gaps in the border for the
+    /// [entrance](Maze::entrance) and [exit](Maze::exit). Fails with [`Error::ImageSize`] if the
+    /// image would be empty or too large.
+    ///
+    /// ```
+    /// use dadalus::maze::RenderOptions;
+    /// use dadalus::MazeGenerator;
+    ///
+    /// let maze = dadalus::Generator.generate_seeded(20, 10, 1);
+    /// let options = RenderOptions {
+    ///     margin: 4,
+    ///     ..RenderOptions::new(10)
+    /// };
+    /// assert_eq!(maze.draw(&options)?.width(), 20 * 10 + 2 * 4);
+    /// # Ok::<(), dadalus::Error>(())
+    /// ```
+    pub fn draw(&self, options: &RenderOptions) -> Result<Pixmap, Error> {
+        let mut pixmap = self.background(options)?;
+        self.draw_walls(&mut pixmap.as_mut(), options, Transform::identity());
+        self.draw_markers(&mut pixmap.as_mut(), options, Transform::identity());
+        Ok(pixmap)
+    }
+
+    /// Draw the maze into `rect` of `pixmap`, which may be part of a larger picture such as a
+    /// worksheet with several mazes on it. The image that [`Maze::draw`] would make with
+    /// `options`, with its background, margin, title and caption, is scaled to fit in `rect`,
+    /// keeping its proportions, and centered in it. The rest of `pixmap` is left alone.
+    ///
+    /// ```
+    /// use dadalus::maze::RenderOptions;
+    /// use dadalus::MazeGenerator;
+    /// use tiny_skia::{Color, Pixmap, Rect};
+    ///
+    /// let mut page = Pixmap::new(400, 200).unwrap();
+    /// let options = RenderOptions {
+    ///     background: Color::WHITE,
+    ///     ..RenderOptions::default()
+    /// };
+    /// for (k, seed) in [1, 2].into_iter().enumerate() {
+    ///     let maze = dadalus::Generator.generate_seeded(10, 10, seed);
+    ///     let rect = Rect::from_xywh(k as f32 * 200.0, 0.0, 200.0, 200.0).unwrap();
+    ///     maze.draw_into(&mut page.as_mut(), rect, &options);
+    /// }
+    /// ```
+    pub fn draw_into(&self, pixmap: &mut PixmapMut, rect: Rect, options: &RenderOptions) {
+        let (width, height) = self.image_size(options);
+        let (width, height) = (width as f32, height as f32);
+        if width == 0.0 || height == 0.0 {
+            return;
+        }
+        let scale = (rect.width() / width).min(rect.height() / height);
+        let transform = Transform::from_translate(
+            rect.x() + (rect.width() - scale * width) / 2.0,
+            rect.y() + (rect.height() - scale * height) / 2.0,
+        )
+        .pre_scale(scale, scale);
+        if let Some(area) = Rect::from_xywh(0.0, 0.0, width, height) {
+            let mut paint = Paint::default();
+            paint.set_color(options.background);
+            pixmap.fill_rect(area, &paint, transform, None);
+        }
+        self.draw_backdrop(pixmap, options, transform);
+        self.draw_walls(pixmap, options, transform);
+        self.draw_markers(pixmap, options, transform);
+    }
+
+    /// Draw the maze as [`Maze::draw`] does, with the shortest route from the entrance to the
+    /// exit drawn over it in red through the middle of the cells, with arrows along it if
+    /// [`RenderOptions::solution_arrows`] is set. Without a route, or without an entrance or
+    /// exit, this is the same as [`Maze::draw`].
+    pub fn draw_with_solution(&self, options: &RenderOptions) -> Result<Pixmap, Error> {
+        let mut pixmap = self.background(options)?;
+        self.draw_walls(&mut pixmap.as_mut(), options, Transform::identity());
+        if let (Some(entrance), Some(exit), Some(path)) =
+            (self.entrance, self.exit, self.solution())
+        {
+            self.draw_route(&mut pixmap, options, entrance, exit, &path);
+        }
+        self.draw_markers(&mut pixmap.as_mut(), options, Transform::identity());
+        Ok(pixmap)
+    }
+
+    /// Stroke the route along `path`, running out through `entrance` and `exit` at its ends
+    fn draw_route(
+        &self,
+        pixmap: &mut Pixmap,
+        options: &RenderOptions,
+        entrance: Opening,
+        exit: Opening,
+        path: &Path,
+    ) {
         let mut paint = Paint::default();
-        paint.set_color_rgba8(0, 0, 0, 200);
-        paint.anti_alias = true;
+        paint.set_color_rgba8(220, 40, 40, 220);
+        paint.anti_alias = options.anti_alias;
+        let scale = options.cell_size as f32;
+        let stroke = Stroke {
+            width: (scale / 5.0).max(1.0),
+            line_cap: LineCap::Round,
+            line_join: LineJoin::Round,
+            ..Stroke::default()
+        };
+
+        // Run the route out through the openings so that it visibly starts and ends outside
+        let center = |(x, y): (usize, usize)| (x as f32 + 0.5, y as f32 + 0.5);
+        let outside = |opening: Opening| {
+            let (x, y) = center((opening.x, opening.y));
+            let (dx, dy) = opening.side.delta();
+            (x + dx as f32 * 0.5, y + dy as f32 * 0.5)
+        };
+        let points: Vec<(f32, f32)> = core::iter::once(outside(entrance))
+            .chain(path.cells().iter().map(|cell| center(*cell)))
+            .chain(core::iter::once(outside(exit)))
+            .collect();
+        let mut pb = PathBuilder::new();
+        for (k, (x, y)) in points.iter().enumerate() {
+            if k == 0 {
+                pb.move_to(x * scale, y * scale);
+            } else {
+                pb.line_to(x * scale, y * scale);
+            }
+        }
+        if let Some(route) = pb.finish() {
+            pixmap.stroke_path(&route, &paint, &stroke, options.origin(), None);
+        }
+        if !options.solution_arrows {
+            return;
+        }
+
+        // An arrowhead where the route crosses from one cell into the next, on every other
+        // crossing so that they do not crowd each other, and one leaving through the exit
+        let mut pb = PathBuilder::new();
+        let last = points.len() - 2;
+        for (k, pair) in points[1..].windows(2).enumerate() {
+            if k % 2 == 1 && k != last - 1 {
+                continue;
+            }
+            let [(x0, y0), (x1, y1)] = [pair[0], pair[1]];
+            let (dx, dy) = (x1 - x0, y1 - y0);
+            let length = dx.hypot(dy);
+            if length == 0.0 {
+                continue;
+            }
+            let (dx, dy) = (dx / length, dy / length);
+            let (x, y) = ((x0 + x1) / 2.0 * scale, (y0 + y1) / 2.0 * scale);
+            let half = 0.2 * scale;
+            pb.move_to(x + dx * half, y + dy * half);
+            pb.line_to(x - (dx + dy) * half, y - (dy - dx) * half);
+            pb.line_to(x - (dx - dy) * half, y - (dy + dx) * half);
+            pb.close();
+        }
+        paint.set_color_rgba8(140, 20, 20, 240);
+        if let Some(arrows) = pb.finish() {
+            pixmap.fill_path(&arrows, &paint, FillRule::Winding, options.origin(), None);
+        }
+    }
 
-        let stroke = Stroke::default();
+    /// Draw the maze as [`Maze::draw`] does over cells filled in rainbow colors by their distance
+    /// from the cell at `root`: red next to the root, through yellow, green and blue, to violet
+    /// for the farthest cells. Cells that cannot be reached from the root are left empty.
+    ///
+    /// # Panics
+    ///
+    /// If `root` is outside of the maze.
+    pub fn draw_heatmap(
+        &self,
+        options: &RenderOptions,
+        root: (usize, usize),
+    ) -> Result<Pixmap, Error> {
+        let distances = self.distances(root);
+        let max = distances.max().max(1) as f32;
+        self.draw_filled(options, |x, y, _| {
+            let distance = distances.get(x, y)?;
+            Some(rainbow(distance as f32 / max))
+        })
+    }
 
-        let width = self.width * cell_size;
-        let height = self.height * cell_size;
-        let mut pixmap = Pixmap::new(width, height).unwrap();
+    /// Draw the maze as [`Maze::draw`] does with dead ends tinted red and junctions, where three
+    /// or four passages meet, tinted blue, to show at a glance how branchy the maze is
+    pub fn draw_branching(&self, options: &RenderOptions) -> Result<Pixmap, Error> {
+        let grid = self.grid();
+        self.draw_filled(options, |x, y, _| {
+            match self.passages(grid.index(x, y)).len() {
+                1 => Some(Color::from_rgba8(230, 80, 80, 120)),
+                3 | 4 => Some(Color::from_rgba8(60, 120, 230, 120)),
+                _ => None,
+            }
+        })
+    }
 
-        let transform = Transform::identity();
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let idx = usize::try_from(y * self.width + x).unwrap();
-                let cell = &self.cells[idx];
+    /// Draw the maze as [`Maze::draw`] does over cells filled with the colors that `fill` gives
+    /// for them. `fill` is called with the x and y of every cell and the cell itself, and the
+    /// cells it returns `None` for are left with the background.
+    ///
+    /// ```
+    /// use dadalus::maze::RenderOptions;
+    /// use dadalus::MazeGenerator;
+    /// use tiny_skia::Color;
+    ///
+    /// let maze = dadalus::Generator.generate_seeded(8, 8, 1);
+    /// let checkerboard = maze.draw_filled(&RenderOptions::new(10), |x, y, _| {
+    ///     ((x + y) % 2 == 0).then_some(Color::from_rgba8(240, 220, 160, 255))
+    /// })?;
+    /// assert_eq!(checkerboard.pixel(5, 5).unwrap().alpha(), 255);
+    /// assert_eq!(checkerboard.pixel(15, 5).unwrap().alpha(), 0);
+    /// # Ok::<(), dadalus::Error>(())
+    /// ```
+    pub fn draw_filled(
+        &self,
+        options: &RenderOptions,
+        fill: impl Fn(usize, usize, &Cell) -> Option<Color>,
+    ) -> Result<Pixmap, Error> {
+        let mut pixmap = self.background(options)?;
+        self.fill_cells(&mut pixmap, options, fill);
+        self.draw_walls(&mut pixmap.as_mut(), options, Transform::identity());
+        self.draw_markers(&mut pixmap.as_mut(), options, Transform::identity());
+        Ok(pixmap)
+    }
 
-                if cell.north_open() && cell.west_open() {
+    /// Draw the maze as [`Maze::draw`] does over `image`, such as a photo. The image is scaled
+    /// to cover the cells, keeping its proportions, and centered on them with whatever sticks
+    /// out past the sides of the maze cut off. The margin, title and caption are left with the
+    /// background color.
+    pub fn draw_over(&self, image: &Pixmap, options: &RenderOptions) -> Result<Pixmap, Error> {
+        let mut pixmap = self.background(options)?;
+        let cell_size = options.cell_size as f32;
+        let (width, height) = (
+            self.width as f32 * cell_size,
+            self.height as f32 * cell_size,
+        );
+        let (image_width, image_height) = (image.width() as f32, image.height() as f32);
+        let scale = (width / image_width).max(height / image_height);
+        let (left, top) = options.offset();
+        let transform = Transform::from_row(
+            scale,
+            0.0,
+            0.0,
+            scale,
+            left + (width - scale * image_width) / 2.0,
+            top + (height - scale * image_height) / 2.0,
+        );
+        let paint = Paint {
+            shader: Pattern::new(
+                image.as_ref(),
+                SpreadMode::Pad,
+                FilterQuality::Bicubic,
+                1.0,
+                transform,
+            ),
+            ..Paint::default()
+        };
+        if let Some(rect) = Rect::from_xywh(left, top, width, height) {
+            pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+        }
+        self.draw_walls(&mut pixmap.as_mut(), options, Transform::identity());
+        self.draw_markers(&mut pixmap.as_mut(), options, Transform::identity());
+        Ok(pixmap)
+    }
+
+    /// Fill each cell that `color` gives a color for with a square of that color, at the cell
+    /// size and margin of `options`
+    fn fill_cells(
+        &self,
+        pixmap: &mut Pixmap,
+        options: &RenderOptions,
+        color: impl Fn(usize, usize, &Cell) -> Option<Color>,
+    ) {
+        let scale = options.cell_size as f32;
+        let transform = options.origin();
+        let mut paint = Paint::default();
+        for y in 0..self.height as usize {
+            for x in 0..self.width as usize {
+                let Some(color) = color(x, y, self.cell(x, y)) else {
                     continue;
+                };
+                paint.set_color(color);
+                if let Some(rect) =
+                    Rect::from_xywh(x as f32 * scale, y as f32 * scale, scale, scale)
+                {
+                    pixmap.fill_rect(rect, &paint, transform, None);
                 }
+            }
+        }
+    }
 
-                let top_left_x = f64::from(x * cell_size);
-                let top_left_y = f64::from(y * cell_size);
-
-                // Leave a gap for the entrance at the north wall of 0,0
-                if !(cell.north_open() || x == 0 && y == 0) {
-                    let mut pb = PathBuilder::new();
-                    pb.move_to(top_left_x as f32, top_left_y as f32);
-                    pb.line_to(
-                        (top_left_x + f64::from(cell_size)) as f32,
-                        top_left_y as f32,
-                    );
-                    let path = pb.finish().unwrap();
-                    pixmap.stroke_path(&path, &paint, &stroke, transform, None);
-                }
+    /// Draw with opaque black walls on a white background, sized in physical units so that the
+    /// printed maze is easy to read for low-vision solvers and young children.
+    pub fn draw_accessible(&self, preset: &AccessiblePrint) -> Result<Pixmap, Error> {
+        let stroke_width = preset.stroke_px();
+        self.draw(&RenderOptions {
+            wall_color: Color::BLACK,
+            background: Color::WHITE,
+            stroke_width,
+            line_cap: LineCap::Square,
+            // Pad by the stroke width so the outer walls are not clipped to half their thickness
+            margin: stroke_width.ceil() as u32,
+            ..RenderOptions::new(preset.cell_size_px())
+        })
+    }
+
+    /// A transparent image of the maze as `options` lays it out, with room for its margin,
+    /// title and caption
+    fn canvas(&self, options: &RenderOptions) -> Result<Pixmap, Error> {
+        let (width, height) = self.image_size(options);
+        pixmap(width, height)
+    }
+
+    /// An image of the maze as `options` lays it out, filled with its background
+    fn background(&self, options: &RenderOptions) -> Result<Pixmap, Error> {
+        let mut pixmap = self.canvas(options)?;
+        pixmap.fill(options.background);
+        self.draw_backdrop(&mut pixmap.as_mut(), options, Transform::identity());
+        Ok(pixmap)
+    }
+
+    /// Draw the [backdrop](RenderOptions::backdrop) of `options` over the whole image, lined up
+    /// with the cells, where `transform` takes the image that `options` lays out onto `pixmap`
+    fn draw_backdrop(&self, pixmap: &mut PixmapMut, options: &RenderOptions, transform: Transform) {
+        let (width, height) = self.image_size(options);
+        let size = (width as f32, height as f32);
+        draw_backdrop(pixmap, options, transform, size, options.offset());
+    }
+
+    /// Draw only the cells from `x0, y0` up to but not including `x1, y1`, as [`Maze::draw`]
+    /// would draw them, onto an image of just that part of the maze. This is for looking at
+    /// mazes too large to draw in one image: the work and the memory it takes are for the
+    /// region alone, and regions next to each other line up as tiles of the image that
+    /// [`Maze::draw`] would make, up to the smoothing of walls along their edges. The margin,
+    /// title and caption of `options` go around the region.
+    ///
+    /// ```
+    /// use dadalus::maze::RenderOptions;
+    /// use dadalus::MazeGenerator;
+    ///
+    /// let maze = dadalus::Generator.generate_seeded(1000, 1000, 1);
+    /// let tile = maze.draw_region(500, 500, 520, 510, &RenderOptions::new(10))?;
+    /// assert_eq!((tile.width(), tile.height()), (200, 100));
+    /// # Ok::<(), dadalus::Error>(())
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the region does not lie within the maze, or ends before it starts.
+    pub fn draw_region(
+        &self,
+        x0: usize,
+        y0: usize,
+        x1: usize,
+        y1: usize,
+        options: &RenderOptions,
+    ) -> Result<Pixmap, Error> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        assert!(
+            x0 <= x1 && y0 <= y1 && x1 <= width && y1 <= height,
+            "region {x0}, {y0} to {x1}, {y1} is not inside the {width} by {height} maze"
+        );
+        let cells = |count: usize| count as u64 * u64::from(options.cell_size);
+        let margins = 2 * u64::from(options.margin);
+        let bands = u64::from(options.title_height) + u64::from(options.caption_height);
+        let size = (cells(x1 - x0) + margins, cells(y1 - y0) + margins + bands);
+        let mut pixmap = pixmap(size.0, size.1)?;
+        pixmap.fill(options.background);
+
+        // Shift the maze so that the region is where the whole maze would start
+        let cell_size = options.cell_size as f32;
+        let (left, top) = (x0 as f32 * cell_size, y0 as f32 * cell_size);
+        let shift = Transform::from_translate(-left, -top);
+        let (corner_x, corner_y) = options.offset();
+        let size = (size.0 as f32, size.1 as f32);
+        let corner = (corner_x - left, corner_y - top);
+        draw_backdrop(
+            &mut pixmap.as_mut(),
+            options,
+            Transform::identity(),
+            size,
+            corner,
+        );
+        let segments = self.wall_segments_in((x0, y0), (x1, y1));
+        self.stroke_segments(&mut pixmap.as_mut(), options, shift, segments);
+        self.draw_markers(&mut pixmap.as_mut(), options, shift);
+        Ok(pixmap)
+    }
 
-                if !cell.west_open() {
-                    let mut pb = PathBuilder::new();
-                    pb.move_to(top_left_x as f32, top_left_y as f32);
-                    pb.line_to(
-                        top_left_x as f32,
-                        (top_left_y + f64::from(cell_size)) as f32,
-                    );
-                    let path = pb.finish().unwrap();
-                    pixmap.stroke_path(&path, &paint, &stroke, transform, None);
+    /// Width and height in pixels of the image of the maze as `options` lays it out
+    fn image_size(&self, options: &RenderOptions) -> (u64, u64) {
+        let pixels = |cells: u32| {
+            u64::from(cells) * u64::from(options.cell_size) + 2 * u64::from(options.margin)
+        };
+        let bands = u64::from(options.title_height) + u64::from(options.caption_height);
+        (pixels(self.width), pixels(self.height) + bands)
+    }
+
+    /// Draw the [start](RenderOptions::start_marker) and [finish](RenderOptions::finish_marker)
+    /// markers of `options` in the entrance and exit cells, their labels beside them, the
+    /// [watermark](RenderOptions::watermark) and the [legend](RenderOptions::legend), where
+    /// `transform` takes the image that `options` lays out onto `pixmap`
+    fn draw_markers(&self, pixmap: &mut PixmapMut, options: &RenderOptions, transform: Transform) {
+        self.draw_labels(pixmap, options, transform);
+        draw_watermark(pixmap, options, transform);
+        self.draw_legend(pixmap, options, transform);
+        let scale = options.cell_size as f32;
+        let transform = transform.pre_concat(options.origin());
+        let markers = [
+            (options.start_marker, self.entrance),
+            (options.finish_marker, self.exit),
+        ];
+        for (marker, opening) in markers {
+            let (Some(marker), Some(opening)) = (marker, opening) else {
+                continue;
+            };
+            let (x, y) = (opening.x as f32 * scale, opening.y as f32 * scale);
+            let (Marker::Dot(color) | Marker::Flag(color)) = marker;
+            let mut paint = Paint::default();
+            paint.set_color(color);
+            paint.anti_alias = options.anti_alias;
+            let mut pb = PathBuilder::new();
+            match marker {
+                Marker::Dot(_) => {
+                    pb.push_circle(x + 0.5 * scale, y + 0.5 * scale, 0.3 * scale);
+                }
+                Marker::Flag(_) => {
+                    // A pennant on the right of a thin pole
+                    let pole = (0.08 * scale).max(1.0);
+                    if let Some(rect) =
+                        Rect::from_xywh(x + 0.3 * scale, y + 0.2 * scale, pole, 0.6 * scale)
+                    {
+                        pb.push_rect(rect);
+                    }
+                    pb.move_to(x + 0.3 * scale + pole, y + 0.2 * scale);
+                    pb.line_to(x + 0.75 * scale, y + 0.35 * scale);
+                    pb.line_to(x + 0.3 * scale + pole, y + 0.5 * scale);
+                    pb.close();
                 }
             }
+            if let Some(path) = pb.finish() {
+                pixmap.fill_path(&path, &paint, FillRule::Winding, transform, None);
+            }
         }
+    }
 
-        // Southern-most wall
+    /// Write the start and finish labels of `options` in the margin outside the entrance and
+    /// exit, centered on the opening and clear of the border by [`LABEL_GAP`]
+    fn draw_labels(&self, pixmap: &mut PixmapMut, options: &RenderOptions, transform: Transform) {
+        let scale = options.cell_size as f32;
+        let height = LABEL_HEIGHT * scale;
+        let gap = LABEL_GAP * scale;
+        let (right, bottom) = (self.width as f32 * scale, self.height as f32 * scale);
+        let labels = [
+            (&options.start_label, self.entrance),
+            (&options.finish_label, self.exit),
+        ];
         let mut pb = PathBuilder::new();
-        pb.move_to(0.0, f64::from(self.height * cell_size) as f32);
-        pb.line_to(
-            // Leave a gap for the exit
-            f64::from((self.width - 1) * cell_size) as f32,
-            f64::from(self.height * cell_size) as f32,
+        for (text, opening) in labels {
+            let (Some(text), Some(opening)) = (text, opening) else {
+                continue;
+            };
+            let (x, y) = (
+                (opening.x as f32 + 0.5) * scale,
+                (opening.y as f32 + 0.5) * scale,
+            );
+            let half_width = label::text_width(text, height) / 2.0;
+            let (x, y) = match opening.side {
+                Direction::North => (x, -gap - height / 2.0),
+                Direction::South => (x, bottom + gap + height / 2.0),
+                Direction::West => (-gap - half_width, y),
+                Direction::East => (right + gap + half_width, y),
+            };
+            label::push_text(&mut pb, text, x, y, height);
+        }
+        let Some(path) = pb.finish() else {
+            return;
+        };
+        let mut paint = Paint::default();
+        paint.set_color(options.wall_color);
+        paint.anti_alias = options.anti_alias;
+        let stroke = Stroke {
+            width: label_pen(height),
+            line_cap: LineCap::Round,
+            line_join: LineJoin::Round,
+            ..Stroke::default()
+        };
+        pixmap.stroke_path(
+            &path,
+            &paint,
+            &stroke,
+            transform.pre_concat(options.origin()),
+            None,
         );
-        let path = pb.finish().unwrap();
-        pixmap.stroke_path(&path, &paint, &stroke, transform, None);
+    }
+
+    /// Draw the [legend](RenderOptions::legend) of `options`, if it has one, as a box in the
+    /// caption band up against the right side of the maze, with one line of text for each thing
+    /// it says
+    fn draw_legend(&self, pixmap: &mut PixmapMut, options: &RenderOptions, transform: Transform) {
+        let Some(legend) = &options.legend else {
+            return;
+        };
+        let lines = legend.lines();
+        if lines.is_empty() {
+            return;
+        }
+        let scale = options.cell_size as f32;
+        let (box_width, box_height) = options.legend_box(&lines);
+        let height = LEGEND_HEIGHT * scale;
+        let gap = LABEL_GAP * scale;
+        let half_stroke = options.stroke_width / 2.0;
+        let right = self.width as f32 * scale - half_stroke;
+        let top = self.height as f32 * scale + options.margin as f32 + gap + half_stroke;
+        let left = right - box_width;
+
+        let mut paint = Paint::default();
+        paint.set_color(options.wall_color);
+        paint.anti_alias = options.anti_alias;
+        let transform = transform.pre_concat(options.origin());
+        if let Some(rect) = Rect::from_xywh(left, top, box_width, box_height) {
+            let outline = PathBuilder::from_rect(rect);
+            let stroke = Stroke {
+                width: options.stroke_width,
+                ..Stroke::default()
+            };
+            pixmap.stroke_path(&outline, &paint, &stroke, transform, None);
+        }
 
-        // Eastern-most wall
+        let pen = label_pen(height);
         let mut pb = PathBuilder::new();
-        pb.move_to(f64::from(self.width * cell_size) as f32, 0.0);
-        pb.line_to(
-            f64::from(self.width * cell_size) as f32,
-            f64::from(self.height * cell_size) as f32,
-        );
-        let path = pb.finish().unwrap();
-        pixmap.stroke_path(&path, &paint, &stroke, transform, None);
+        for (k, line) in lines.iter().enumerate() {
+            // push_text centers the text, so it is moved right by half its width to line up on
+            // the left
+            let x = left + gap + pen / 2.0 + label::text_width(line, height) / 2.0;
+            let y = top + gap + pen / 2.0 + (k as f32 * 1.5 + 0.5) * height;
+            label::push_text(&mut pb, line, x, y, height);
+        }
+        if let Some(path) = pb.finish() {
+            let stroke = Stroke {
+                width: pen,
+                line_cap: LineCap::Round,
+                line_join: LineJoin::Round,
+                ..Stroke::default()
+            };
+            pixmap.stroke_path(&path, &paint, &stroke, transform, None);
+        }
+    }
+
+    /// Stroke the walls onto `pixmap` with the paint, stroke and margin of `options`, where
+    /// `transform` takes the image that `options` lays out onto `pixmap`
+    fn draw_walls(&self, pixmap: &mut PixmapMut, options: &RenderOptions, transform: Transform) {
+        self.stroke_segments(pixmap, options, transform, self.wall_segments());
+    }
+
+    /// Stroke `segments` as [`Maze::draw_walls`] strokes the walls
+    fn stroke_segments(
+        &self,
+        pixmap: &mut PixmapMut,
+        options: &RenderOptions,
+        transform: Transform,
+        segments: impl IntoIterator<Item = Segment>,
+    ) {
+        let mut paint = Paint::default();
+        paint.set_color(options.wall_color);
+        paint.anti_alias = options.anti_alias;
+        let stroke = Stroke {
+            width: options.stroke_width,
+            line_cap: options.line_cap,
+            line_join: options.line_join,
+            ..Stroke::default()
+        };
+
+        // The walls between the cells of the watermark region are left out so that it is blank
+        // for the watermark, but the walls around it are kept
+        let watermark = options.watermark.as_ref().map(|watermark| watermark.region);
+        let segments = segments
+            .into_iter()
+            .filter(|segment| watermark.is_none_or(|region| !inside(region, segment.midpoint())));
+
+        // Merge segments first so that long walls are stroked as a single line rather than as
+        // per-cell pieces whose overlapping ends show up as seams
+        let cell_size = options.cell_size as f32;
+        let mut pb = PathBuilder::new();
+        for segment in merge_collinear(segments) {
+            pb.move_to(segment.from.0 * cell_size, segment.from.1 * cell_size);
+            pb.line_to(segment.to.0 * cell_size, segment.to.1 * cell_size);
+        }
+        // Stroke every wall in one pass so that antialiasing is not applied twice where walls meet
+        if let Some(path) = pb.finish() {
+            let transform = transform.pre_concat(options.origin());
+            pixmap.stroke_path(&path, &paint, &stroke, transform, None);
+        }
+    }
+}
+
+#[cfg(feature = "render")]
+/// Whether the point `x, y`, in cells, is inside `region` and not on its edge
+fn inside(region: Region, (x, y): (f32, f32)) -> bool {
+    let (left, top) = (region.x as f32, region.y as f32);
+    let (right, bottom) = (left + region.width as f32, top + region.height as f32);
+    left < x && x < right && top < y && y < bottom
+}
+
+#[cfg(feature = "render")]
+/// Draw the [watermark](RenderOptions::watermark) of `options`, if it has one, as large as fits
+/// in its region and centered in it, where `transform` takes the image that `options` lays out
+/// onto `pixmap`
+fn draw_watermark(pixmap: &mut PixmapMut, options: &RenderOptions, transform: Transform) {
+    let Some(watermark) = &options.watermark else {
+        return;
+    };
+    let scale = options.cell_size as f32;
+    let region = watermark.region;
+    let padding = WATERMARK_PADDING * scale + options.stroke_width / 2.0;
+    let (width, height) = (
+        region.width as f32 * scale - 2.0 * padding,
+        region.height as f32 * scale - 2.0 * padding,
+    );
+    if width <= 0.0 || height <= 0.0 {
+        return;
+    }
+    let (center_x, center_y) = (
+        (region.x as f32 + region.width as f32 / 2.0) * scale,
+        (region.y as f32 + region.height as f32 / 2.0) * scale,
+    );
+    let transform = transform.pre_concat(options.origin());
+    match &watermark.content {
+        WatermarkContent::Text(text) => {
+            // The text is as wide as it is high times its width at a height of 1, and the pen
+            // sticks out by half its width all around
+            let across = label::text_width(text, 1.0);
+            let mut size = height.min(width / across);
+            size -= label_pen(size);
+            if size <= 0.0 {
+                return;
+            }
+            let mut pb = PathBuilder::new();
+            label::push_text(&mut pb, text, center_x, center_y, size);
+            let Some(path) = pb.finish() else {
+                return;
+            };
+            let mut paint = Paint::default();
+            paint.set_color(options.wall_color);
+            paint.anti_alias = options.anti_alias;
+            let stroke = Stroke {
+                width: label_pen(size),
+                line_cap: LineCap::Round,
+                line_join: LineJoin::Round,
+                ..Stroke::default()
+            };
+            pixmap.stroke_path(&path, &paint, &stroke, transform, None);
+        }
+        WatermarkContent::Image(image) => {
+            let (image_width, image_height) = (image.width() as f32, image.height() as f32);
+            let fit = (width / image_width).min(height / image_height);
+            let (drawn_width, drawn_height) = (fit * image_width, fit * image_height);
+            let (left, top) = (center_x - drawn_width / 2.0, center_y - drawn_height / 2.0);
+            let image_transform = Transform::from_row(fit, 0.0, 0.0, fit, left, top);
+            let paint = Paint {
+                shader: Pattern::new(
+                    image.as_ref(),
+                    SpreadMode::Pad,
+                    FilterQuality::Bicubic,
+                    1.0,
+                    image_transform,
+                ),
+                anti_alias: options.anti_alias,
+                ..Paint::default()
+            };
+            if let Some(rect) = Rect::from_xywh(left, top, drawn_width, drawn_height) {
+                pixmap.fill_rect(rect, &paint, transform, None);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "render")]
+/// Draw the [backdrop](RenderOptions::backdrop) of `options` over an image `size` pixels wide
+/// and high, lined up with the cells of a maze whose top left corner is at `corner`
+fn draw_backdrop(
+    pixmap: &mut PixmapMut,
+    options: &RenderOptions,
+    transform: Transform,
+    (width, height): (f32, f32),
+    (corner_x, corner_y): (f32, f32),
+) {
+    let spacing = (options.cell_size as f32 / 4.0).max(3.0);
+    let (first_x, first_y) = (corner_x.rem_euclid(spacing), corner_y.rem_euclid(spacing));
+    let lines = |first: f32, end: f32| {
+        (0..)
+            .map(move |k| first + k as f32 * spacing)
+            .take_while(move |at| *at <= end)
+    };
+
+    let mut pb = PathBuilder::new();
+    let color = match options.backdrop {
+        Backdrop::Plain => return,
+        Backdrop::Grid(color) => {
+            for x in lines(first_x, width) {
+                pb.move_to(x, 0.0);
+                pb.line_to(x, height);
+            }
+            for y in lines(first_y, height) {
+                pb.move_to(0.0, y);
+                pb.line_to(width, y);
+            }
+            color
+        }
+        Backdrop::Halftone(color) => {
+            // Every other row is shifted by half a step, which lines the dots up at 45
+            // degrees like the screen of a printed photo
+            for y in lines(first_y, height) {
+                let row = float::round((y - corner_y) / spacing) as i64;
+                let shift = if row % 2 != 0 { spacing / 2.0 } else { 0.0 };
+                for x in lines(first_x + shift, width) {
+                    pb.push_circle(x, y, 0.3 * spacing);
+                }
+            }
+            color
+        }
+    };
+    let mut paint = Paint::default();
+    paint.set_color(color);
+    paint.anti_alias = options.anti_alias;
+    if let Some(path) = pb.finish() {
+        match options.backdrop {
+            Backdrop::Grid(_) => {
+                let stroke = Stroke::default();
+                pixmap.stroke_path(&path, &paint, &stroke, transform, None);
+            }
+            _ => pixmap.fill_path(&path, &paint, FillRule::Winding, transform, None),
+        }
+    }
+}
+
+#[cfg(feature = "render")]
+/// A transparent image of `width` by `height` pixels, or [`Error::ImageSize`] if there cannot be
+/// one that size
+pub(crate) fn pixmap(width: u64, height: u64) -> Result<Pixmap, Error> {
+    u32::try_from(width)
+        .ok()
+        .zip(u32::try_from(height).ok())
+        .and_then(|(w, h)| Pixmap::new(w, h))
+        .ok_or(Error::ImageSize { width, height })
+}
+
+#[cfg(feature = "render")]
+/// A fully saturated color with a hue `t` of the way from red to violet
+fn rainbow(t: f32) -> Color {
+    let hue = t.clamp(0.0, 1.0) * 270.0 / 60.0;
+    let x = 1.0 - (hue % 2.0 - 1.0).abs();
+    let (r, g, b) = match hue as u32 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        _ => (x, 0.0, 1.0),
+    };
+    Color::from_rgba(r, g, b, 1.0).unwrap()
+}
+
+/// The pieces of the border wall of the cell `along` cells from the end of its side that are
+/// not covered by any of `gaps`
+fn border_pieces(along: f32, gaps: &[(f32, f32)]) -> Vec<(f32, f32)> {
+    let mut pieces = vec![(along, along + 1.0)];
+    for &(gap_start, gap_end) in gaps {
+        pieces = pieces
+            .into_iter()
+            .flat_map(|(from, to)| {
+                [(from, gap_start.min(to)), (gap_end.max(from), to)]
+                    .into_iter()
+                    .filter(|(from, to)| from < to)
+            })
+            .collect();
+    }
+    pieces
+}
+
+/// Join segments that continue each other in a straight line into single, longer segments
+#[cfg(feature = "render")]
+pub(crate) fn merge_collinear(segments: impl IntoIterator<Item = Segment>) -> Vec<Segment> {
+    let mut segments: Vec<Segment> = segments.into_iter().collect();
+    for segment in segments.iter_mut() {
+        if (segment.to.0, segment.to.1) < (segment.from.0, segment.from.1) {
+            core::mem::swap(&mut segment.from, &mut segment.to);
+        }
+    }
+    let (mut horizontal, mut vertical): (Vec<Segment>, Vec<Segment>) =
+        segments.into_iter().partition(|s| s.from.1 == s.to.1);
+    horizontal.sort_unstable_by(|a, b| {
+        (a.from.1.total_cmp(&b.from.1)).then(a.from.0.total_cmp(&b.from.0))
+    });
+    vertical.sort_unstable_by(|a, b| {
+        (a.from.0.total_cmp(&b.from.0)).then(a.from.1.total_cmp(&b.from.1))
+    });
+
+    let mut merged: Vec<Segment> = Vec::with_capacity(horizontal.len() + vertical.len());
+    for run in [horizontal, vertical] {
+        let run_start = merged.len();
+        for segment in run {
+            match merged[run_start..].last_mut() {
+                Some(last) if last.to == segment.from => last.to = segment.to,
+                _ => merged.push(segment),
+            }
+        }
+    }
+    merged
+}
+
+#[cfg(feature = "render")]
+/// How [`Maze::draw`] and the other drawing methods draw a maze. The defaults draw thin,
+/// translucent black walls on a transparent image, right up to its edges.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderOptions {
+    /// Width and height of a cell in pixels
+    pub cell_size: u32,
+    pub wall_color: Color,
+    /// Color to fill the image with before the walls are drawn. Leave it
+    /// [transparent](Color::TRANSPARENT) to lay the maze over another picture.
+    pub background: Color,
+    /// Pattern to draw over the background
+    pub backdrop: Backdrop,
+    /// Thickness of the walls in pixels
+    pub stroke_width: f32,
+    /// Shape of the loose ends of walls. [`LineCap::Square`] carries thick walls on past their
+    /// ends by half their thickness so that corners are closed.
+    pub line_cap: LineCap,
+    /// Shape of the corners where a wall turns
+    pub line_join: LineJoin,
+    /// Pixels of space around the maze on every side. Without it the outer walls are cut to
+    /// half their thickness at the edges of the image.
+    pub margin: u32,
+    /// Pixels of empty space above the maze and its margin, left with the background for a
+    /// title to be drawn into
+    pub title_height: u32,
+    /// Pixels of empty space below the maze and its margin, left with the background for a
+    /// caption, such as a name line on a worksheet
+    pub caption_height: u32,
+    /// Mark to draw in the entrance cell, where the maze starts
+    pub start_marker: Option<Marker>,
+    /// Mark to draw in the exit cell, where the maze finishes
+    pub finish_marker: Option<Marker>,
+    /// Text to write in the wall color beside the entrance, such as `START` or a player's name.
+    /// It goes in the margin outside the border, which [`RenderOptions::label_room`] says how
+    /// wide to make. Letters are written in capitals, and characters other than letters, digits
+    /// and hyphens are left as spaces.
+    pub start_label: Option<String>,
+    /// Text to write beside the exit, as for [`RenderOptions::start_label`]
+    pub finish_label: Option<String>,
+    /// Whether [`Maze::draw_with_solution`] points arrows along the route to show which way it
+    /// goes
+    pub solution_arrows: bool,
+    /// Logo or text to draw into a region of cells, with the walls inside the region left out
+    pub watermark: Option<Watermark>,
+    /// Box with the seed, difficulty and date to draw in the caption band, which
+    /// [`RenderOptions::legend_room`] says how high to make
+    pub legend: Option<Legend>,
+    /// Whether to smooth the edges of the walls
+    pub anti_alias: bool,
+}
+
+#[cfg(feature = "render")]
+/// A pattern that [`RenderOptions`] draws over the background of the image, in steps of a
+/// quarter of a cell that line up with the walls
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Backdrop {
+    Plain,
+    /// Thin lines in both directions, like graph paper
+    Grid(Color),
+    /// Rows of dots, like the halftone screen of a newspaper photo
+    Halftone(Color),
+}
+
+#[cfg(feature = "render")]
+/// A ready-made look for drawn mazes, with the colors, wall thickness and background of
+/// [`RenderOptions`] chosen to go together
+///
+/// ```
+/// use dadalus::maze::Theme;
+/// use dadalus::MazeGenerator;
+///
+/// let maze = dadalus::Generator.generate_seeded(20, 10, 1);
+/// let pixmap = maze.draw(&Theme::Blueprint.options(20))?;
+/// assert_eq!(pixmap.pixel(0, 0).unwrap().alpha(), 255);
+/// # Ok::<(), dadalus::Error>(())
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Theme {
+    /// Black walls on white paper
+    Classic,
+    /// White walls on blue graph paper
+    Blueprint,
+    /// Bright green walls on a dark background
+    Neon,
+    /// Black walls on off-white newsprint with a halftone screen
+    Newspaper,
+}
+
+#[cfg(feature = "render")]
+impl Theme {
+    /// Options for drawing in this theme with `cell_size` pixels per cell. The margin is wide
+    /// enough for the outer walls to show in full.
+    pub fn options(self, cell_size: u32) -> RenderOptions {
+        let scale = cell_size as f32;
+        let (wall_color, background, backdrop, stroke_width) = match self {
+            Theme::Classic => (Color::BLACK, Color::WHITE, Backdrop::Plain, scale / 12.0),
+            Theme::Blueprint => (
+                Color::from_rgba8(235, 242, 255, 255),
+                Color::from_rgba8(22, 64, 140, 255),
+                Backdrop::Grid(Color::from_rgba8(255, 255, 255, 40)),
+                scale / 10.0,
+            ),
+            Theme::Neon => (
+                Color::from_rgba8(60, 255, 120, 255),
+                Color::from_rgba8(12, 10, 24, 255),
+                Backdrop::Plain,
+                scale / 8.0,
+            ),
+            Theme::Newspaper => (
+                Color::from_rgba8(30, 30, 30, 255),
+                Color::from_rgba8(240, 236, 224, 255),
+                Backdrop::Halftone(Color::from_rgba8(0, 0, 0, 36)),
+                scale / 10.0,
+            ),
+        };
+        let stroke_width = stroke_width.max(1.0);
+        RenderOptions {
+            wall_color,
+            background,
+            backdrop,
+            stroke_width,
+            line_cap: LineCap::Square,
+            margin: stroke_width.ceil() as u32,
+            ..RenderOptions::new(cell_size)
+        }
+    }
+}
+
+#[cfg(feature = "render")]
+/// Something for [`RenderOptions`] to draw into a rectangle of cells, such as the logo on a
+/// branded puzzle sheet. Leave the region out of the maze with
+/// [`Mask::reserving`](crate::mask::Mask::reserving) so that no passages run under it.
+///
+/// ```
+/// use dadalus::builder::Algorithm;
+/// use dadalus::mask::Mask;
+/// use dadalus::maze::{RenderOptions, Watermark, WatermarkContent};
+/// use dadalus::regional::Region;
+/// use dadalus::Maze;
+///
+/// let region = Region::new(6, 4, 8, 2);
+/// let maze = Maze::builder()
+///     .algorithm(Algorithm::Wilsons)
+///     .mask(Mask::reserving(20, 10, region))
+///     .seed(1)
+///     .build()?;
+/// let options = RenderOptions {
+///     watermark: Some(Watermark {
+///         region,
+///         content: WatermarkContent::Text("DADALUS".into()),
+///     }),
+///     ..RenderOptions::new(20)
+/// };
+/// let pixmap = maze.draw(&options)?;
+/// // The wall between the first two cells of the region, above the text, is left out
+/// assert_eq!(pixmap.pixel(140, 82).unwrap().alpha(), 0);
+/// # Ok::<(), dadalus::Error>(())
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Watermark {
+    /// The cells to draw into
+    pub region: Region,
+    pub content: WatermarkContent,
+}
+
+#[cfg(feature = "render")]
+/// What a [`Watermark`] draws
+#[derive(Clone, Debug, PartialEq)]
+pub enum WatermarkContent {
+    /// Text written in the wall color, with the letters and characters of the start and finish
+    /// labels
+    Text(String),
+    /// An image scaled to fit, keeping its proportions
+    Image(Pixmap),
+}
+
+#[cfg(feature = "render")]
+/// What the legend box of [`RenderOptions`] says about the maze, so that printed puzzles can be
+/// told apart and made again. Each part that is given gets its own line.
+///
+/// ```
+/// use dadalus::builder::Difficulty;
+/// use dadalus::maze::{Legend, RenderOptions};
+/// use dadalus::Maze;
+///
+/// let maze = Maze::builder().difficulty(Difficulty::Easy).seed(7).build()?;
+/// let mut options = RenderOptions {
+///     legend: Some(Legend {
+///         seed: Some(7),
+///         difficulty: Some(Difficulty::Easy),
+///         date: Some("2026-10-14".into()),
+///     }),
+///     ..RenderOptions::new(20)
+/// };
+/// options.caption_height = options.legend_room();
+/// let pixmap = maze.draw(&options)?;
+/// assert_eq!(pixmap.height(), 9 * 20 + options.legend_room());
+/// # Ok::<(), dadalus::Error>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Legend {
+    /// Seed the maze was generated from
+    pub seed: Option<u64>,
+    /// Preset the maze was generated with
+    pub difficulty: Option<Difficulty>,
+    /// Date written as it is given, such as `2026-10-14`, with the characters of the start and
+    /// finish labels
+    pub date: Option<String>,
+}
+
+#[cfg(feature = "render")]
+impl Legend {
+    /// The lines of text in the box
+    fn lines(&self) -> Vec<String> {
+        let seed = self.seed.map(|seed| format!("SEED {seed}"));
+        let difficulty = (self.difficulty).map(|level| format!("DIFFICULTY {}", level.name()));
+        [seed, difficulty, self.date.clone()]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+}
+
+#[cfg(feature = "render")]
+/// A mark that [`RenderOptions`] draws in the entrance or exit cell, to show solvers where to
+/// start and where to finish
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Marker {
+    /// A filled circle in the middle of the cell
+    Dot(Color),
+    /// A pennant on a pole
+    Flag(Color),
+}
+
+#[cfg(feature = "render")]
+impl RenderOptions {
+    /// The default options with `cell_size` pixels per cell
+    pub fn new(cell_size: u32) -> Self {
+        Self {
+            cell_size,
+            ..Self::default()
+        }
+    }
+
+    /// Pixels of margin that the start and finish labels need to fit in the image, whichever
+    /// sides of the maze their openings are on, or 0 without labels
+    pub fn label_room(&self) -> u32 {
+        let scale = self.cell_size as f32;
+        let height = LABEL_HEIGHT * scale;
+        [&self.start_label, &self.finish_label]
+            .into_iter()
+            .flatten()
+            .map(|text| {
+                let across = label::text_width(text, height).max(height);
+                // The gap goes on both sides, so the label is not up against the edge either
+                ((2.0 * LABEL_GAP * scale + across + label_pen(height)).ceil()) as u32
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Pixels of caption band that the legend needs to fit below the maze and its margin, or 0
+    /// without a legend
+    pub fn legend_room(&self) -> u32 {
+        let Some(legend) = &self.legend else {
+            return 0;
+        };
+        let lines = legend.lines();
+        if lines.is_empty() {
+            return 0;
+        }
+        let (_, height) = self.legend_box(&lines);
+        let gap = LABEL_GAP * self.cell_size as f32;
+        (2.0 * gap + height + self.stroke_width).ceil() as u32
+    }
+
+    /// Width and height in pixels of the box around the legend `lines`, inside its outline
+    fn legend_box(&self, lines: &[String]) -> (f32, f32) {
+        let scale = self.cell_size as f32;
+        let height = LEGEND_HEIGHT * scale;
+        let gap = LABEL_GAP * scale;
+        let across = lines
+            .iter()
+            .map(|line| label::text_width(line, height))
+            .fold(0.0, f32::max);
+        let down = (lines.len() as f32 * 1.5 - 0.5) * height;
+        let pen = label_pen(height);
+        (across + 2.0 * gap + pen, down + 2.0 * gap + pen)
+    }
+
+    /// Where the top left corner of the maze is drawn in the image, past the margin and title
+    fn offset(&self) -> (f32, f32) {
+        let margin = self.margin as f32;
+        (margin, margin + self.title_height as f32)
+    }
+
+    /// Transform from maze pixels, with the top left corner of the maze at 0, 0, to the image
+    fn origin(&self) -> Transform {
+        let (left, top) = self.offset();
+        Transform::from_translate(left, top)
+    }
+}
+
+#[cfg(feature = "render")]
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 25,
+            wall_color: Color::from_rgba8(0, 0, 0, 200),
+            background: Color::TRANSPARENT,
+            backdrop: Backdrop::Plain,
+            stroke_width: 1.0,
+            line_cap: LineCap::Butt,
+            line_join: LineJoin::Miter,
+            margin: 0,
+            title_height: 0,
+            caption_height: 0,
+            start_marker: None,
+            finish_marker: None,
+            start_label: None,
+            finish_label: None,
+            solution_arrows: false,
+            watermark: None,
+            legend: None,
+            anti_alias: true,
+        }
+    }
+}
 
-        pixmap
+#[cfg(feature = "render")]
+/// Physical sizing for [`Maze::draw_accessible`], and for vector output through
+/// [`SvgOptions::accessible`](crate::svg::SvgOptions::accessible) and
+/// [`PdfOptions::accessible`](crate::pdf::PdfOptions::accessible)
+#[derive(Clone, Copy, Debug)]
+pub struct AccessiblePrint {
+    /// Resolution the image will be printed at
+    pub dpi: f32,
+    /// Minimum printed wall thickness in millimeters
+    pub min_stroke_mm: f32,
+    /// Minimum printed corridor width (the gap between walls) in millimeters
+    pub min_corridor_mm: f32,
+}
+
+#[cfg(feature = "render")]
+impl AccessiblePrint {
+    fn mm_to_px(&self, mm: f32) -> f32 {
+        mm / 25.4 * self.dpi
+    }
+
+    pub(crate) fn stroke_px(&self) -> f32 {
+        self.mm_to_px(self.min_stroke_mm).max(1.0)
+    }
+
+    pub(crate) fn cell_size_px(&self) -> u32 {
+        (self.mm_to_px(self.min_corridor_mm) + self.stroke_px()).ceil() as u32
+    }
+}
+
+#[cfg(feature = "render")]
+impl Default for AccessiblePrint {
+    fn default() -> Self {
+        Self {
+            dpi: 150.0,
+            min_stroke_mm: 1.5,
+            min_corridor_mm: 8.0,
+        }
     }
 }
 
+#[cfg(feature = "render")]
+/// Width of the pen that labels `height` pixels high are written with
+fn label_pen(height: f32) -> f32 {
+    (height / 10.0).max(1.0)
+}
+
+/// A straight wall in cell units, where cell corners range from `(0, 0)` to `(width, height)`.
+/// Walls normally run between cell corners, but may end partway along a cell where the border is
+/// cut for an opening.
 #[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Segment {
+    pub from: (f32, f32),
+    pub to: (f32, f32),
+}
+
+impl Segment {
+    pub fn new(from: (f32, f32), to: (f32, f32)) -> Self {
+        Self { from, to }
+    }
+
+    #[cfg(feature = "render")]
+    fn midpoint(&self) -> (f32, f32) {
+        (
+            (self.from.0 + self.to.0) / 2.0,
+            (self.from.1 + self.to.1) / 2.0,
+        )
+    }
+}
+
+/// A gap in the outer border of a maze, in the `side` wall of the cell at `x, y`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Opening {
+    pub x: usize,
+    pub y: usize,
+    pub side: Direction,
+}
+
+impl Opening {
+    /// An opening in the border wall of a cell on the edge of `grid`, preferring the north and
+    /// south walls of corner cells
+    fn on_border(grid: &RectGrid, (x, y): (usize, usize)) -> Self {
+        let side = if y == 0 {
+            Direction::North
+        } else if y + 1 == grid.height {
+            Direction::South
+        } else if x == 0 {
+            Direction::West
+        } else {
+            Direction::East
+        };
+        Self { x, y, side }
+    }
+}
+
+/// Two cells that [`Maze::open_between`] or [`Maze::close_between`] cannot join or separate,
+/// because they are not next to each other or one of them is outside of the maze
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NotAdjacent {
+    pub a: (usize, usize),
+    pub b: (usize, usize),
+}
+
+impl core::fmt::Display for NotAdjacent {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "cells {}, {} and {}, {} are not next to each other in the maze",
+            self.a.0, self.a.1, self.b.0, self.b.1
+        )
+    }
+}
+
+impl core::error::Error for NotAdjacent {}
+
+/// A cell next to another one, from [`Maze::neighbors`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Neighbor {
+    pub x: usize,
+    pub y: usize,
+    /// The direction from the other cell to this one
+    pub direction: Direction,
+    /// Whether the wall between the two cells is open
+    pub open: bool,
+}
+
+/// A cell of a [`Maze`] that knows where it is, from [`Maze::cell_ref`]. It dereferences to the
+/// [`Cell`] itself.
+#[derive(Clone, Copy)]
+pub struct CellRef<'a> {
+    maze: &'a Maze,
+    x: usize,
+    y: usize,
+}
+
+impl<'a> CellRef<'a> {
+    pub fn x(&self) -> usize {
+        self.x
+    }
+
+    pub fn y(&self) -> usize {
+        self.y
+    }
+
+    /// The row-major index of the cell
+    pub fn index(&self) -> usize {
+        self.y * self.maze.width as usize + self.x
+    }
+
+    pub fn cell(&self) -> &'a Cell {
+        &self.maze.cells[self.index()]
+    }
+
+    /// Whether the wall on the `direction` side of the cell is open, whichever cell owns it. The
+    /// walls on the border of the maze are open only for the entrance and exit. Tunnels under a
+    /// neighbor do not count.
+    pub fn is_open(&self, direction: Direction) -> bool {
+        let bit = match direction {
+            Direction::North => 1,
+            Direction::East => 2,
+            Direction::South => 4,
+            Direction::West => 8,
+        };
+        self.maze.tile_mask(self.x, self.y) & bit != 0
+    }
+}
+
+impl core::ops::Deref for CellRef<'_> {
+    type Target = Cell;
+
+    fn deref(&self) -> &Cell {
+        self.cell()
+    }
+}
+
+impl core::fmt::Debug for CellRef<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CellRef")
+            .field("x", &self.x)
+            .field("y", &self.y)
+            .field("cell", self.cell())
+            .finish()
+    }
+}
+
+/// The open walls of a single maze cell
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Cell {
     bits: u8,
 }
@@ -107,6 +1967,17 @@ impl Cell {
         Cell { bits }
     }
 
+    /// The raw wall and tunnel flags, for saving the cell
+    pub(crate) fn bits(&self) -> u8 {
+        self.bits
+    }
+
+    /// A cell from flags saved with [`Cell::bits`], or `None` if unknown flags are set
+    #[cfg(feature = "std")]
+    pub(crate) fn from_bits(bits: u8) -> Option<Self> {
+        (bits & !7u8 == 0).then_some(Cell { bits })
+    }
+
     pub fn west_open(&self) -> bool {
         self.bits & 1u8 > 0
     }
@@ -115,6 +1986,10 @@ impl Cell {
         self.bits |= 1u8;
     }
 
+    pub fn set_west_closed(&mut self) {
+        self.bits &= !1u8;
+    }
+
     pub fn north_open(&self) -> bool {
         self.bits & 2u8 > 0
     }
@@ -122,4 +1997,18 @@ impl Cell {
     pub fn set_north_open(&mut self) {
         self.bits |= 2u8;
     }
+
+    pub fn set_north_closed(&mut self) {
+        self.bits &= !2u8;
+    }
+
+    /// Whether a passage runs under this cell, crossing the straight passage through it. The
+    /// tunnel joins the two neighbors on the sides whose walls are closed.
+    pub fn tunnel(&self) -> bool {
+        self.bits & 4u8 > 0
+    }
+
+    pub fn set_tunnel(&mut self) {
+        self.bits |= 4u8;
+    }
 }
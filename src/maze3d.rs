@@ -0,0 +1,297 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use rand::prelude::*;
+#[cfg(feature = "render")]
+use tiny_skia::{FillRule, Paint, PathBuilder, Pixmap, Stroke, Transform};
+
+#[cfg(feature = "render")]
+use crate::error::Error;
+#[cfg(feature = "render")]
+use crate::maze::{merge_collinear, pixmap, Segment};
+
+/// A direction from a cell to an adjacent cell in a stack of grid layers. Up is towards the
+/// layer with the next higher index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction3d {
+    North,
+    South,
+    East,
+    West,
+    Up,
+    Down,
+}
+
+impl Direction3d {
+    pub const ALL: [Direction3d; 6] = [
+        Direction3d::North,
+        Direction3d::South,
+        Direction3d::East,
+        Direction3d::West,
+        Direction3d::Up,
+        Direction3d::Down,
+    ];
+}
+
+/// A maze of `depth` rectangular layers stacked on top of each other, with passages up and down
+/// between cells at the same position in neighboring layers. Each cell owns its north and west
+/// walls and the floor to the layer below. The entrance is in the north wall of the top left
+/// cell of layer 0 and the exit in the south wall of the bottom right cell of the last layer.
+pub struct Maze3d {
+    cells: Box<[Cell3d]>,
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+}
+
+impl Maze3d {
+    /// A maze with every wall and floor closed
+    pub fn new(width: u32, height: u32, depth: u32) -> Self {
+        let len = usize::try_from(width * height * depth).unwrap();
+        Self {
+            cells: vec![Cell3d::default(); len].into_boxed_slice(),
+            width,
+            height,
+            depth,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The index of the cell at `x, y` in `layer`. Cells are ordered row by row and then layer
+    /// by layer.
+    pub fn index(&self, x: usize, y: usize, layer: usize) -> usize {
+        (layer * self.height as usize + y) * self.width as usize + x
+    }
+
+    pub fn coords(&self, index: usize) -> (usize, usize, usize) {
+        let (width, height) = (self.width as usize, self.height as usize);
+        (
+            index % width,
+            index / width % height,
+            index / (width * height),
+        )
+    }
+
+    pub fn cell(&self, index: usize) -> &Cell3d {
+        &self.cells[index]
+    }
+
+    /// Index of the cell next to `index` in `direction`, or `None` if that would be outside of
+    /// the maze
+    pub fn adjacent_index(&self, index: usize, direction: Direction3d) -> Option<usize> {
+        let (x, y, z) = self.coords(index);
+        let (width, height, depth) = (
+            self.width as usize,
+            self.height as usize,
+            self.depth as usize,
+        );
+        let (x, y, z) = match direction {
+            Direction3d::North => (x, y.checked_sub(1)?, z),
+            Direction3d::South => (x, (y + 1 < height).then_some(y + 1)?, z),
+            Direction3d::West => (x.checked_sub(1)?, y, z),
+            Direction3d::East => ((x + 1 < width).then_some(x + 1)?, y, z),
+            Direction3d::Down => (x, y, z.checked_sub(1)?),
+            Direction3d::Up => (x, y, (z + 1 < depth).then_some(z + 1)?),
+        };
+        Some(self.index(x, y, z))
+    }
+
+    /// Open the wall or floor between the cell at `index` and its neighbor in `direction`. The
+    /// neighbor must exist.
+    pub fn open_wall(&mut self, index: usize, direction: Direction3d) {
+        let neighbor = self.adjacent_index(index, direction).unwrap();
+        match direction {
+            Direction3d::North => self.cells[index].set_north_open(),
+            Direction3d::West => self.cells[index].set_west_open(),
+            Direction3d::Down => self.cells[index].set_down_open(),
+            Direction3d::South => self.cells[neighbor].set_north_open(),
+            Direction3d::East => self.cells[neighbor].set_west_open(),
+            Direction3d::Up => self.cells[neighbor].set_down_open(),
+        }
+    }
+
+    /// Whether there is a passage up from the cell at `index` to the layer above
+    pub fn up_open(&self, index: usize) -> bool {
+        self.adjacent_index(index, Direction3d::Up)
+            .is_some_and(|above| self.cells[above].down_open())
+    }
+}
+
+#[cfg(feature = "render")]
+impl Maze3d {
+    /// Draw every layer as its own page, from layer 0 up
+    pub fn draw_layers(&self, cell_size: u32) -> Result<Vec<Pixmap>, Error> {
+        (0..self.depth as usize)
+            .map(|layer| self.draw_layer(layer, cell_size))
+            .collect()
+    }
+
+    /// Draw the walls of one layer onto a transparent image with `cell_size` pixels per cell.
+    /// Cells with a passage to the layer above are marked with a triangle pointing up, and cells
+    /// with a passage to the layer below with one pointing down.
+    pub fn draw_layer(&self, layer: usize, cell_size: u32) -> Result<Pixmap, Error> {
+        let mut paint = Paint::default();
+        paint.set_color_rgba8(0, 0, 0, 200);
+        paint.anti_alias = true;
+
+        let width = u64::from(self.width) * u64::from(cell_size);
+        let height = u64::from(self.height) * u64::from(cell_size);
+        let mut pixmap = pixmap(width, height)?;
+
+        let scale = cell_size as f32;
+        let mut pb = PathBuilder::new();
+        for segment in merge_collinear(self.layer_segments(layer)) {
+            pb.move_to(segment.from.0 * scale, segment.from.1 * scale);
+            pb.line_to(segment.to.0 * scale, segment.to.1 * scale);
+        }
+        if let Some(path) = pb.finish() {
+            pixmap.stroke_path(
+                &path,
+                &paint,
+                &Stroke::default(),
+                Transform::identity(),
+                None,
+            );
+        }
+
+        // Up markers go in the west half of the cell and down markers in the east half, so that
+        // a cell with both shows both
+        let mut pb = PathBuilder::new();
+        for y in 0..self.height as usize {
+            for x in 0..self.width as usize {
+                let idx = self.index(x, y, layer);
+                let (fx, fy) = (x as f32 * scale, y as f32 * scale);
+                if self.up_open(idx) {
+                    push_triangle(&mut pb, fx + 0.3 * scale, fy + 0.5 * scale, scale, -1.0);
+                }
+                if self.cells[idx].down_open() {
+                    push_triangle(&mut pb, fx + 0.7 * scale, fy + 0.5 * scale, scale, 1.0);
+                }
+            }
+        }
+        if let Some(path) = pb.finish() {
+            pixmap.fill_path(
+                &path,
+                &paint,
+                FillRule::Winding,
+                Transform::identity(),
+                None,
+            );
+        }
+        Ok(pixmap)
+    }
+
+    fn layer_segments(&self, layer: usize) -> Vec<Segment> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let mut segments = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let cell = &self.cells[self.index(x, y, layer)];
+                let (fx, fy) = (x as f32, y as f32);
+                let entrance = layer == 0 && x == 0 && y == 0;
+                if !cell.north_open() && !entrance {
+                    segments.push(Segment::new((fx, fy), (fx + 1.0, fy)));
+                }
+                if !cell.west_open() {
+                    segments.push(Segment::new((fx, fy), (fx, fy + 1.0)));
+                }
+            }
+        }
+        let (fw, fh) = (width as f32, height as f32);
+        let exit_on_layer = layer + 1 == self.depth as usize;
+        for x in 0..width {
+            if !(exit_on_layer && x + 1 == width) {
+                let fx = x as f32;
+                segments.push(Segment::new((fx, fh), (fx + 1.0, fh)));
+            }
+        }
+        segments.push(Segment::new((fw, 0.0), (fw, fh)));
+        segments
+    }
+}
+
+#[cfg(feature = "render")]
+/// Push a triangle centered on `x, y` that points up if `dir` is -1 and down if it is 1
+fn push_triangle(pb: &mut PathBuilder, x: f32, y: f32, cell_size: f32, dir: f32) {
+    let half = 0.15 * cell_size;
+    pb.move_to(x, y + dir * half);
+    pb.line_to(x - half, y - dir * half);
+    pb.line_to(x + half, y - dir * half);
+    pb.close();
+}
+
+/// Generate a uniformly random 3D maze with Wilson's algorithm, walking in all six directions
+pub fn generate<R: Rng + ?Sized>(width: u32, height: u32, depth: u32, rng: &mut R) -> Maze3d {
+    let mut maze = Maze3d::new(width, height, depth);
+    if maze.is_empty() {
+        return maze;
+    }
+    let mut candidates: Vec<usize> = (0..maze.len()).collect();
+    candidates.shuffle(rng);
+
+    let mut in_maze = vec![false; maze.len()];
+    in_maze[candidates.pop().unwrap()] = true;
+    // The direction each walk cell was last left in, as in the 2D walk
+    let mut next = vec![Direction3d::North; maze.len()];
+    while let Some(start_idx) = candidates.pop() {
+        let mut curr_idx = start_idx;
+        while !in_maze[curr_idx] {
+            let (direction, next_idx) = Direction3d::ALL
+                .iter()
+                .filter_map(|d| maze.adjacent_index(curr_idx, *d).map(|idx| (*d, idx)))
+                .choose(rng)
+                .expect("cell has no neighbors");
+            next[curr_idx] = direction;
+            curr_idx = next_idx;
+        }
+
+        curr_idx = start_idx;
+        while !in_maze[curr_idx] {
+            in_maze[curr_idx] = true;
+            maze.open_wall(curr_idx, next[curr_idx]);
+            curr_idx = maze.adjacent_index(curr_idx, next[curr_idx]).unwrap();
+        }
+    }
+    maze
+}
+
+/// The open walls and floor of a single cell of a [`Maze3d`]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Cell3d {
+    bits: u8,
+}
+
+impl Cell3d {
+    pub fn west_open(&self) -> bool {
+        self.bits & 1u8 > 0
+    }
+
+    pub fn set_west_open(&mut self) {
+        self.bits |= 1u8;
+    }
+
+    pub fn north_open(&self) -> bool {
+        self.bits & 2u8 > 0
+    }
+
+    pub fn set_north_open(&mut self) {
+        self.bits |= 2u8;
+    }
+
+    /// Whether there is a passage down to the layer below
+    pub fn down_open(&self) -> bool {
+        self.bits & 4u8 > 0
+    }
+
+    pub fn set_down_open(&mut self) {
+        self.bits |= 4u8;
+    }
+}
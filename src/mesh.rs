@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::maze::Maze;
+
+/// Sizes for [`Maze::to_stl`] and [`Maze::to_obj`], all in millimeters
+#[derive(Clone, Copy, Debug)]
+pub struct MeshOptions {
+    pub cell_size: f32,
+    pub wall_height: f32,
+    pub wall_thickness: f32,
+    /// Thickness of the floor plate under the walls, or 0 for loose walls without a floor
+    pub base_thickness: f32,
+}
+
+impl Default for MeshOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 10.0,
+            wall_height: 8.0,
+            wall_thickness: 1.5,
+            base_thickness: 2.0,
+        }
+    }
+}
+
+type Point = [f32; 3];
+
+/// A rectangular face of the solid, with its corners wound counterclockwise when seen from the
+/// outside
+struct Face {
+    normal: Point,
+    corners: [Point; 4],
+    /// Whether the face belongs to a wall rather than to the floor
+    wall: bool,
+}
+
+impl Maze {
+    /// Extrude the walls into a solid for 3D printing and write it as a binary STL mesh. The
+    /// mesh is watertight, with no overlapping walls for the slicer to merge, and the maze lies
+    /// on the XY plane with north towards +Y.
+    pub fn to_stl(&self, options: &MeshOptions) -> Vec<u8> {
+        let triangles: Vec<(Point, [Point; 3])> = self
+            .solid(options)
+            .into_iter()
+            .flat_map(|face| {
+                let [a, b, c, d] = face.corners;
+                [(face.normal, [a, b, c]), (face.normal, [a, c, d])]
+            })
+            .collect();
+
+        let mut out = Vec::with_capacity(84 + triangles.len() * 50);
+        let mut header = [0u8; 80];
+        let name = b"dadalus maze";
+        header[..name.len()].copy_from_slice(name);
+        out.extend_from_slice(&header);
+        out.extend_from_slice(&(triangles.len() as u32).to_le_bytes());
+        for (normal, points) in triangles {
+            for value in normal.iter().chain(points.iter().flatten()) {
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+            out.extend_from_slice(&0u16.to_le_bytes());
+        }
+        out
+    }
+
+    /// Write the same solid as [`Maze::to_stl`] as a Wavefront OBJ mesh for game engines and 3D
+    /// modelling tools, with the floor and the walls as separate objects so that each can be
+    /// given its own material. Following the usual convention for OBJ, +Y is up and north is
+    /// towards -Z. Corners shared between faces are written once.
+    pub fn to_obj(&self, options: &MeshOptions) -> String {
+        let faces = self.solid(options);
+        let mut obj = String::from("# dadalus maze\n");
+        let mut vertices: HashMap<[u32; 3], usize> = HashMap::new();
+        let mut normals: HashMap<[u32; 3], usize> = HashMap::new();
+        for (name, wall) in [("floor", false), ("walls", true)] {
+            writeln!(obj, "o {name}").unwrap();
+            for face in faces.iter().filter(|face| face.wall == wall) {
+                let normal = index(&mut obj, &mut normals, "vn", face.normal);
+                let corners = face
+                    .corners
+                    .map(|corner| index(&mut obj, &mut vertices, "v", corner));
+                writeln!(
+                    obj,
+                    "f {0}//{normal} {1}//{normal} {2}//{normal} {3}//{normal}",
+                    corners[0], corners[1], corners[2], corners[3]
+                )
+                .unwrap();
+            }
+        }
+        obj
+    }
+
+    /// Every face of the maze extruded as a solid.
+    ///
+    /// The floor is split into strips along the wall lines and the cells between them, and every
+    /// piece of the resulting grid is a block as tall as the floor, or the floor and a wall. Only
+    /// the faces between blocks of different heights are kept, so neighboring blocks share their
+    /// edges exactly.
+    fn solid(&self, options: &MeshOptions) -> Vec<Face> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let half = options.wall_thickness / 2.0;
+        // Column and row 2k are wall lines and 2k + 1 the cells between them
+        let edges = |count: usize| -> Vec<f32> {
+            (0..=count)
+                .flat_map(|k| {
+                    let line = k as f32 * options.cell_size;
+                    [line - half, line + half]
+                })
+                .collect()
+        };
+        let (xs, ys) = (edges(width), edges(height));
+        let (columns, rows) = (xs.len() - 1, ys.len() - 1);
+
+        let floor = options.base_thickness;
+        let top = floor + options.wall_height;
+        let block = |column: usize, row: usize| -> f32 {
+            if width > 0 && height > 0 && self.wall_at(column, row) {
+                top
+            } else {
+                floor
+            }
+        };
+
+        // The y axis of the maze points south
+        let mut faces = Vec::new();
+        let corner = |column: usize, row: usize, z: f32| [xs[column], -ys[row], z];
+        for row in 0..rows {
+            for column in 0..columns {
+                let z = block(column, row);
+                if z == 0.0 {
+                    continue;
+                }
+                let quad = [(0, 0), (1, 0), (1, 1), (0, 1)];
+                let face = |level: f32| quad.map(|(c, r)| corner(column + c, row + r, level));
+                push_face(&mut faces, face(z), [0.0, 0.0, 1.0], z > floor);
+                push_face(&mut faces, face(0.0), [0.0, 0.0, -1.0], floor == 0.0);
+
+                // Sides towards each neighbor that is lower, split at the floor so that the
+                // sides along the outside meet the sides between blocks edge to edge
+                let sides = [
+                    ((1, 0), [(1, 0), (1, 1)]),
+                    ((-1, 0), [(0, 1), (0, 0)]),
+                    ((0, 1), [(1, 1), (0, 1)]),
+                    ((0, -1), [(0, 0), (1, 0)]),
+                ];
+                for ((dx, dy), [(c0, r0), (c1, r1)]) in sides {
+                    let neighbor = column
+                        .checked_add_signed(dx)
+                        .zip(row.checked_add_signed(dy))
+                        .filter(|(c, r)| *c < columns && *r < rows)
+                        .map_or(0.0, |(c, r)| block(c, r));
+                    let normal = [dx as f32, -dy as f32, 0.0];
+                    for (low, high) in [(0.0, floor), (floor, top)] {
+                        if low >= neighbor && high <= z && low < high {
+                            let a = corner(column + c0, row + r0, low);
+                            let b = corner(column + c1, row + r1, low);
+                            let c = corner(column + c1, row + r1, high);
+                            let d = corner(column + c0, row + r0, high);
+                            push_face(&mut faces, [a, b, c, d], normal, low >= floor);
+                        }
+                    }
+                }
+            }
+        }
+        faces
+    }
+}
+
+/// The number of the OBJ vertex or normal at `p`, written as a `kind` line the first time it is
+/// seen
+fn index(obj: &mut String, seen: &mut HashMap<[u32; 3], usize>, kind: &str, p: Point) -> usize {
+    // Swap to Y up, keeping the mesh right-handed. Adding zero turns -0 into 0, so that both are
+    // written as the same vertex.
+    let p = [p[0], p[2], -p[1]].map(|v| v + 0.0);
+    let next = seen.len() + 1;
+    *seen.entry(p.map(f32::to_bits)).or_insert_with(|| {
+        writeln!(obj, "{kind} {} {} {}", p[0], p[1], p[2]).unwrap();
+        next
+    })
+}
+
+/// Add the face with `corners`, turning them around if needed so that they wind
+/// counterclockwise when seen from the side that `normal` points to
+fn push_face(faces: &mut Vec<Face>, corners: [Point; 4], normal: Point, wall: bool) {
+    let [a, b, c, d] = corners;
+    let sub = |p: Point, q: Point| [p[0] - q[0], p[1] - q[1], p[2] - q[2]];
+    let (u, v) = (sub(b, a), sub(c, a));
+    let cross = [
+        u[1] * v[2] - u[2] * v[1],
+        u[2] * v[0] - u[0] * v[2],
+        u[0] * v[1] - u[1] * v[0],
+    ];
+    let dot: f32 = cross.iter().zip(normal).map(|(p, q)| p * q).sum();
+    let corners = if dot >= 0.0 {
+        [a, b, c, d]
+    } else {
+        [a, d, c, b]
+    };
+    faces.push(Face {
+        normal,
+        corners,
+        wall,
+    });
+}
@@ -0,0 +1,141 @@
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use rand::prelude::*;
+
+use crate::generator::MazeGenerator;
+use crate::grid::{Direction, RectGrid};
+use crate::maze::Maze;
+
+/// A perfect maze that can be mutated one step at a time with the origin shift algorithm.
+///
+/// The maze is kept as a tree with every cell pointing towards a root cell, the origin. Each
+/// step moves the origin to a random neighbor: the old origin opens a passage to the new one and
+/// the new origin closes the passage to the cell it used to point to. The maze stays perfect
+/// after every step and changes by at most one wall, so rendering it after each step shows a
+/// slowly morphing maze.
+pub struct OriginShift {
+    maze: Maze,
+    grid: RectGrid,
+    /// The direction from each cell to its parent, `None` for the origin
+    parent: Vec<Option<Direction>>,
+    origin: usize,
+}
+
+impl OriginShift {
+    /// Start from an existing perfect maze, with the origin in the top left cell. The walls must
+    /// form a spanning tree: cells that cannot be reached from the origin are never changed.
+    pub fn new(maze: Maze) -> Self {
+        let grid = RectGrid::new(maze.width as usize, maze.height as usize);
+        let mut parent = vec![None; grid.len()];
+        let mut seen = vec![false; grid.len()];
+        let mut queue = VecDeque::new();
+        if !grid.is_empty() {
+            seen[0] = true;
+            queue.push_back(0);
+        }
+        while let Some(idx) = queue.pop_front() {
+            for direction in Direction::iter() {
+                let Some(next_idx) = grid.adjacent_index(idx, direction) else {
+                    continue;
+                };
+                if !seen[next_idx] && maze.is_open(idx, direction) {
+                    seen[next_idx] = true;
+                    parent[next_idx] = Some(direction.opposite());
+                    queue.push_back(next_idx);
+                }
+            }
+        }
+        Self {
+            maze,
+            grid,
+            parent,
+            origin: 0,
+        }
+    }
+
+    /// Start from the simplest perfect maze: every row is a corridor running east into the
+    /// eastmost column, which runs south to the origin in the bottom right cell
+    pub fn with_size(width: usize, height: usize) -> Self {
+        let grid = RectGrid::new(width, height);
+        let mut maze = Maze::new(width as u32, height as u32);
+        let mut parent = vec![None; grid.len()];
+        for (idx, parent) in parent.iter_mut().enumerate() {
+            let (x, y) = grid.coords(idx);
+            let direction = if x + 1 < width {
+                Direction::East
+            } else if y + 1 < height {
+                Direction::South
+            } else {
+                continue;
+            };
+            maze.open_wall(idx, direction);
+            *parent = Some(direction);
+        }
+        Self {
+            maze,
+            grid,
+            parent,
+            origin: grid.len().saturating_sub(1),
+        }
+    }
+
+    pub fn maze(&self) -> &Maze {
+        &self.maze
+    }
+
+    pub fn into_maze(self) -> Maze {
+        self.maze
+    }
+
+    /// The index of the root cell that every path in the tree leads to
+    pub fn origin(&self) -> usize {
+        self.origin
+    }
+
+    /// Move the origin to a random neighbor, changing at most one wall of the maze
+    pub fn step<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let Some((direction, next_idx)) = Direction::iter()
+            .filter_map(|d| self.grid.adjacent_index(self.origin, d).map(|idx| (d, idx)))
+            .choose(rng)
+        else {
+            return;
+        };
+        if let Some(old) = self.parent[next_idx] {
+            self.maze.close_wall(next_idx, old);
+        }
+        self.maze.open_wall(self.origin, direction);
+        self.parent[self.origin] = Some(direction);
+        self.parent[next_idx] = None;
+        self.origin = next_idx;
+    }
+
+    pub fn steps<R: Rng + ?Sized>(&mut self, count: usize, rng: &mut R) {
+        for _ in 0..count {
+            self.step(rng);
+        }
+    }
+}
+
+/// Generates mazes by running origin shift from the simplest perfect maze until the origin has
+/// visited every cell. Every cell then points in the direction it was last left in, which makes
+/// the maze a uniformly random spanning tree with none of the starting corridors left.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Generator;
+
+impl MazeGenerator for Generator {
+    fn generate<R: Rng + ?Sized>(&self, width: usize, height: usize, rng: &mut R) -> Maze {
+        let mut shift = OriginShift::with_size(width, height);
+        let mut visited = vec![false; shift.grid.len()];
+        let mut unvisited = shift.grid.len();
+        while unvisited > 0 {
+            if !visited[shift.origin] {
+                visited[shift.origin] = true;
+                unvisited -= 1;
+            }
+            shift.step(rng);
+        }
+        shift.into_maze()
+    }
+}
@@ -0,0 +1,264 @@
+use std::fmt::Write;
+
+use crate::error::Error;
+use crate::maze::{merge_collinear, AccessiblePrint, Maze, Segment};
+
+/// Millimeters in an inch, for giving [`PdfOptions`] sizes in inches
+pub const MM_PER_INCH: f32 = 25.4;
+
+/// PDF points per millimeter
+const PT_PER_MM: f32 = 72.0 / MM_PER_INCH;
+
+/// Page and maze sizes for [`Maze::to_pdf`], all in millimeters. The maze is centered on the
+/// page at exactly the cell size given, so it prints at known physical dimensions as long as
+/// the printer is not told to scale the page to fit.
+#[derive(Clone, Copy, Debug)]
+pub struct PdfOptions {
+    pub page_width: f32,
+    pub page_height: f32,
+    pub cell_size: f32,
+    pub stroke_width: f32,
+}
+
+impl PdfOptions {
+    /// An ISO A4 page, 210 by 297 mm
+    pub fn a4(cell_size: f32) -> Self {
+        Self {
+            page_width: 210.0,
+            page_height: 297.0,
+            cell_size,
+            stroke_width: 0.5,
+        }
+    }
+
+    /// A US Letter page, 8.5 by 11 inches
+    pub fn letter(cell_size: f32) -> Self {
+        Self {
+            page_width: 8.5 * MM_PER_INCH,
+            page_height: 11.0 * MM_PER_INCH,
+            cell_size,
+            stroke_width: 0.5,
+        }
+    }
+
+    /// An A4 page with walls at least [`AccessiblePrint::min_stroke_mm`] thick and corridors at
+    /// least [`AccessiblePrint::min_corridor_mm`] wide. PDF sizes are physical, so the preset's
+    /// resolution does not matter.
+    pub fn accessible(preset: &AccessiblePrint) -> Self {
+        Self {
+            stroke_width: preset.min_stroke_mm,
+            ..Self::a4(preset.min_corridor_mm + preset.min_stroke_mm)
+        }
+    }
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        Self::a4(5.0)
+    }
+}
+
+/// Room left around the part of the maze on each page of [`Maze::to_tiled_pdf`], in millimeters.
+/// Most printers cannot print right up to the edge of the paper, and the label goes in the top
+/// margin.
+const TILE_MARGIN: f32 = 10.0;
+
+impl Maze {
+    /// Draw the walls as vector strokes on a single-page PDF document
+    pub fn to_pdf(&self, options: &PdfOptions) -> Vec<u8> {
+        let (page_width, page_height) = (
+            options.page_width * PT_PER_MM,
+            options.page_height * PT_PER_MM,
+        );
+        let scale = options.cell_size * PT_PER_MM;
+        let left = (page_width - self.width as f32 * scale) / 2.0;
+        let top = (page_height - self.height as f32 * scale) / 2.0;
+        // PDF's y axis points up from the bottom of the page
+        let point = |(x, y): (f32, f32)| (left + x * scale, page_height - top - y * scale);
+
+        let content = wall_strokes(options, &merge_collinear(self.wall_segments()), point);
+        document(page_width, page_height, &[content])
+    }
+
+    /// Split a maze too large for one page across as many pages as it takes at the cell size
+    /// given, to be taped together into a poster. Neighboring pages repeat `overlap` millimeters
+    /// of the maze, marked with a dashed line where the next page starts, and each page is
+    /// labeled with its row and column. Fails with [`Error::PageSize`] if the pages have no room
+    /// inside their 10 mm margins.
+    pub fn to_tiled_pdf(&self, options: &PdfOptions, overlap: f32) -> Result<Vec<u8>, Error> {
+        let page_height = options.page_height * PT_PER_MM;
+        let (window_width, window_height) = (
+            options.page_width - 2.0 * TILE_MARGIN,
+            options.page_height - 2.0 * TILE_MARGIN,
+        );
+        // Written so that NaN sizes are rejected too
+        if !(window_width > 0.0 && window_height > 0.0) {
+            return Err(Error::PageSize {
+                width: options.page_width,
+                height: options.page_height,
+            });
+        }
+        let overlap = if overlap.is_nan() { 0.0 } else { overlap };
+        let (maze_width, maze_height) = (
+            self.width as f32 * options.cell_size,
+            self.height as f32 * options.cell_size,
+        );
+        let overlap = overlap.clamp(0.0, window_width.min(window_height) / 2.0);
+        let (step_x, step_y) = (window_width - overlap, window_height - overlap);
+        let count = |length: f32, window: f32, step: f32| {
+            ((length - window).max(0.0) / step).ceil() as usize + 1
+        };
+        let columns = count(maze_width, window_width, step_x);
+        let rows = count(maze_height, window_height, step_y);
+
+        // The part of the maze on a page fills it inside the margins
+        let margin = TILE_MARGIN * PT_PER_MM;
+        let (clip_width, clip_height) = (window_width * PT_PER_MM, window_height * PT_PER_MM);
+        let overlap_pt = overlap * PT_PER_MM;
+        let segments = merge_collinear(self.wall_segments());
+        let mut pages = Vec::with_capacity(rows * columns);
+        for row in 0..rows {
+            for column in 0..columns {
+                let (origin_x, origin_y) = (column as f32 * step_x, row as f32 * step_y);
+                let point = |(x, y): (f32, f32)| {
+                    (
+                        (TILE_MARGIN + x * options.cell_size - origin_x) * PT_PER_MM,
+                        page_height - (TILE_MARGIN + y * options.cell_size - origin_y) * PT_PER_MM,
+                    )
+                };
+
+                let mut content = String::new();
+                writeln!(
+                    content,
+                    "BT /F1 9 Tf {margin:.3} {:.3} Td (Row {} of {rows}, column {} of {columns}) Tj ET",
+                    page_height - margin / 2.0,
+                    row + 1,
+                    column + 1
+                )
+                .unwrap();
+                // Mark where the next pages to the right and below start
+                writeln!(content, "0.3 w 0.6 G [3 3] 0 d").unwrap();
+                if column + 1 < columns {
+                    let x = margin + clip_width - overlap_pt;
+                    writeln!(
+                        content,
+                        "{x:.3} {margin:.3} m {x:.3} {:.3} l S",
+                        margin + clip_height
+                    )
+                    .unwrap();
+                }
+                if row + 1 < rows {
+                    let y = margin + overlap_pt;
+                    writeln!(
+                        content,
+                        "{margin:.3} {y:.3} m {:.3} {y:.3} l S",
+                        margin + clip_width
+                    )
+                    .unwrap();
+                }
+                writeln!(
+                    content,
+                    "[] 0 d {margin:.3} {margin:.3} {clip_width:.3} {clip_height:.3} re W n"
+                )
+                .unwrap();
+                // Leave out the walls that are nowhere near this page
+                let (left, top) = (origin_x / options.cell_size, origin_y / options.cell_size);
+                let (right, bottom) = (
+                    left + window_width / options.cell_size,
+                    top + window_height / options.cell_size,
+                );
+                let visible: Vec<Segment> = segments
+                    .iter()
+                    .filter(|s| {
+                        s.from.0.min(s.to.0) <= right
+                            && s.from.0.max(s.to.0) >= left
+                            && s.from.1.min(s.to.1) <= bottom
+                            && s.from.1.max(s.to.1) >= top
+                    })
+                    .copied()
+                    .collect();
+                content.push_str(&wall_strokes(options, &visible, point));
+                pages.push(content);
+            }
+        }
+        Ok(document(
+            options.page_width * PT_PER_MM,
+            page_height,
+            &pages,
+        ))
+    }
+}
+
+/// Content stream that strokes `segments`, with `point` taking maze coordinates to points on
+/// the page
+fn wall_strokes(
+    options: &PdfOptions,
+    segments: &[Segment],
+    point: impl Fn((f32, f32)) -> (f32, f32),
+) -> String {
+    let mut content = String::new();
+    writeln!(
+        content,
+        "{:.3} w 2 J 0 0 0 RG",
+        options.stroke_width * PT_PER_MM
+    )
+    .unwrap();
+    for segment in segments {
+        let (from, to) = (point(segment.from), point(segment.to));
+        writeln!(
+            content,
+            "{:.3} {:.3} m {:.3} {:.3} l S",
+            from.0, from.1, to.0, to.1
+        )
+        .unwrap();
+    }
+    content
+}
+
+/// A PDF document with one page of the size given, in points, for each content stream in
+/// `pages`. Pages can set text in Helvetica as `/F1`.
+fn document(page_width: f32, page_height: f32, pages: &[String]) -> Vec<u8> {
+    // The catalog, page tree and font come first, then each page and its contents
+    let kids: Vec<String> = (0..pages.len())
+        .map(|k| format!("{} 0 R", 4 + 2 * k))
+        .collect();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_owned(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_owned(),
+    ];
+    for (k, content) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {page_width:.3} {page_height:.3}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            5 + 2 * k
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{content}endstream",
+            content.len()
+        ));
+    }
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        writeln!(pdf, "{} 0 obj\n{object}\nendobj", i + 1).unwrap();
+    }
+    let xref = pdf.len();
+    writeln!(pdf, "xref\n0 {}", objects.len() + 1).unwrap();
+    pdf.push_str("0000000000 65535 f \n");
+    for offset in offsets {
+        writeln!(pdf, "{offset:010} 00000 n ").unwrap();
+    }
+    writeln!(
+        pdf,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF",
+        objects.len() + 1
+    )
+    .unwrap();
+    pdf.into_bytes()
+}
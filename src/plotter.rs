@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::maze::{Maze, Segment};
+
+/// HPGL plotter units per millimeter
+const HPGL_UNITS_PER_MM: f32 = 40.0;
+
+type Point = (f32, f32);
+
+/// The walls of a maze arranged for a pen plotter. Wall segments are merged into the minimum
+/// number of continuous strokes and the strokes are ordered so that pen-up travel is short.
+#[derive(Debug)]
+pub struct Plot {
+    width: u32,
+    height: u32,
+    strokes: Vec<Vec<Point>>,
+}
+
+impl Plot {
+    pub fn new(maze: &Maze) -> Self {
+        let trails = trails(&maze.wall_segments().collect::<Vec<_>>());
+        let strokes = order(maze.width, maze.height, trails)
+            .into_iter()
+            .map(simplify)
+            .collect();
+        Self {
+            width: maze.width,
+            height: maze.height,
+            strokes,
+        }
+    }
+
+    /// The strokes in plotting order. Each stroke is a polyline of grid points that is drawn
+    /// without lifting the pen.
+    pub fn strokes(&self) -> &[Vec<Point>] {
+        &self.strokes
+    }
+
+    /// Total distance travelled with the pen up, in cells, starting from the origin
+    pub fn pen_up_distance(&self) -> f64 {
+        let mut pen = (0.0, 0.0);
+        let mut distance = 0.0;
+        for stroke in self.strokes.iter() {
+            distance += euclidean(pen, stroke[0]);
+            pen = *stroke.last().unwrap();
+        }
+        distance
+    }
+
+    pub fn to_svg(&self, cell_size_mm: f32) -> String {
+        let width = self.width as f32 * cell_size_mm;
+        let height = self.height as f32 * cell_size_mm;
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}mm" height="{height}mm" viewBox="0 0 {width} {height}">"#
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<g fill="none" stroke="black" stroke-width="0.3" stroke-linecap="round" stroke-linejoin="round">"#
+        )
+        .unwrap();
+        for stroke in self.strokes.iter() {
+            svg.push_str(r#"<polyline points=""#);
+            for (i, (x, y)) in stroke.iter().enumerate() {
+                if i > 0 {
+                    svg.push(' ');
+                }
+                write!(svg, "{},{}", x * cell_size_mm, y * cell_size_mm).unwrap();
+            }
+            svg.push_str("\"/>\n");
+        }
+        svg.push_str("</g>\n</svg>\n");
+        svg
+    }
+
+    pub fn to_hpgl(&self, cell_size_mm: f32) -> String {
+        let scale = cell_size_mm * HPGL_UNITS_PER_MM;
+        // HPGL's y axis points up, so flip the maze vertically to keep north at the top
+        let coord = |(x, y): Point| {
+            (
+                (x * scale).round() as i64,
+                ((self.height as f32 - y) * scale).round() as i64,
+            )
+        };
+        let mut hpgl = String::from("IN;SP1;\n");
+        for stroke in self.strokes.iter() {
+            let (x, y) = coord(stroke[0]);
+            write!(hpgl, "PU{x},{y};PD").unwrap();
+            for (i, point) in stroke[1..].iter().enumerate() {
+                let (x, y) = coord(*point);
+                if i > 0 {
+                    hpgl.push(',');
+                }
+                write!(hpgl, "{x},{y}").unwrap();
+            }
+            hpgl.push_str(";\n");
+        }
+        hpgl.push_str("PU;SP0;\n");
+        hpgl
+    }
+}
+
+/// Decompose the wall segments into the minimum number of trails, where each trail is a
+/// sequence of grid points connected by segments.
+///
+/// Every pair of odd-degree points is joined by a virtual (pen-up) edge so that each connected
+/// piece of the drawing has an Euler circuit. The circuits are found with Hierholzer's algorithm
+/// and then split apart at the virtual edges.
+fn trails(segments: &[Segment]) -> Vec<Vec<Point>> {
+    // Segment ends are computed the same way wherever walls meet, so exact comparison of the
+    // coordinates is enough to find shared points
+    let mut points: Vec<Point> = Vec::new();
+    let mut indexes: HashMap<(u32, u32), usize> = HashMap::new();
+    let mut index = |p: Point| {
+        *indexes
+            .entry((p.0.to_bits(), p.1.to_bits()))
+            .or_insert_with(|| {
+                points.push(p);
+                points.len() - 1
+            })
+    };
+    // Edges are (point, point, is_virtual)
+    let mut edges: Vec<(usize, usize, bool)> = segments
+        .iter()
+        .map(|s| (index(s.from), index(s.to), false))
+        .collect();
+    let point_count = points.len();
+    let point = |idx: usize| points[idx];
+
+    let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); point_count];
+    for (id, (a, b, _)) in edges.iter().enumerate() {
+        adjacency[*a].push(id);
+        adjacency[*b].push(id);
+    }
+    let odd: Vec<usize> = (0..point_count)
+        .filter(|idx| adjacency[*idx].len() % 2 == 1)
+        .collect();
+    for pair in odd.chunks(2) {
+        let id = edges.len();
+        edges.push((pair[0], pair[1], true));
+        adjacency[pair[0]].push(id);
+        adjacency[pair[1]].push(id);
+    }
+
+    let mut used = vec![false; edges.len()];
+    let mut next_edge = vec![0usize; point_count];
+    let mut trails = Vec::new();
+    for start in 0..point_count {
+        // Each entry is a point and the edge that connects it to the next point in the circuit
+        let mut stack: Vec<(usize, Option<usize>)> = vec![(start, None)];
+        let mut circuit: Vec<(usize, Option<usize>)> = Vec::new();
+        while let Some(&(curr, _)) = stack.last() {
+            let mut unused = None;
+            while next_edge[curr] < adjacency[curr].len() {
+                let id = adjacency[curr][next_edge[curr]];
+                next_edge[curr] += 1;
+                if !used[id] {
+                    unused = Some(id);
+                    break;
+                }
+            }
+            match unused {
+                Some(id) => {
+                    used[id] = true;
+                    let (a, b, _) = edges[id];
+                    stack.push((if a == curr { b } else { a }, Some(id)));
+                }
+                None => circuit.push(stack.pop().unwrap()),
+            }
+        }
+        if circuit.len() < 2 {
+            continue;
+        }
+
+        let first = trails.len();
+        let mut trail = vec![point(circuit[0].0)];
+        for window in circuit.windows(2) {
+            let (_, edge) = window[0];
+            if edges[edge.unwrap()].2 {
+                trails.push(std::mem::take(&mut trail));
+            }
+            trail.push(point(window[1].0));
+        }
+        trails.push(trail);
+
+        // The circuit is closed, so unless it was split right at its starting point the last
+        // trail continues into the first one
+        let wraps = circuit[circuit.len() - 1].0 == circuit[0].0;
+        let split_at_start = circuit
+            .iter()
+            .rev()
+            .nth(1)
+            .and_then(|(_, edge)| *edge)
+            .is_some_and(|id| edges[id].2);
+        if wraps && !split_at_start && trails.len() - first > 1 {
+            let last = trails.pop().unwrap();
+            let mut joined = last;
+            joined.extend_from_slice(&trails[first][1..]);
+            trails[first] = joined;
+        }
+    }
+    trails.retain(|trail| trail.len() > 1);
+    trails
+}
+
+/// Order the trails greedily, always moving the pen to the closest unplotted trail endpoint and
+/// reversing the trail if it is entered from its end
+fn order(width: u32, height: u32, trails: Vec<Vec<Point>>) -> Vec<Vec<Point>> {
+    // Bucket endpoints by the cell corner at or before them so that the nearest endpoint can be
+    // found by searching outward from the pen
+    let cols = width as usize + 1;
+    let rows = height as usize + 1;
+    let bucket = |(x, y): Point| (y as usize).min(rows - 1) * cols + (x as usize).min(cols - 1);
+
+    let mut endpoints: Vec<Vec<usize>> = vec![Vec::new(); cols * rows];
+    for (id, trail) in trails.iter().enumerate() {
+        endpoints[bucket(trail[0])].push(id);
+        endpoints[bucket(*trail.last().unwrap())].push(id);
+    }
+
+    let mut trails: Vec<Option<Vec<Point>>> = trails.into_iter().map(Some).collect();
+    let mut ordered = Vec::with_capacity(trails.len());
+    let mut pen: Point = (0.0, 0.0);
+    for _ in 0..trails.len() {
+        let Some(nearest) = nearest_endpoint(pen, cols, rows, &mut endpoints, &trails) else {
+            break;
+        };
+        let mut trail = trails[nearest].take().unwrap();
+        if euclidean(pen, trail[0]) > euclidean(pen, *trail.last().unwrap()) {
+            trail.reverse();
+        }
+        pen = *trail.last().unwrap();
+        ordered.push(trail);
+    }
+    ordered
+}
+
+/// Search outward from `from` in square rings of buckets for the closest endpoint of an
+/// unplotted trail
+fn nearest_endpoint(
+    from: Point,
+    cols: usize,
+    rows: usize,
+    endpoints: &mut [Vec<usize>],
+    trails: &[Option<Vec<Point>>],
+) -> Option<usize> {
+    let (cx, cy) = (from.0 as i64, from.1 as i64);
+    let max_radius = cols.max(rows) as i64;
+    let mut best: Option<(f64, usize)> = None;
+    for radius in 0..=max_radius {
+        // Every endpoint in this ring or beyond is at least `radius - 1` away
+        if best.is_some_and(|(dist, _)| dist <= (radius - 1) as f64) {
+            break;
+        }
+        let mut visit = |x: i64, y: i64| {
+            if x < 0 || y < 0 || x >= cols as i64 || y >= rows as i64 {
+                return;
+            }
+            let ids = &mut endpoints[y as usize * cols + x as usize];
+            ids.retain(|id| trails[*id].is_some());
+            for id in ids.iter() {
+                let trail = trails[*id].as_ref().unwrap();
+                let dist = euclidean(from, trail[0]).min(euclidean(from, *trail.last().unwrap()));
+                if best.is_none_or(|(best_dist, _)| dist < best_dist) {
+                    best = Some((dist, *id));
+                }
+            }
+        };
+        if radius == 0 {
+            visit(cx, cy);
+            continue;
+        }
+        for dx in -radius..=radius {
+            visit(cx + dx, cy - radius);
+            visit(cx + dx, cy + radius);
+        }
+        for dy in (1 - radius)..radius {
+            visit(cx - radius, cy + dy);
+            visit(cx + radius, cy + dy);
+        }
+    }
+    best.map(|(_, id)| id)
+}
+
+/// Drop the interior points of straight runs so that a stroke only lists its corners
+fn simplify(trail: Vec<Point>) -> Vec<Point> {
+    let mut simplified: Vec<Point> = Vec::with_capacity(trail.len());
+    for point in trail {
+        if simplified.len() >= 2 {
+            let a = simplified[simplified.len() - 2];
+            let b = simplified[simplified.len() - 1];
+            let collinear = (a.0 == b.0 && b.0 == point.0) || (a.1 == b.1 && b.1 == point.1);
+            if collinear {
+                *simplified.last_mut().unwrap() = point;
+                continue;
+            }
+        }
+        simplified.push(point);
+    }
+    simplified
+}
+
+fn euclidean(a: Point, b: Point) -> f64 {
+    let dx = f64::from(a.0 - b.0);
+    let dy = f64::from(a.1 - b.1);
+    (dx * dx + dy * dy).sqrt()
+}
@@ -0,0 +1,91 @@
+/// The eight bytes every PNG file starts with
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// Add a `tEXt` chunk for each keyword and value in `entries` to the encoded PNG `png`, right
+/// after its header, where image viewers and tools such as `exiftool` show them. Keywords must
+/// be 1 to 79 Latin-1 characters; characters outside Latin-1 are replaced with `?` in both
+/// keywords and values.
+///
+/// # Panics
+///
+/// If `png` does not start with a PNG signature and header chunk, as the output of
+/// [`tiny_skia::Pixmap::encode_png`] does.
+pub fn with_text(png: &[u8], entries: &[(&str, &str)]) -> Vec<u8> {
+    assert!(
+        png.starts_with(&SIGNATURE) && png.get(12..16) == Some(b"IHDR"),
+        "not a PNG image"
+    );
+    // The header has 13 bytes of data between its length and type and its checksum
+    let header_end = SIGNATURE.len() + 8 + 13 + 4;
+    let mut out = png[..header_end].to_vec();
+    for (keyword, value) in entries {
+        assert!(
+            (1..80).contains(&keyword.chars().count()),
+            "PNG keywords must be 1 to 79 characters"
+        );
+        let mut data = latin1(keyword);
+        data.push(0);
+        data.extend(latin1(value));
+        push_chunk(&mut out, b"tEXt", &data);
+    }
+    out.extend_from_slice(&png[header_end..]);
+    out
+}
+
+/// Every keyword and value stored in the `tEXt` chunks of the PNG `png`, in the order they
+/// appear. Chunks that are cut off or have no keyword are skipped.
+pub fn text(png: &[u8]) -> Vec<(String, String)> {
+    let mut entries = Vec::new();
+    if !png.starts_with(&SIGNATURE) {
+        return entries;
+    }
+    let mut rest = &png[SIGNATURE.len()..];
+    while rest.len() >= 12 {
+        let length = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+        let kind = &rest[4..8];
+        let Some(data) = rest.get(8..8 + length) else {
+            break;
+        };
+        if kind == b"tEXt" {
+            if let Some(split) = data.iter().position(|b| *b == 0).filter(|at| *at > 0) {
+                let decode = |bytes: &[u8]| bytes.iter().map(|b| *b as char).collect();
+                entries.push((decode(&data[..split]), decode(&data[split + 1..])));
+            }
+        }
+        if kind == b"IEND" {
+            break;
+        }
+        rest = rest.get(12 + length..).unwrap_or_default();
+    }
+    entries
+}
+
+fn latin1(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| u8::try_from(c).unwrap_or(b'?'))
+        .collect()
+}
+
+/// Append a chunk of type `kind` holding `data`, with its length and checksum
+fn push_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// The CRC-32 checksum that PNG chunks end with, computed over their type and data, and that
+/// gzip streams end with
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
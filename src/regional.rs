@@ -0,0 +1,139 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use rand::prelude::*;
+
+use crate::disjoint_set::DisjointSet;
+use crate::generator::MazeGenerator;
+use crate::grid::{Direction, RectGrid};
+use crate::kruskal;
+use crate::maze::Maze;
+
+/// A rectangle of cells, with `x, y` at its top left corner
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Region {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Region {
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+}
+
+/// [`MazeGenerator`] with the random number generator as a trait object, so that generators of
+/// different types can be stored together
+trait DynGenerator {
+    fn generate_dyn(&self, width: usize, height: usize, rng: &mut dyn RngCore) -> Maze;
+}
+
+impl<G: MazeGenerator> DynGenerator for G {
+    fn generate_dyn(&self, width: usize, height: usize, rng: &mut dyn RngCore) -> Maze {
+        self.generate(width, height, rng)
+    }
+}
+
+/// Generates mazes where different rectangular regions of the grid are carved by different
+/// algorithms, so that each zone keeps its own texture.
+///
+/// The whole grid belongs to a fill generator unless it is covered by a region, and regions
+/// added later take precedence where they overlap. Each generator carves a maze the size of its
+/// region and the passages between cells it owns are copied over. The regions are then knitted
+/// together Kruskal-style, by opening random walls between cells that are not yet connected,
+/// which keeps the result a perfect maze.
+pub struct Generator {
+    fill: Box<dyn DynGenerator>,
+    regions: Vec<(Region, Box<dyn DynGenerator>)>,
+}
+
+impl Generator {
+    /// A generator that carves the whole grid with `fill`
+    pub fn new<G: MazeGenerator + 'static>(fill: G) -> Self {
+        Self {
+            fill: Box::new(fill),
+            regions: Vec::new(),
+        }
+    }
+
+    /// Carve `region` with `generator`. Parts of the region outside the grid are ignored.
+    pub fn region<G: MazeGenerator + 'static>(mut self, region: Region, generator: G) -> Self {
+        self.regions.push((region, Box::new(generator)));
+        self
+    }
+}
+
+impl core::fmt::Debug for Generator {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let regions: Vec<&Region> = self.regions.iter().map(|(region, _)| region).collect();
+        f.debug_struct("Generator")
+            .field("regions", &regions)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MazeGenerator for Generator {
+    fn generate<R: Rng + ?Sized>(&self, width: usize, height: usize, mut rng: &mut R) -> Maze {
+        let grid = RectGrid::new(width, height);
+        let mut maze = Maze::new(width as u32, height as u32);
+
+        // Region 0 is the fill, which covers the whole grid
+        let fill = (Region::new(0, 0, width, height), &self.fill);
+        let regions: Vec<(Region, &Box<dyn DynGenerator>)> = core::iter::once(fill)
+            .chain(self.regions.iter().map(|(region, g)| (*region, g)))
+            .collect();
+        let mut owner = vec![0; grid.len()];
+        for (id, (region, _)) in regions.iter().enumerate() {
+            for y in region.y..(region.y + region.height).min(height) {
+                for x in region.x..(region.x + region.width).min(width) {
+                    owner[grid.index(x, y)] = id;
+                }
+            }
+        }
+
+        let mut sets = DisjointSet::new(grid.len());
+        for (id, (region, generator)) in regions.iter().enumerate() {
+            let region_width = region.width.min(width.saturating_sub(region.x));
+            let region_height = region.height.min(height.saturating_sub(region.y));
+            if region_width == 0 || region_height == 0 {
+                continue;
+            }
+            let carved = generator.generate_dyn(region_width, region_height, &mut rng);
+            let local = RectGrid::new(region_width, region_height);
+            for local_idx in 0..local.len() {
+                let (lx, ly) = local.coords(local_idx);
+                let idx = grid.index(region.x + lx, region.y + ly);
+                let cell = carved.cell(lx, ly);
+                for (open, direction) in [
+                    (cell.north_open(), Direction::North),
+                    (cell.west_open(), Direction::West),
+                ] {
+                    let Some(other) = grid.adjacent_index(idx, direction) else {
+                        continue;
+                    };
+                    // Passages into cells that a later region took over are dropped, and the
+                    // cells are reconnected when the regions are knitted together
+                    if open && owner[idx] == id && owner[other] == id {
+                        maze.open_wall(idx, direction);
+                        sets.union(idx, other);
+                    }
+                }
+            }
+        }
+
+        kruskal::knit(&mut maze, &mut sets, rng);
+        maze
+    }
+}
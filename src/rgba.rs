@@ -0,0 +1,76 @@
+use tiny_skia::Pixmap;
+
+use crate::error::Error;
+use crate::maze::{Maze, RenderOptions};
+
+/// A drawn image as straight 8-bit RGBA pixels, row by row from the top left, the way image
+/// libraries, GUI toolkits and video encoders usually take them. A [`Pixmap`] keeps its colors
+/// premultiplied by their alpha instead, which [`Pixmap::data`] gives as is.
+///
+/// ```
+/// use dadalus::maze::RenderOptions;
+/// use dadalus::MazeGenerator;
+///
+/// let maze = dadalus::Generator.generate_seeded(20, 10, 1);
+/// let image = maze.draw_rgba(&RenderOptions::new(10))?;
+/// assert_eq!(image.as_bytes().len(), 4 * 200 * 100);
+/// # Ok::<(), dadalus::Error>(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rgba {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl Rgba {
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Four bytes for each pixel: red, green, blue and alpha
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_vec(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+impl From<&Pixmap> for Rgba {
+    fn from(pixmap: &Pixmap) -> Self {
+        let data = pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect();
+        Self {
+            width: pixmap.width(),
+            height: pixmap.height(),
+            data,
+        }
+    }
+}
+
+#[cfg(feature = "image")]
+impl From<Rgba> for image::RgbaImage {
+    fn from(rgba: Rgba) -> Self {
+        image::RgbaImage::from_raw(rgba.width, rgba.height, rgba.data)
+            .expect("four bytes for every pixel")
+    }
+}
+
+impl Maze {
+    /// Draw the maze as [`Maze::draw`] does, as straight RGBA pixels
+    pub fn draw_rgba(&self, options: &RenderOptions) -> Result<Rgba, Error> {
+        self.draw(options).map(|pixmap| Rgba::from(&pixmap))
+    }
+}
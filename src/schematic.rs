@@ -0,0 +1,154 @@
+use crate::maze::Maze;
+use crate::png::crc32;
+
+/// The Minecraft data version written into schematics, that of Java Edition 1.20.1. Newer
+/// versions of the game and of WorldEdit upgrade older schematics when they load them.
+const DATA_VERSION: i32 = 3465;
+
+/// Blocks and sizes for [`Maze::to_schematic`]
+#[derive(Clone, Debug)]
+pub struct SchematicOptions {
+    /// Block that walls are built from, as a namespaced block state such as
+    /// `minecraft:stone_bricks`
+    pub material: String,
+    /// Height of the walls in blocks
+    pub wall_height: u16,
+    /// Block to lay a floor of under the whole maze, or `None` to build only the walls
+    pub floor: Option<String>,
+    /// Width of the passages in blocks. Walls are always one block thick.
+    pub passage_width: u16,
+}
+
+impl Default for SchematicOptions {
+    fn default() -> Self {
+        Self {
+            material: "minecraft:stone_bricks".to_owned(),
+            wall_height: 3,
+            floor: None,
+            passage_width: 1,
+        }
+    }
+}
+
+impl Maze {
+    /// Build the walls out of blocks as a Sponge schematic (version 2), the `.schem` files that
+    /// WorldEdit, Litematica and other Minecraft tools paste into a world. North in the maze is
+    /// north in the game.
+    ///
+    /// # Panics
+    ///
+    /// If the maze is wider or longer than the 65535 blocks a schematic can hold.
+    pub fn to_schematic(&self, options: &SchematicOptions) -> Vec<u8> {
+        let passage = options.passage_width.max(1) as usize;
+        // Every cell is one block of wall line and then the passage, along both axes
+        let line_of = |length: usize| -> Vec<usize> {
+            (0..=2 * length)
+                .flat_map(|k| {
+                    let repeat = if k % 2 == 0 { 1 } else { passage };
+                    std::iter::repeat_n(k, repeat)
+                })
+                .collect()
+        };
+        let (columns, rows) = (line_of(self.width as usize), line_of(self.height as usize));
+        let floor_height = options.floor.is_some() as usize;
+        let height = floor_height + options.wall_height as usize;
+        let size = |blocks: usize| u16::try_from(blocks).expect("maze too large for a schematic");
+        let (width, length) = (size(columns.len()), size(rows.len()));
+
+        // Palette indices below 128 are a single byte in the varint encoding of the block data
+        let (air, wall, floor) = (0u8, 1u8, 2u8);
+        let mut blocks = Vec::with_capacity(columns.len() * rows.len() * height);
+        for y in 0..height {
+            for row in &rows {
+                for column in &columns {
+                    let block = if y < floor_height {
+                        floor
+                    } else if self.width > 0 && self.height > 0 && self.wall_at(*column, *row) {
+                        wall
+                    } else {
+                        air
+                    };
+                    blocks.push(block);
+                }
+            }
+        }
+
+        let mut nbt = Nbt::default();
+        nbt.compound("Schematic");
+        nbt.int("Version", 2);
+        nbt.int("DataVersion", DATA_VERSION);
+        nbt.short("Width", width);
+        nbt.short("Height", size(height));
+        nbt.short("Length", length);
+        nbt.int("PaletteMax", 2 + floor_height as i32);
+        nbt.compound("Palette");
+        nbt.int("minecraft:air", air.into());
+        nbt.int(&options.material, wall.into());
+        if let Some(material) = &options.floor {
+            nbt.int(material, floor.into());
+        }
+        nbt.end();
+        nbt.byte_array("BlockData", &blocks);
+        nbt.end();
+        gzip(&nbt.0)
+    }
+}
+
+/// Big-endian named binary tags, the format of Minecraft's data files
+#[derive(Default)]
+struct Nbt(Vec<u8>);
+
+impl Nbt {
+    fn tag(&mut self, kind: u8, name: &str) {
+        self.0.push(kind);
+        self.0.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        self.0.extend_from_slice(name.as_bytes());
+    }
+
+    fn short(&mut self, name: &str, value: u16) {
+        self.tag(2, name);
+        self.0.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn int(&mut self, name: &str, value: i32) {
+        self.tag(3, name);
+        self.0.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn byte_array(&mut self, name: &str, bytes: &[u8]) {
+        self.tag(7, name);
+        self.0
+            .extend_from_slice(&(bytes.len() as i32).to_be_bytes());
+        self.0.extend_from_slice(bytes);
+    }
+
+    /// Open a compound tag, which holds every tag after it up to the matching [`Nbt::end`]
+    fn compound(&mut self, name: &str) {
+        self.tag(10, name);
+    }
+
+    fn end(&mut self) {
+        self.0.push(0);
+    }
+}
+
+/// Wrap `data` in a gzip stream, as Minecraft expects NBT files to be. The data is stored
+/// without compression, which every gzip reader accepts.
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff];
+    let mut chunks = data.chunks(u16::MAX as usize).peekable();
+    if chunks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(chunk) = chunks.next() {
+        let last = chunks.peek().is_none();
+        let len = chunk.len() as u16;
+        out.push(last as u8);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(chunk);
+    }
+    out.extend_from_slice(&crc32(data).to_le_bytes());
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out
+}
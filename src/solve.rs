@@ -0,0 +1,382 @@
+use alloc::collections::{BTreeSet, BinaryHeap, VecDeque};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+use crate::grid::Direction;
+use crate::maze::Maze;
+
+/// A route through a maze, as the coordinates of the cells it passes through from start to end.
+/// Consecutive cells are neighbors, except where the route goes through a tunnel and skips the
+/// cell it passes under.
+///
+/// Routes are measured in moves, one less than their number of cells. A move to a neighbor and a
+/// move through a tunnel count the same, and the shortest route that [`Maze::solve`],
+/// [`Maze::solve_astar`] and [`Maze::k_shortest_paths`] find is the one with the fewest moves.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Path {
+    cells: Vec<(usize, usize)>,
+}
+
+impl Path {
+    pub fn new(cells: Vec<(usize, usize)>) -> Self {
+        Self { cells }
+    }
+
+    pub fn cells(&self) -> &[(usize, usize)] {
+        &self.cells
+    }
+
+    /// Number of cells on the path, including the start and the end
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn start(&self) -> Option<(usize, usize)> {
+        self.cells.first().copied()
+    }
+
+    pub fn end(&self) -> Option<(usize, usize)> {
+        self.cells.last().copied()
+    }
+}
+
+impl Maze {
+    /// The shortest path from the entrance to the exit, as [`Maze::solve`] finds it. Returns
+    /// `None` if the maze has no entrance or no exit, or the exit cannot be reached.
+    pub fn solution(&self) -> Option<Path> {
+        let (entrance, exit) = (self.entrance()?, self.exit()?);
+        self.solve((entrance.x, entrance.y), (exit.x, exit.y))
+    }
+
+    /// The shortest path from the cell at `start` to the cell at `end`, found with a breadth-first
+    /// search through open walls and tunnels. Returns `None` if either cell is outside of the
+    /// maze or `end` cannot be reached.
+    pub fn solve(&self, start: (usize, usize), end: (usize, usize)) -> Option<Path> {
+        let grid = self.grid();
+        if !grid.contains(start.0, start.1) || !grid.contains(end.0, end.1) {
+            return None;
+        }
+        let (start, end) = (grid.index(start.0, start.1), grid.index(end.0, end.1));
+        self.shortest(start, end, &BTreeSet::new(), &BTreeSet::new())
+            .map(|cells| Path::new(cells.into_iter().map(|idx| grid.coords(idx)).collect()))
+    }
+
+    /// Up to `k` of the shortest paths from `start` to `end` that never visit a cell twice,
+    /// shortest first, found with Yen's algorithm. Paths of the same length are ordered by the
+    /// cells they pass through. A perfect maze has only one such path, while a braided maze may
+    /// have many.
+    pub fn k_shortest_paths(
+        &self,
+        start: (usize, usize),
+        end: (usize, usize),
+        k: usize,
+    ) -> Vec<Path> {
+        let grid = self.grid();
+        if !grid.contains(start.0, start.1) || !grid.contains(end.0, end.1) || k == 0 {
+            return Vec::new();
+        }
+        let (start, end) = (grid.index(start.0, start.1), grid.index(end.0, end.1));
+        let Some(first) = self.shortest(start, end, &BTreeSet::new(), &BTreeSet::new()) else {
+            return Vec::new();
+        };
+
+        let mut found = vec![first];
+        let mut candidates: Vec<Vec<usize>> = Vec::new();
+        while found.len() < k {
+            let last = found.last().unwrap();
+            // Branch off the last path found at each of its cells in turn, keeping the part
+            // before the branch and avoiding every way out of it that is already taken
+            for spur in 0..last.len() - 1 {
+                let root = &last[..=spur];
+                let blocked_steps: BTreeSet<(usize, usize)> = found
+                    .iter()
+                    .filter(|path| path.len() > spur + 1 && path[..=spur] == *root)
+                    .map(|path| (path[spur], path[spur + 1]))
+                    .collect();
+                let blocked_cells: BTreeSet<usize> = root[..spur].iter().copied().collect();
+                if let Some(rest) = self.shortest(last[spur], end, &blocked_cells, &blocked_steps) {
+                    let path: Vec<usize> = root[..spur].iter().copied().chain(rest).collect();
+                    if !candidates.contains(&path) && !found.contains(&path) {
+                        candidates.push(path);
+                    }
+                }
+            }
+            let Some(best) = (0..candidates.len()).min_by(|a, b| {
+                (candidates[*a].len(), &candidates[*a])
+                    .cmp(&(candidates[*b].len(), &candidates[*b]))
+            }) else {
+                break;
+            };
+            found.push(candidates.swap_remove(best));
+        }
+        found
+            .into_iter()
+            .map(|cells| Path::new(cells.into_iter().map(|idx| grid.coords(idx)).collect()))
+            .collect()
+    }
+
+    /// Breadth-first search from `start` to `end` that stays out of `blocked_cells` and does not
+    /// take any of the `blocked_steps` from one cell to the next
+    fn shortest(
+        &self,
+        start: usize,
+        end: usize,
+        blocked_cells: &BTreeSet<usize>,
+        blocked_steps: &BTreeSet<(usize, usize)>,
+    ) -> Option<Vec<usize>> {
+        let mut previous = vec![usize::MAX; self.grid().len()];
+        previous[start] = start;
+        let mut queue = VecDeque::from([start]);
+        while let Some(idx) = queue.pop_front() {
+            if idx == end {
+                break;
+            }
+            for next in self.passages(idx) {
+                if previous[next] == usize::MAX
+                    && !blocked_cells.contains(&next)
+                    && !blocked_steps.contains(&(idx, next))
+                {
+                    previous[next] = idx;
+                    queue.push_back(next);
+                }
+            }
+        }
+        trace(&previous, start, end)
+    }
+
+    /// The shortest path from `start` to `end` found with A* search and the [`Manhattan`]
+    /// heuristic, which visits far fewer cells than [`Maze::solve`] when the route is fairly
+    /// direct. A move through a tunnel crosses two cells, so in a maze with tunnels half of the
+    /// Manhattan distance is used instead, which never overestimates.
+    pub fn solve_astar(&self, start: (usize, usize), end: (usize, usize)) -> Option<Path> {
+        if self.iter_cells().any(|cell| cell.tunnel()) {
+            let half = |from, to| Manhattan.estimate(from, to).div_ceil(2);
+            self.solve_astar_with(start, end, &half)
+        } else {
+            self.solve_astar_with(start, end, &Manhattan)
+        }
+    }
+
+    /// The shortest path from `start` to `end` found with A* search guided by `heuristic`. Every
+    /// move costs the same, including one through a tunnel, as for [`Maze::solve`]. Returns
+    /// `None` if either cell is outside of the maze or `end` cannot be reached.
+    pub fn solve_astar_with<H: Heuristic + ?Sized>(
+        &self,
+        start: (usize, usize),
+        end: (usize, usize),
+        heuristic: &H,
+    ) -> Option<Path> {
+        let grid = self.grid();
+        if !grid.contains(start.0, start.1) || !grid.contains(end.0, end.1) {
+            return None;
+        }
+        let target = end;
+        let (start, end) = (grid.index(start.0, start.1), grid.index(end.0, end.1));
+
+        let mut previous = vec![usize::MAX; grid.len()];
+        let mut cost = vec![usize::MAX; grid.len()];
+        previous[start] = start;
+        cost[start] = 0;
+        let mut open = BinaryHeap::from([Reverse((
+            heuristic.estimate(grid.coords(start), target),
+            start,
+        ))]);
+        while let Some(Reverse((_, idx))) = open.pop() {
+            if idx == end {
+                break;
+            }
+            for next in self.passages(idx) {
+                let next_cost = cost[idx] + 1;
+                if next_cost < cost[next] {
+                    cost[next] = next_cost;
+                    previous[next] = idx;
+                    let priority = next_cost + heuristic.estimate(grid.coords(next), target);
+                    open.push(Reverse((priority, next)));
+                }
+            }
+        }
+        trace(&previous, start, end)
+            .map(|cells| Path::new(cells.into_iter().map(|idx| grid.coords(idx)).collect()))
+    }
+
+    /// Walk from `start` to `end` keeping one hand on the wall, and return every cell visited in
+    /// order, including those walked through again on the way back out of dead ends. The walk
+    /// starts facing away from the entrance if `start` is the entrance cell and north otherwise.
+    ///
+    /// Returns `None` if either cell is outside of the maze or the walk comes back to where it
+    /// has already been without reaching `end`, which happens when `end` is not on the same wall
+    /// as `start`, for example in a braided maze.
+    pub fn follow_wall(
+        &self,
+        start: (usize, usize),
+        end: (usize, usize),
+        hand: Hand,
+    ) -> Option<Path> {
+        let grid = self.grid();
+        if !grid.contains(start.0, start.1) || !grid.contains(end.0, end.1) {
+            return None;
+        }
+        let mut facing = self
+            .entrance()
+            .filter(|entrance| start == (entrance.x, entrance.y))
+            .map_or(Direction::North, |entrance| entrance.side.opposite());
+        let (mut idx, end) = (grid.index(start.0, start.1), grid.index(end.0, end.1));
+        let mut cells = vec![grid.coords(idx)];
+        // The walk is deterministic, so being in the same cell facing the same way twice means
+        // it is going around in circles
+        let mut seen = BTreeSet::new();
+        while idx != end {
+            if !seen.insert((idx, facing)) {
+                return None;
+            }
+            let turns = match hand {
+                Hand::Left => [facing.left(), facing, facing.right(), facing.opposite()],
+                Hand::Right => [facing.right(), facing, facing.left(), facing.opposite()],
+            };
+            let (direction, next) = turns
+                .into_iter()
+                .find_map(|d| self.step(idx, d).map(|next| (d, next)))?;
+            (facing, idx) = (direction, next);
+            cells.push(grid.coords(idx));
+        }
+        Some(Path::new(cells))
+    }
+}
+
+/// The hand kept on the wall by [`Maze::follow_wall`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+/// An estimate of the number of moves between two cells for [`Maze::solve_astar_with`]. The
+/// estimate must never be more than the real number of moves for the path found to be the
+/// shortest, which [`Manhattan`] can be in a maze with tunnels.
+///
+/// Closures taking the coordinates of the two cells work as heuristics.
+pub trait Heuristic {
+    fn estimate(&self, from: (usize, usize), to: (usize, usize)) -> usize;
+}
+
+impl<F: Fn((usize, usize), (usize, usize)) -> usize> Heuristic for F {
+    fn estimate(&self, from: (usize, usize), to: (usize, usize)) -> usize {
+        self(from, to)
+    }
+}
+
+/// The distance between two cells moving only north, south, east and west
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Manhattan;
+
+impl Heuristic for Manhattan {
+    fn estimate(&self, from: (usize, usize), to: (usize, usize)) -> usize {
+        from.0.abs_diff(to.0) + from.1.abs_diff(to.1)
+    }
+}
+
+/// Follow `previous` back from `end` to `start`, returning the cells in order from `start`, or
+/// `None` if `end` was never reached
+pub(crate) fn trace(previous: &[usize], start: usize, end: usize) -> Option<Vec<usize>> {
+    if previous[end] == usize::MAX {
+        return None;
+    }
+    let mut cells = vec![end];
+    let mut idx = end;
+    while idx != start {
+        idx = previous[idx];
+        cells.push(idx);
+    }
+    cells.reverse();
+    Some(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::Algorithm;
+
+    fn braided(algorithm: Algorithm) -> Maze {
+        Maze::builder()
+            .size(14, 10)
+            .algorithm(algorithm)
+            .braid(1.0)
+            .seed(9)
+            .build()
+            .unwrap()
+    }
+
+    /// Whether `path` goes from `start` to `end` through passages of `maze` without visiting a
+    /// cell twice
+    fn is_route(maze: &Maze, path: &Path, start: (usize, usize), end: (usize, usize)) -> bool {
+        let grid = maze.grid();
+        let cells = path.cells();
+        let distinct: BTreeSet<_> = cells.iter().collect();
+        path.start() == Some(start)
+            && path.end() == Some(end)
+            && distinct.len() == cells.len()
+            && cells.windows(2).all(|pair| {
+                let next = grid.index(pair[1].0, pair[1].1);
+                maze.passages(grid.index(pair[0].0, pair[0].1))
+                    .contains(&next)
+            })
+    }
+
+    #[test]
+    fn bfs_finds_a_route_through_braided_mazes() {
+        let (start, end) = ((0, 0), (13, 9));
+        for algorithm in [Algorithm::Wilsons, Algorithm::Weave] {
+            let maze = braided(algorithm);
+            let bfs = maze.solve(start, end).unwrap();
+            assert!(is_route(&maze, &bfs, start, end));
+            assert_eq!(maze.solution().unwrap().len(), bfs.len());
+        }
+    }
+
+    #[test]
+    fn astar_agrees_with_bfs_on_braided_mazes() {
+        let (start, end) = ((0, 0), (13, 9));
+        for algorithm in [Algorithm::Wilsons, Algorithm::Weave] {
+            let maze = braided(algorithm);
+            let astar = maze.solve_astar(start, end).unwrap();
+            assert!(is_route(&maze, &astar, start, end));
+            assert_eq!(astar.len(), maze.solve(start, end).unwrap().len());
+        }
+    }
+
+    #[test]
+    fn k_shortest_paths_are_distinct_routes_in_order() {
+        let (start, end) = ((0, 0), (13, 9));
+        for algorithm in [Algorithm::Wilsons, Algorithm::Weave] {
+            let maze = braided(algorithm);
+            let paths = maze.k_shortest_paths(start, end, 8);
+            assert_eq!(paths.len(), 8);
+            assert_eq!(paths[0].len(), maze.solve(start, end).unwrap().len());
+            assert!(paths.iter().all(|path| is_route(&maze, path, start, end)));
+            assert!(paths.windows(2).all(|pair| pair[0].len() <= pair[1].len()));
+            let distinct: BTreeSet<_> = paths.iter().map(Path::cells).collect();
+            assert_eq!(distinct.len(), paths.len());
+        }
+    }
+
+    #[test]
+    fn perfect_mazes_have_one_route() {
+        let maze = Maze::builder().size(14, 10).seed(9).build().unwrap();
+        assert_eq!(maze.k_shortest_paths((0, 0), (13, 9), 5).len(), 1);
+    }
+
+    #[test]
+    fn cells_outside_have_no_route() {
+        let maze = braided(Algorithm::Wilsons);
+        assert_eq!(maze.solve((0, 0), (14, 0)), None);
+        assert_eq!(maze.solve_astar((0, 10), (0, 0)), None);
+        assert!(maze.k_shortest_paths((0, 0), (0, 10), 3).is_empty());
+        assert!(maze.k_shortest_paths((0, 0), (13, 9), 0).is_empty());
+    }
+}
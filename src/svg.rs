@@ -0,0 +1,257 @@
+use std::fmt::Write;
+
+use crate::maze::{merge_collinear, AccessiblePrint, Maze};
+
+/// How [`Maze::to_svg`] lays out the drawing. Sizes are in SVG user units, which viewers treat
+/// as pixels; the drawing scales without loss to any size it is printed or displayed at.
+#[derive(Clone, Debug)]
+pub struct SvgOptions {
+    /// Width and height of a cell
+    pub cell_size: f32,
+    pub stroke_width: f32,
+    /// Space left around the maze, so that the outer walls are not cut off at the edge
+    pub margin: f32,
+    /// Color of the walls, as any SVG color such as `black` or `#336699`
+    pub wall_color: String,
+    /// Color to fill the whole drawing with, or `None` to leave it transparent
+    pub background: Option<String>,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 25.0,
+            stroke_width: 2.0,
+            margin: 2.0,
+            wall_color: "black".to_owned(),
+            background: None,
+        }
+    }
+}
+
+impl SvgOptions {
+    /// Opaque black walls on white at the pixel sizes [`Maze::draw_accessible`] uses, so the SVG
+    /// prints the same as the PNG at the preset's resolution
+    pub fn accessible(preset: &AccessiblePrint) -> Self {
+        let stroke_width = preset.stroke_px();
+        Self {
+            cell_size: preset.cell_size_px() as f32,
+            stroke_width,
+            margin: stroke_width.ceil(),
+            wall_color: "black".to_owned(),
+            background: Some("white".to_owned()),
+        }
+    }
+}
+
+/// How [`Maze::to_laser_svg`] lays out a maze for a laser cutter. Sizes are in millimeters, and
+/// colors are what the laser software maps to its cut and engrave operations.
+#[derive(Clone, Debug)]
+pub struct LaserOptions {
+    pub cell_size: f32,
+    /// Width of the material burnt away by the beam. The cut outline is moved out by half of it
+    /// so that the board comes out at its drawn size.
+    pub kerf: f32,
+    /// Width of the board around the maze, between the outer walls and the cut edge
+    pub border: f32,
+    pub edge: Edge,
+    /// Color of the outline cut around the board
+    pub cut_color: String,
+    /// Color of the walls, which are engraved into the board
+    pub engrave_color: String,
+    /// Width of every line. Most laser software wants hairlines.
+    pub stroke_width: f32,
+}
+
+impl Default for LaserOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 5.0,
+            kerf: 0.1,
+            border: 5.0,
+            edge: Edge::Plain,
+            cut_color: "#ff0000".to_owned(),
+            engrave_color: "#0000ff".to_owned(),
+            stroke_width: 0.01,
+        }
+    }
+}
+
+/// Shape of the edges of the board cut by [`Maze::to_laser_svg`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Edge {
+    Plain,
+    /// Tabs about `finger_width` long that stick out `depth`, usually the thickness of the
+    /// material, so that the board slots into the sides of a box. Each edge starts and ends
+    /// with a tab.
+    FingerJoints {
+        finger_width: f32,
+        depth: f32,
+    },
+}
+
+impl Maze {
+    /// Draw the walls as an SVG document, with all of them in a single `<path>` element
+    pub fn to_svg(&self, options: &SvgOptions) -> String {
+        let scale = options.cell_size;
+        let margin = options.margin;
+        let width = self.width as f32 * scale + 2.0 * margin;
+        let height = self.height as f32 * scale + 2.0 * margin;
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+        )
+        .unwrap();
+        if let Some(background) = &options.background {
+            writeln!(
+                svg,
+                r#"<rect width="{width}" height="{height}" fill="{background}"/>"#
+            )
+            .unwrap();
+        }
+
+        svg.push_str(r#"<path d=""#);
+        for (i, segment) in merge_collinear(self.wall_segments()).iter().enumerate() {
+            if i > 0 {
+                svg.push(' ');
+            }
+            let point = |(x, y): (f32, f32)| (x * scale + margin, y * scale + margin);
+            let (from, to) = (point(segment.from), point(segment.to));
+            write!(svg, "M{},{} L{},{}", from.0, from.1, to.0, to.1).unwrap();
+        }
+        writeln!(
+            svg,
+            r#"" fill="none" stroke="{}" stroke-width="{}" stroke-linecap="square"/>"#,
+            options.wall_color, options.stroke_width
+        )
+        .unwrap();
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Draw the maze as an SVG document for a laser cutter, in millimeters. The walls are
+    /// engraved and the outline of the board around them is cut, each in its own color and layer.
+    pub fn to_laser_svg(&self, options: &LaserOptions) -> String {
+        let scale = options.cell_size;
+        let inner_width = self.width as f32 * scale + 2.0 * options.border;
+        let inner_height = self.height as f32 * scale + 2.0 * options.border;
+        let outline = offset_outline(
+            &board_outline(inner_width, inner_height, options.edge),
+            options.kerf / 2.0,
+        );
+        // Leave room for the tabs and the kerf outside the board
+        let reach = match options.edge {
+            Edge::Plain => 0.0,
+            Edge::FingerJoints { depth, .. } => depth,
+        } + options.kerf / 2.0
+            + options.stroke_width;
+        let origin = reach + options.border;
+        let width = inner_width + 2.0 * reach;
+        let height = inner_height + 2.0 * reach;
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" width="{width}mm" height="{height}mm" viewBox="0 0 {width} {height}">"#
+        )
+        .unwrap();
+
+        writeln!(
+            svg,
+            r#"<g id="engrave" inkscape:groupmode="layer" inkscape:label="engrave" fill="none" stroke="{}" stroke-width="{}">"#,
+            options.engrave_color, options.stroke_width
+        )
+        .unwrap();
+        svg.push_str(r#"<path d=""#);
+        for (i, segment) in merge_collinear(self.wall_segments()).iter().enumerate() {
+            if i > 0 {
+                svg.push(' ');
+            }
+            let point = |(x, y): (f32, f32)| (x * scale + origin, y * scale + origin);
+            let (from, to) = (point(segment.from), point(segment.to));
+            write!(svg, "M{},{} L{},{}", from.0, from.1, to.0, to.1).unwrap();
+        }
+        svg.push_str("\"/>\n</g>\n");
+
+        writeln!(
+            svg,
+            r#"<g id="cut" inkscape:groupmode="layer" inkscape:label="cut" fill="none" stroke="{}" stroke-width="{}">"#,
+            options.cut_color, options.stroke_width
+        )
+        .unwrap();
+        svg.push_str(r#"<path d=""#);
+        for (i, (x, y)) in outline.iter().enumerate() {
+            let command = if i == 0 { 'M' } else { 'L' };
+            write!(svg, "{command}{},{} ", x + reach, y + reach).unwrap();
+        }
+        svg.push_str("Z\"/>\n</g>\n");
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Corners of the edge of a board `width` by `height` with its top left corner at the origin,
+/// clockwise from the top left corner
+fn board_outline(width: f32, height: f32, edge: Edge) -> Vec<(f32, f32)> {
+    let Edge::FingerJoints {
+        finger_width,
+        depth,
+    } = edge
+    else {
+        return vec![(0.0, 0.0), (width, 0.0), (width, height), (0.0, height)];
+    };
+
+    let mut corners = Vec::new();
+    let sides = [
+        ((0.0, 0.0), (1.0, 0.0), width),
+        ((width, 0.0), (0.0, 1.0), height),
+        ((width, height), (-1.0, 0.0), width),
+        ((0.0, height), (0.0, -1.0), height),
+    ];
+    for ((x, y), (dx, dy), length) in sides {
+        // An odd number of fingers puts a tab at both ends of every side
+        let fingers = ((length / finger_width).round() as usize).max(1) | 1;
+        let finger = length / fingers as f32;
+        // Tabs stick out to the left of the direction of travel, which is outwards when going
+        // clockwise with y pointing down
+        let (ox, oy) = (dy * depth, -dx * depth);
+        for k in (0..fingers).step_by(2) {
+            let (start, end) = (k as f32 * finger, (k + 1) as f32 * finger);
+            corners.push((x + dx * start, y + dy * start));
+            corners.push((x + dx * start + ox, y + dy * start + oy));
+            corners.push((x + dx * end + ox, y + dy * end + oy));
+            corners.push((x + dx * end, y + dy * end));
+        }
+    }
+    // The tabs at the ends of neighboring sides start where the other ends
+    corners.dedup();
+    if corners.first() == corners.last() {
+        corners.pop();
+    }
+    corners
+}
+
+/// Move every edge of the clockwise, axis-aligned outline `corners` outwards by `distance`
+fn offset_outline(corners: &[(f32, f32)], distance: f32) -> Vec<(f32, f32)> {
+    let count = corners.len();
+    // The outward normal of an edge going clockwise
+    let normal = |from: (f32, f32), to: (f32, f32)| {
+        let direction = |d: f32| if d == 0.0 { 0.0 } else { d.signum() };
+        (direction(to.1 - from.1), -direction(to.0 - from.0))
+    };
+    (0..count)
+        .map(|i| {
+            let (prev, here, next) = (
+                corners[(i + count - 1) % count],
+                corners[i],
+                corners[(i + 1) % count],
+            );
+            let (a, b) = (normal(prev, here), normal(here, next));
+            (
+                here.0 + (a.0 + b.0) * distance,
+                here.1 + (a.1 + b.1) * distance,
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,610 @@
+#[cfg(feature = "render")]
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "render")]
+use core::ops::Range;
+
+#[cfg(feature = "render")]
+use tiny_skia::{Paint, PathBuilder, Pixmap, Stroke, Transform};
+
+#[cfg(feature = "render")]
+use crate::error::Error;
+use crate::float;
+use crate::graph::Graph;
+use crate::grid::{Direction, RectGrid};
+#[cfg(feature = "render")]
+use crate::maze::pixmap;
+use crate::maze::Maze;
+
+/// A tiling of the plane into cells that a maze can be carved from: a [`Graph`] of the cells
+/// that share a wall, together with the shape of the walls. Implement this to generate and draw
+/// mazes over custom cell shapes.
+pub trait Tessellation: Graph {
+    /// The walls around `cell` in cell units. Every wall between two cells must be listed by
+    /// both cells, normally with the same end points. A wall that is drawn in two places, like
+    /// one that wraps around the edges of a torus, is listed by each cell where it is drawn on
+    /// that cell's side.
+    fn walls(&self, cell: usize) -> Vec<Wall>;
+
+    /// Whether [`Tessellation::walls`] already leaves out the outer walls of an entrance and an
+    /// exit, as [`Cylinder`] does. Otherwise [`Carving::draw`] opens the outer walls of the two
+    /// boundary cells that are farthest apart for them.
+    fn has_openings(&self) -> bool {
+        false
+    }
+}
+
+/// A straight wall on the edge of a cell
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Wall {
+    /// The cell on the other side of the wall, or `None` if the wall is on the outer boundary
+    pub neighbor: Option<usize>,
+    pub from: (f32, f32),
+    pub to: (f32, f32),
+}
+
+/// The rectangular grid of square cells that [`Maze`] uses. Cells are indexed in row-major
+/// order.
+#[derive(Clone, Copy, Debug)]
+pub struct Square {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Graph for Square {
+    fn len(&self) -> usize {
+        RectGrid::new(self.width, self.height).len()
+    }
+
+    fn neighbors(&self, cell: usize) -> Vec<usize> {
+        let grid = RectGrid::new(self.width, self.height);
+        Direction::iter()
+            .filter_map(|direction| grid.adjacent_index(cell, direction))
+            .collect()
+    }
+}
+
+impl Tessellation for Square {
+    fn walls(&self, cell: usize) -> Vec<Wall> {
+        let grid = RectGrid::new(self.width, self.height);
+        square_walls(&grid, cell, |direction| {
+            grid.adjacent_index(cell, direction)
+        })
+    }
+}
+
+/// A rectangular grid of square cells whose opposite edges are joined, so that the maze lies on
+/// a torus and has no outer boundary, and so no entrance or exit. The walls along the edges are
+/// drawn on both sides of the image, and passages that wrap around show up as gaps in both.
+#[derive(Clone, Copy, Debug)]
+pub struct Torus {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Graph for Torus {
+    fn len(&self) -> usize {
+        RectGrid::new(self.width, self.height).len()
+    }
+
+    fn neighbors(&self, cell: usize) -> Vec<usize> {
+        let mut neighbors: Vec<usize> =
+            self.walls(cell).iter().filter_map(|w| w.neighbor).collect();
+        // In a grid two cells wide or high the cell across the edge is also the adjacent one
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        neighbors
+    }
+}
+
+impl Tessellation for Torus {
+    fn walls(&self, cell: usize) -> Vec<Wall> {
+        let grid = RectGrid::new(self.width, self.height);
+        square_walls(&grid, cell, |direction| wrap(&grid, cell, direction))
+    }
+}
+
+/// A rectangular grid of square cells whose east and west edges are joined, so that the maze
+/// lies on the side of a cylinder. The entrance is in the north wall of the top left cell and
+/// the exit in the south wall of the bottom right cell; those walls are not listed.
+#[derive(Clone, Copy, Debug)]
+pub struct Cylinder {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Graph for Cylinder {
+    fn len(&self) -> usize {
+        RectGrid::new(self.width, self.height).len()
+    }
+
+    fn neighbors(&self, cell: usize) -> Vec<usize> {
+        let mut neighbors: Vec<usize> =
+            self.walls(cell).iter().filter_map(|w| w.neighbor).collect();
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        neighbors
+    }
+}
+
+impl Tessellation for Cylinder {
+    fn walls(&self, cell: usize) -> Vec<Wall> {
+        let grid = RectGrid::new(self.width, self.height);
+        let mut walls = square_walls(&grid, cell, |direction| match direction {
+            Direction::East | Direction::West => wrap(&grid, cell, direction),
+            Direction::North | Direction::South => grid.adjacent_index(cell, direction),
+        });
+        // Walls are listed north, south, west, east, so remove the exit first to keep the
+        // entrance's position
+        if cell == grid.len() - 1 {
+            walls.remove(1);
+        }
+        if cell == 0 {
+            walls.remove(0);
+        }
+        walls
+    }
+
+    fn has_openings(&self) -> bool {
+        true
+    }
+}
+
+/// The north, south, west and east walls of a square cell, with the neighbors across them
+fn square_walls(
+    grid: &RectGrid,
+    cell: usize,
+    neighbor: impl Fn(Direction) -> Option<usize>,
+) -> Vec<Wall> {
+    let (x, y) = grid.coords(cell);
+    let (fx, fy) = (x as f32, y as f32);
+    vec![
+        wall(neighbor(Direction::North), (fx, fy), (fx + 1.0, fy)),
+        wall(
+            neighbor(Direction::South),
+            (fx, fy + 1.0),
+            (fx + 1.0, fy + 1.0),
+        ),
+        wall(neighbor(Direction::West), (fx, fy), (fx, fy + 1.0)),
+        wall(
+            neighbor(Direction::East),
+            (fx + 1.0, fy),
+            (fx + 1.0, fy + 1.0),
+        ),
+    ]
+}
+
+/// The wrapped neighbor of `cell`. A grid one cell wide or high wraps onto the same cell, which
+/// is not a neighbor.
+fn wrap(grid: &RectGrid, cell: usize, direction: Direction) -> Option<usize> {
+    Some(grid.wrapping_adjacent_index(cell, direction)).filter(|n| *n != cell)
+}
+
+/// Rows of square cells where every odd row is shifted east by half a cell, like bricks in a
+/// wall. Each cell touches up to two cells in each of the rows above and below it.
+#[derive(Clone, Copy, Debug)]
+pub struct Brick {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Graph for Brick {
+    fn len(&self) -> usize {
+        self.width * self.height
+    }
+
+    fn neighbors(&self, cell: usize) -> Vec<usize> {
+        self.walls(cell).iter().filter_map(|w| w.neighbor).collect()
+    }
+}
+
+impl Tessellation for Brick {
+    fn walls(&self, cell: usize) -> Vec<Wall> {
+        let (x, y) = (cell % self.width, cell / self.width);
+        let shift = if y % 2 == 1 { 0.5 } else { 0.0 };
+        let (fx, fy) = (x as f32 + shift, y as f32);
+
+        // The cells above and below that overlap the west and east halves of this cell
+        let overlapping = |row: usize| {
+            let index = |col: usize| row * self.width + col;
+            if y % 2 == 1 {
+                (Some(index(x)), (x + 1 < self.width).then(|| index(x + 1)))
+            } else {
+                (x.checked_sub(1).map(index), Some(index(x)))
+            }
+        };
+        let mut walls = Vec::with_capacity(6);
+        for (row, wall_y) in [(y.checked_sub(1), fy), (Some(y + 1), fy + 1.0)] {
+            let (west_half, east_half) = match row {
+                Some(row) if row < self.height => overlapping(row),
+                _ => (None, None),
+            };
+            walls.push(wall(west_half, (fx, wall_y), (fx + 0.5, wall_y)));
+            walls.push(wall(east_half, (fx + 0.5, wall_y), (fx + 1.0, wall_y)));
+        }
+        let west = (x > 0).then(|| cell - 1);
+        let east = (x < self.width - 1).then(|| cell + 1);
+        walls.push(wall(west, (fx, fy), (fx, fy + 1.0)));
+        walls.push(wall(east, (fx + 1.0, fy), (fx + 1.0, fy + 1.0)));
+        walls
+    }
+}
+
+/// Concentric rings of cells around a single center cell, for circular mazes. Every ring is one
+/// cell deep. A ring is split into more cells than the ring inside it whenever that keeps its
+/// cells roughly square, so each cell has one or more neighbors in the next ring out. Cells are
+/// numbered ring by ring, starting from the center and going clockwise from the east in each
+/// ring.
+///
+/// Curved walls are approximated by short straight pieces.
+#[derive(Clone, Debug)]
+pub struct Polar {
+    /// The number of cells in each ring, from the center out
+    ring_lens: Vec<usize>,
+    /// The index of the first cell of each ring
+    ring_starts: Vec<usize>,
+}
+
+impl Polar {
+    pub fn new(rings: usize) -> Self {
+        let mut ring_lens: Vec<usize> = Vec::with_capacity(rings);
+        for ring in 0..rings {
+            let len = match ring_lens.last() {
+                None => 1,
+                Some(&inner) => {
+                    // Split each inner cell so that the new cells are about as wide as the rings
+                    // are deep
+                    let width = core::f32::consts::TAU * ring as f32 / inner as f32;
+                    inner * (float::round(width) as usize).max(1)
+                }
+            };
+            ring_lens.push(len);
+        }
+        let ring_starts = ring_lens
+            .iter()
+            .scan(0, |start, len| {
+                let ring_start = *start;
+                *start += len;
+                Some(ring_start)
+            })
+            .collect();
+        Self {
+            ring_lens,
+            ring_starts,
+        }
+    }
+
+    pub fn rings(&self) -> usize {
+        self.ring_lens.len()
+    }
+
+    pub fn ring_len(&self, ring: usize) -> usize {
+        self.ring_lens[ring]
+    }
+
+    /// The ring of `cell` and its position in the ring
+    pub fn locate(&self, cell: usize) -> (usize, usize) {
+        let ring = self.ring_starts.partition_point(|start| *start <= cell) - 1;
+        (ring, cell - self.ring_starts[ring])
+    }
+
+    fn cell(&self, ring: usize, position: usize) -> usize {
+        self.ring_starts[ring] + position % self.ring_lens[ring]
+    }
+
+    /// How many cells each cell of `ring` has in the next ring out
+    fn ratio(&self, ring: usize) -> usize {
+        self.ring_lens[ring + 1] / self.ring_lens[ring]
+    }
+
+    fn point(&self, radius: f32, position: usize, ring_len: usize) -> (f32, f32) {
+        let center = self.rings() as f32;
+        let angle = core::f32::consts::TAU * (position % ring_len) as f32 / ring_len as f32;
+        (
+            center + radius * float::cos(angle),
+            center + radius * float::sin(angle),
+        )
+    }
+
+    /// Push the arc at `radius` from `position` to the next position of a ring with `ring_len`
+    /// cells, as straight pieces about a quarter of a cell long
+    fn push_arc(
+        &self,
+        walls: &mut Vec<Wall>,
+        neighbor: Option<usize>,
+        radius: f32,
+        position: usize,
+        ring_len: usize,
+    ) {
+        let (start, end) = (position as f32, (position + 1) as f32);
+        let length = core::f32::consts::TAU * radius / ring_len as f32;
+        let pieces = (float::ceil(length * 4.0) as usize).max(1);
+        let center = self.rings() as f32;
+        let at = |piece: usize| {
+            if piece == 0 {
+                return self.point(radius, position, ring_len);
+            }
+            if piece == pieces {
+                return self.point(radius, position + 1, ring_len);
+            }
+            let t = start + (end - start) * piece as f32 / pieces as f32;
+            let angle = core::f32::consts::TAU * t / ring_len as f32;
+            (
+                center + radius * float::cos(angle),
+                center + radius * float::sin(angle),
+            )
+        };
+        for piece in 0..pieces {
+            walls.push(wall(neighbor, at(piece), at(piece + 1)));
+        }
+    }
+}
+
+impl Graph for Polar {
+    fn len(&self) -> usize {
+        self.ring_lens.iter().sum()
+    }
+
+    fn neighbors(&self, cell: usize) -> Vec<usize> {
+        let mut neighbors: Vec<usize> =
+            self.walls(cell).iter().filter_map(|w| w.neighbor).collect();
+        // Arcs are split into several walls, each listing the same neighbor
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        neighbors
+    }
+}
+
+impl Tessellation for Polar {
+    fn walls(&self, cell: usize) -> Vec<Wall> {
+        let (ring, position) = self.locate(cell);
+        let len = self.ring_lens[ring];
+        let (inner, outer) = (ring as f32, (ring + 1) as f32);
+        let mut walls = Vec::new();
+        if ring > 0 {
+            let parent = self.cell(ring - 1, position / self.ratio(ring - 1));
+            self.push_arc(&mut walls, Some(parent), inner, position, len);
+        }
+        if ring + 1 < self.rings() {
+            let ratio = self.ratio(ring);
+            let outer_len = self.ring_lens[ring + 1];
+            for child in position * ratio..(position + 1) * ratio {
+                let neighbor = Some(self.cell(ring + 1, child));
+                self.push_arc(&mut walls, neighbor, outer, child, outer_len);
+            }
+        } else {
+            self.push_arc(&mut walls, None, outer, position, len);
+        }
+        if len > 1 {
+            for (side, neighbor) in [(position, position + len - 1), (position + 1, position + 1)] {
+                let neighbor = Some(self.cell(ring, neighbor));
+                let from = self.point(inner, side, len);
+                let to = self.point(outer, side, len);
+                walls.push(wall(neighbor, from, to));
+            }
+        }
+        walls
+    }
+}
+
+pub(crate) fn wall(neighbor: Option<usize>, from: (f32, f32), to: (f32, f32)) -> Wall {
+    Wall { neighbor, from, to }
+}
+
+/// A maze carved out of a graph, usually a tessellation: the passages that have been opened
+/// between neighboring cells
+#[derive(Debug)]
+pub struct Carving<T> {
+    tessellation: T,
+    passages: Vec<Vec<usize>>,
+}
+
+impl<T: Graph> Carving<T> {
+    /// A carving with every wall still standing
+    pub fn new(tessellation: T) -> Self {
+        let passages = vec![Vec::new(); tessellation.len()];
+        Self {
+            tessellation,
+            passages,
+        }
+    }
+
+    pub fn tessellation(&self) -> &T {
+        &self.tessellation
+    }
+
+    /// Open the wall between two neighboring cells
+    pub fn link(&mut self, a: usize, b: usize) {
+        if !self.is_linked(a, b) {
+            self.passages[a].push(b);
+            self.passages[b].push(a);
+        }
+    }
+
+    pub fn is_linked(&self, a: usize, b: usize) -> bool {
+        self.passages(a).contains(&b)
+    }
+
+    /// The cells that `cell` has an open passage to
+    pub fn passages(&self, cell: usize) -> &[usize] {
+        &self.passages[cell]
+    }
+
+    /// Every open passage once, with the lower cell first. For a carving made by one of the
+    /// generators these are the edges of a spanning tree.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.passages.iter().enumerate().flat_map(|(a, passages)| {
+            passages
+                .iter()
+                .filter(move |b| a < **b)
+                .map(move |b| (a, *b))
+        })
+    }
+}
+
+/// The cells of the tessellation, whether or not the walls between them are open
+impl<T: Graph> Graph for Carving<T> {
+    fn len(&self) -> usize {
+        self.tessellation.len()
+    }
+
+    fn neighbors(&self, node: usize) -> Vec<usize> {
+        self.tessellation.neighbors(node)
+    }
+}
+
+#[cfg(feature = "render")]
+impl<T: Tessellation> Carving<T> {
+    /// Whether `cell` lists the wall to `neighbor` with the same end points as `wall`
+    fn lists_same_wall(&self, cell: usize, neighbor: usize, wall: &Wall) -> bool {
+        self.tessellation.walls(cell).iter().any(|other| {
+            other.neighbor == Some(neighbor)
+                && ((other.from, other.to) == (wall.from, wall.to)
+                    || (other.from, other.to) == (wall.to, wall.from))
+        })
+    }
+
+    /// The two cells on the outer boundary that are farthest apart through the passages, found
+    /// as [`Maze::place_openings_farthest`] finds them, or `None` if there is no boundary
+    fn farthest_boundary_cells(&self) -> Option<(usize, usize)> {
+        let boundary: Vec<usize> = (0..self.tessellation.len())
+            .filter(|cell| {
+                let walls = self.tessellation.walls(*cell);
+                walls.iter().any(|wall| wall.neighbor.is_none())
+            })
+            .collect();
+        let farthest = |from: usize| {
+            let mut distances = vec![usize::MAX; self.tessellation.len()];
+            distances[from] = 0;
+            let mut queue = VecDeque::from([from]);
+            while let Some(cell) = queue.pop_front() {
+                for next in self.passages(cell) {
+                    if distances[*next] == usize::MAX {
+                        distances[*next] = distances[cell] + 1;
+                        queue.push_back(*next);
+                    }
+                }
+            }
+            boundary
+                .iter()
+                .copied()
+                .filter(|cell| distances[*cell] != usize::MAX)
+                .max_by_key(|cell| distances[*cell])
+        };
+        let entrance = farthest(*boundary.first()?)?;
+        Some((entrance, farthest(entrance)?))
+    }
+
+    /// Draw the walls that are still standing. Unless the tessellation
+    /// [has openings](Tessellation::has_openings) of its own, the entrance and exit are cut in
+    /// the outer walls of the two boundary cells farthest apart, each through the longest stretch
+    /// of its outer walls, such as a whole side or arc.
+    pub fn draw(&self, cell_size: u32) -> Result<Pixmap, Error> {
+        let openings = if self.tessellation.has_openings() {
+            None
+        } else {
+            self.farthest_boundary_cells()
+        };
+
+        let mut paint = Paint::default();
+        paint.set_color_rgba8(0, 0, 0, 200);
+        paint.anti_alias = true;
+
+        let stroke = Stroke::default();
+        let scale = cell_size as f32;
+
+        let mut pb = PathBuilder::new();
+        let (mut max_x, mut max_y) = (0.0f32, 0.0f32);
+        for cell in 0..self.tessellation.len() {
+            let walls = self.tessellation.walls(cell);
+            let opening = match openings {
+                Some((entrance, exit)) if cell == entrance || cell == exit => opening(&walls),
+                _ => 0..0,
+            };
+            for (k, wall) in walls.into_iter().enumerate() {
+                max_x = max_x.max(wall.from.0).max(wall.to.0);
+                max_y = max_y.max(wall.from.1).max(wall.to.1);
+                // Interior walls are listed by both cells, so only draw them from the lower index
+                // unless the other cell draws its side of the wall somewhere else
+                let standing = match wall.neighbor {
+                    None => !opening.contains(&k),
+                    Some(neighbor) => {
+                        !self.is_linked(cell, neighbor)
+                            && (cell < neighbor || !self.lists_same_wall(neighbor, cell, &wall))
+                    }
+                };
+                if standing {
+                    pb.move_to(wall.from.0 * scale, wall.from.1 * scale);
+                    pb.line_to(wall.to.0 * scale, wall.to.1 * scale);
+                }
+            }
+        }
+
+        let width = float::ceil(max_x * scale) as u64;
+        let height = float::ceil(max_y * scale) as u64;
+        let mut pixmap = pixmap(width, height)?;
+        if let Some(path) = pb.finish() {
+            pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+        }
+        Ok(pixmap)
+    }
+}
+
+/// The outer walls of a cell to leave out for an entrance or exit, as a range of its `walls`.
+/// Outer walls that carry on from each other's ends in directions within 30 degrees, like the
+/// halves of a side or the pieces of an arc, are taken together, and of these runs the one whose
+/// ends are farthest apart is left out, so that a notch in the boundary is passed over for a
+/// whole side.
+#[cfg(feature = "render")]
+fn opening(walls: &[Wall]) -> Range<usize> {
+    let square = |(x, y): (f32, f32)| x * x + y * y;
+    let direction = |wall: &Wall| (wall.to.0 - wall.from.0, wall.to.1 - wall.from.1);
+    let carries_on = |last: &Wall, next: &Wall| {
+        let (a, b) = (direction(last), direction(next));
+        let dot = a.0 * b.0 + a.1 * b.1;
+        // The cosine of the angle between them is over cos 30° = √3 / 2
+        next.neighbor.is_none()
+            && next.from == last.to
+            && dot > 0.0
+            && dot * dot > 0.75 * square(a) * square(b)
+    };
+    let mut best = 0..0;
+    let mut best_span = 0.0;
+    let mut start = 0;
+    while start < walls.len() {
+        if walls[start].neighbor.is_some() {
+            start += 1;
+            continue;
+        }
+        let mut end = start + 1;
+        while end < walls.len() && carries_on(&walls[end - 1], &walls[end]) {
+            end += 1;
+        }
+        let (from, to) = (walls[start].from, walls[end - 1].to);
+        let span = square((to.0 - from.0, to.1 - from.1));
+        if span > best_span {
+            (best, best_span) = (start..end, span);
+        }
+        start = end;
+    }
+    best
+}
+
+impl Carving<Square> {
+    /// Convert a carved square grid into a [`Maze`], which owns the north and west walls of each
+    /// cell
+    pub fn to_maze(&self) -> Maze {
+        let Square { width, height } = self.tessellation;
+        let mut maze = Maze::new(width as u32, height as u32);
+        for idx in 0..self.tessellation.len() {
+            if idx % width > 0 && self.is_linked(idx, idx - 1) {
+                maze.cell_at_index_mut(idx).set_west_open();
+            }
+            if idx >= width && self.is_linked(idx, idx - width) {
+                maze.cell_at_index_mut(idx).set_north_open();
+            }
+        }
+        maze
+    }
+}
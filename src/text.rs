@@ -0,0 +1,164 @@
+use std::fmt;
+
+use tiny_skia::{FillRule, Paint, PathBuilder, Pixmap, Transform};
+use ttf_parser::{Face, OutlineBuilder};
+
+use crate::mask::Mask;
+
+/// Columns of blank cells between letters, so that neighboring letters never touch and each
+/// stays a maze of its own
+const LETTER_GAP: usize = 1;
+
+/// A mask in the shape of `text` set in a TrueType or OpenType font, `height` cells high from
+/// the font's ascender to its descender.
+///
+/// Every letter becomes its own maze with an entrance and an exit. Parts of a letter that would
+/// not be connected to the rest of it on the grid, like the dot of an `i` or hairlines too thin
+/// for the cells, are dropped so that every letter can be solved. Characters that the font has no
+/// glyph for are left out.
+pub fn mask(text: &str, font: &[u8], height: usize) -> Result<Mask, FontError> {
+    let face = Face::parse(font, 0).map_err(|_| FontError)?;
+    let ascender = f32::from(face.ascender());
+    let scale = height as f32 / (ascender - f32::from(face.descender()));
+
+    // Rows of kept cells, grown a letter at a time
+    let mut rows: Vec<Vec<bool>> = vec![Vec::new(); height];
+    for c in text.chars() {
+        let Some(glyph) = face.glyph_index(c) else {
+            continue;
+        };
+        let advance = f32::from(face.glyph_hor_advance(glyph).unwrap_or(0)) * scale;
+        let mut outline = Outline {
+            pb: PathBuilder::new(),
+            scale,
+            ascender,
+        };
+        let letter = face
+            .outline_glyph(glyph, &mut outline)
+            .and_then(|bbox| {
+                let x_min = f32::from(bbox.x_min) * scale;
+                let width = (f32::from(bbox.x_max) * scale - x_min).ceil() as usize + 1;
+                rasterize(outline.pb.finish()?, -x_min, width, height)
+            })
+            .map(largest_piece);
+        // Spaces and other glyphs without an outline just move the pen along
+        let (cells, width, gap) = match letter {
+            Some((cells, width)) => (cells, width, LETTER_GAP),
+            None => (Vec::new(), 0, advance.round() as usize),
+        };
+        for (y, row) in rows.iter_mut().enumerate() {
+            row.extend_from_slice(&cells[y * width..(y + 1) * width]);
+            row.extend(std::iter::repeat_n(false, gap));
+        }
+    }
+
+    let width = rows.first().map_or(0, |row| row.len());
+    Ok(Mask::new(width, height, |x, y| rows[y][x]))
+}
+
+/// Fill a glyph onto a grid of `width` by `height` cells, keeping the cells whose centers are
+/// inside it
+fn rasterize(
+    path: tiny_skia::Path,
+    shift: f32,
+    width: usize,
+    height: usize,
+) -> Option<(Vec<bool>, usize)> {
+    let mut pixmap = Pixmap::new(width as u32, height as u32)?;
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(0, 0, 0, 255);
+    paint.anti_alias = false;
+    let transform = Transform::from_translate(shift, 0.0);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, transform, None);
+    let cells = pixmap.pixels().iter().map(|p| p.alpha() >= 128).collect();
+    Some((cells, width))
+}
+
+/// Keep only the largest connected piece of a letter
+fn largest_piece((cells, width): (Vec<bool>, usize)) -> (Vec<bool>, usize) {
+    let mut piece = vec![usize::MAX; cells.len()];
+    let mut sizes = Vec::new();
+    for start in 0..cells.len() {
+        if !cells[start] || piece[start] != usize::MAX {
+            continue;
+        }
+        let id = sizes.len();
+        let mut size = 0;
+        piece[start] = id;
+        let mut stack = vec![start];
+        while let Some(idx) = stack.pop() {
+            size += 1;
+            let (x, y) = (idx % width, idx / width);
+            let neighbors = [
+                (x > 0).then(|| idx - 1),
+                (x + 1 < width).then_some(idx + 1),
+                (y > 0).then(|| idx - width),
+                (idx + width < cells.len()).then_some(idx + width),
+            ];
+            for neighbor in neighbors.into_iter().flatten() {
+                if cells[neighbor] && piece[neighbor] == usize::MAX {
+                    piece[neighbor] = id;
+                    stack.push(neighbor);
+                }
+            }
+        }
+        sizes.push(size);
+    }
+    let largest = (0..sizes.len()).max_by_key(|id| sizes[*id]);
+    let kept = piece.iter().map(|id| Some(*id) == largest).collect();
+    (kept, width)
+}
+
+/// Collects a glyph outline into a path in cell units, with y pointing down from the ascender
+struct Outline {
+    pb: PathBuilder,
+    scale: f32,
+    ascender: f32,
+}
+
+impl Outline {
+    fn point(&self, x: f32, y: f32) -> (f32, f32) {
+        (x * self.scale, (self.ascender - y) * self.scale)
+    }
+}
+
+impl OutlineBuilder for Outline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.point(x, y);
+        self.pb.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.point(x, y);
+        self.pb.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x1, y1) = self.point(x1, y1);
+        let (x, y) = self.point(x, y);
+        self.pb.quad_to(x1, y1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x1, y1) = self.point(x1, y1);
+        let (x2, y2) = self.point(x2, y2);
+        let (x, y) = self.point(x, y);
+        self.pb.cubic_to(x1, y1, x2, y2, x, y);
+    }
+
+    fn close(&mut self) {
+        self.pb.close();
+    }
+}
+
+/// The font data is not a TrueType or OpenType font that can be read
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FontError;
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("could not parse the font")
+    }
+}
+
+impl std::error::Error for FontError {}
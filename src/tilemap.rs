@@ -0,0 +1,81 @@
+use std::fmt::Write;
+
+use crate::maze::Maze;
+
+/// Layout of the map written by [`Maze::to_tmx`]
+#[derive(Clone, Debug)]
+pub struct TmxOptions {
+    /// Width and height of a tile in pixels
+    pub tile_width: u32,
+    pub tile_height: u32,
+    /// Path of the tileset, relative to the map. Its first sixteen tiles are the tiles for the
+    /// masks from 0 to 15, in that order.
+    pub tileset: String,
+}
+
+impl Default for TmxOptions {
+    fn default() -> Self {
+        Self {
+            tile_width: 16,
+            tile_height: 16,
+            tileset: "maze.tsx".to_owned(),
+        }
+    }
+}
+
+impl Maze {
+    /// The [`tile_mask`](Maze::tile_mask) of every cell as comma-separated values, one line per
+    /// row, which Tiled, Godot and Unity tools can all import as a tile layer
+    pub fn to_tile_csv(&self) -> String {
+        let mut csv = String::new();
+        for y in 0..self.height as usize {
+            let row: Vec<String> = (0..self.width as usize)
+                .map(|x| self.tile_mask(x, y).to_string())
+                .collect();
+            writeln!(csv, "{}", row.join(",")).unwrap();
+        }
+        csv
+    }
+
+    /// A Tiled map with a single tile layer holding the [`tile_mask`](Maze::tile_mask) of every
+    /// cell. The layer refers to tiles by global ids, which are one more than the mask because
+    /// Tiled keeps 0 for an empty tile.
+    pub fn to_tmx(&self, options: &TmxOptions) -> String {
+        let (width, height) = (self.width, self.height);
+        let (tile_width, tile_height) = (options.tile_width, options.tile_height);
+        let mut tmx = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        writeln!(
+            tmx,
+            r#"<map version="1.10" orientation="orthogonal" renderorder="right-down" width="{width}" height="{height}" tilewidth="{tile_width}" tileheight="{tile_height}" infinite="0" nextlayerid="2" nextobjectid="1">"#
+        )
+        .unwrap();
+        writeln!(
+            tmx,
+            r#" <tileset firstgid="1" source="{}"/>"#,
+            escape(&options.tileset)
+        )
+        .unwrap();
+        writeln!(
+            tmx,
+            r#" <layer id="1" name="maze" width="{width}" height="{height}">"#
+        )
+        .unwrap();
+        tmx.push_str("  <data encoding=\"csv\">\n");
+        for y in 0..height as usize {
+            let row: Vec<String> = (0..width as usize)
+                .map(|x| (self.tile_mask(x, y) + 1).to_string())
+                .collect();
+            let separator = if y + 1 < height as usize { "," } else { "" };
+            writeln!(tmx, "{}{separator}", row.join(",")).unwrap();
+        }
+        tmx.push_str("  </data>\n </layer>\n</map>\n");
+        tmx
+    }
+}
+
+/// `text` with the characters that are special inside an XML attribute replaced by entities
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,71 @@
+use std::io::{self, Write};
+
+use tiny_skia::{Color, Pixmap, PixmapPaint, Transform};
+
+use crate::error::Error;
+use crate::maze::pixmap;
+
+/// Writes frames as a raw video stream of 8-bit RGBA pixels, one frame after another with no
+/// header, which ffmpeg reads with
+///
+/// ```text
+/// ffmpeg -f rawvideo -pixel_format rgba -video_size WIDTHxHEIGHT -framerate 30 -i frames.rgba out.mp4
+/// ```
+///
+/// Frames are drawn over an opaque white background, since video formats have no transparency.
+/// Writing to a named pipe lets ffmpeg encode the frames as they are produced instead of keeping
+/// them all on disk.
+pub struct RawVideo<W: Write> {
+    inner: W,
+    frame: Pixmap,
+    frames: usize,
+}
+
+impl<W: Write> RawVideo<W> {
+    /// A stream of `width` by `height` frames written to `inner`, or [`Error::ImageSize`] if
+    /// either dimension is zero
+    pub fn new(inner: W, width: u32, height: u32) -> Result<Self, Error> {
+        Ok(Self {
+            inner,
+            frame: pixmap(width.into(), height.into())?,
+            frames: 0,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.frame.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.frame.height()
+    }
+
+    /// Number of frames written so far
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Write `image` as the next frame. Images smaller than the frame are drawn in its top left
+    /// corner and larger ones are cropped.
+    pub fn write_frame(&mut self, image: &Pixmap) -> io::Result<()> {
+        self.frame.fill(Color::WHITE);
+        self.frame.draw_pixmap(
+            0,
+            0,
+            image.as_ref(),
+            &PixmapPaint::default(),
+            Transform::identity(),
+            None,
+        );
+        // Over an opaque background premultiplied and straight alpha are the same
+        self.inner.write_all(self.frame.data())?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Flush and return the underlying writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
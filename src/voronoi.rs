@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+
+use rand::prelude::*;
+
+use crate::graph::Graph;
+use crate::tessellation::{wall, Tessellation, Wall};
+
+/// How far the middle of an interior wall may bow out to either side, relative to its length
+const MAX_BULGE: f64 = 0.2;
+
+/// Straight pieces that each curved wall is drawn with
+const CURVE_PIECES: usize = 6;
+
+/// The Voronoi diagram of random seed points, for organic "crack" mazes. One seed point is
+/// scattered at random in each square of a `width` by `height` grid, and every cell is the part
+/// of the rectangle that is closer to its seed point than to any other. Cells are neighbors when
+/// they share an edge, which makes the graph the Delaunay triangulation of the points.
+///
+/// Interior walls are drawn as gentle curves bowing out by a random amount, so that the maze
+/// looks hand drawn. The outer boundary stays straight.
+#[derive(Clone, Debug)]
+pub struct Voronoi {
+    pub width: usize,
+    pub height: usize,
+    points: Vec<(f64, f64)>,
+    walls: Vec<Vec<Wall>>,
+}
+
+impl Voronoi {
+    pub fn new<R: Rng + ?Sized>(width: usize, height: usize, rng: &mut R) -> Self {
+        let points: Vec<(f64, f64)> = (0..width * height)
+            .map(|idx| {
+                let (x, y) = (idx % width, idx / width);
+                (x as f64 + rng.gen::<f64>(), y as f64 + rng.gen::<f64>())
+            })
+            .collect();
+        let mut voronoi = Self {
+            width,
+            height,
+            points,
+            walls: Vec::new(),
+        };
+
+        // Each cell computes the edges it shares with its neighbors separately, so the two
+        // copies of a wall only agree up to rounding. Draw every wall from the copy of the lower
+        // of its two cells so that both cells list exactly the same pieces.
+        let polygons: Vec<Vec<Edge>> = (0..voronoi.points.len())
+            .map(|idx| voronoi.polygon(idx))
+            .collect();
+        let mut shared: HashMap<(usize, usize), Vec<Wall>> = HashMap::new();
+        for (idx, polygon) in polygons.iter().enumerate() {
+            for edge in polygon {
+                if let Some(neighbor) = edge.neighbor {
+                    let key = (idx.min(neighbor), idx.max(neighbor));
+                    if idx == key.0 || !shared.contains_key(&key) {
+                        shared.insert(key, curve(edge, rng));
+                    }
+                }
+            }
+        }
+        let mut walls: Vec<Vec<Wall>> = polygons
+            .iter()
+            .map(|polygon| {
+                polygon
+                    .iter()
+                    .filter(|edge| edge.neighbor.is_none())
+                    .map(|edge| wall(None, to_f32(edge.from), to_f32(edge.to)))
+                    .collect()
+            })
+            .collect();
+        // Sort so that the pieces are listed in the same order on every run
+        let mut shared: Vec<((usize, usize), Vec<Wall>)> = shared.into_iter().collect();
+        shared.sort_unstable_by_key(|(key, _)| *key);
+        for ((a, b), pieces) in shared {
+            for piece in pieces {
+                walls[a].push(Wall {
+                    neighbor: Some(b),
+                    ..piece
+                });
+                walls[b].push(Wall {
+                    neighbor: Some(a),
+                    ..piece
+                });
+            }
+        }
+        voronoi.walls = walls;
+        voronoi
+    }
+
+    /// The seed point of each cell
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+
+    /// The cell around the seed point at `idx`, as its edges in order. It starts out as the
+    /// whole rectangle and is cut down by the bisector with every seed point close enough to
+    /// matter.
+    fn polygon(&self, idx: usize) -> Vec<Edge> {
+        let (w, h) = (self.width as f64, self.height as f64);
+        let mut polygon: Vec<((f64, f64), Option<usize>)> = vec![
+            ((0.0, 0.0), None),
+            ((w, 0.0), None),
+            ((w, h), None),
+            ((0.0, h), None),
+        ];
+        let p = self.points[idx];
+        let (gx, gy) = ((idx % self.width) as isize, (idx / self.width) as isize);
+        // Search outward in square rings of grid squares. Seed points in ring `r` are at least
+        // `r - 1` away, and a point more than twice as far away as every corner of the polygon
+        // cannot cut it.
+        for radius in 1.. {
+            let reach = polygon
+                .iter()
+                .map(|(v, _)| distance(p, *v))
+                .fold(0.0, f64::max);
+            if (radius - 1) as f64 > 2.0 * reach {
+                break;
+            }
+            if radius > self.width.max(self.height) as isize {
+                break;
+            }
+            for dy in -radius..=radius {
+                for dx in -radius..=radius {
+                    if dx.abs() != radius && dy.abs() != radius {
+                        continue;
+                    }
+                    let (x, y) = (gx + dx, gy + dy);
+                    if x < 0 || y < 0 || x >= self.width as isize || y >= self.height as isize {
+                        continue;
+                    }
+                    let other = y as usize * self.width + x as usize;
+                    if other != idx {
+                        polygon = clip(&polygon, p, self.points[other], other);
+                    }
+                }
+            }
+        }
+        (0..polygon.len())
+            .map(|k| Edge {
+                from: polygon[k].0,
+                to: polygon[(k + 1) % polygon.len()].0,
+                neighbor: polygon[k].1,
+            })
+            .filter(|edge| distance(edge.from, edge.to) > 1e-6)
+            .collect()
+    }
+}
+
+impl Graph for Voronoi {
+    fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    fn neighbors(&self, cell: usize) -> Vec<usize> {
+        let mut neighbors: Vec<usize> =
+            self.walls[cell].iter().filter_map(|w| w.neighbor).collect();
+        // Curved walls are split into several pieces, each listing the same neighbor
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        neighbors
+    }
+}
+
+impl Tessellation for Voronoi {
+    fn walls(&self, cell: usize) -> Vec<Wall> {
+        self.walls[cell].clone()
+    }
+}
+
+/// A straight edge of a Voronoi cell
+struct Edge {
+    from: (f64, f64),
+    to: (f64, f64),
+    neighbor: Option<usize>,
+}
+
+/// Cut `polygon` down to the half of the plane that is closer to `p` than to `q`. Each vertex
+/// carries the cell on the other side of the edge that starts at it, and the new edge along the
+/// bisector gets `other`.
+fn clip(
+    polygon: &[((f64, f64), Option<usize>)],
+    p: (f64, f64),
+    q: (f64, f64),
+    other: usize,
+) -> Vec<((f64, f64), Option<usize>)> {
+    let mid = ((p.0 + q.0) / 2.0, (p.1 + q.1) / 2.0);
+    let normal = (q.0 - p.0, q.1 - p.1);
+    let side = |v: (f64, f64)| (v.0 - mid.0) * normal.0 + (v.1 - mid.1) * normal.1;
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+    for k in 0..polygon.len() {
+        let (curr, label) = polygon[k];
+        let next = polygon[(k + 1) % polygon.len()].0;
+        let (curr_side, next_side) = (side(curr), side(next));
+        let crossing = || {
+            let t = curr_side / (curr_side - next_side);
+            (
+                curr.0 + t * (next.0 - curr.0),
+                curr.1 + t * (next.1 - curr.1),
+            )
+        };
+        if curr_side <= 0.0 {
+            clipped.push((curr, label));
+            if next_side > 0.0 {
+                clipped.push((crossing(), Some(other)));
+            }
+        } else if next_side <= 0.0 {
+            clipped.push((crossing(), label));
+        }
+    }
+    clipped
+}
+
+/// The pieces of a wall that bows out from the straight edge by a random amount
+fn curve<R: Rng + ?Sized>(edge: &Edge, rng: &mut R) -> Vec<Wall> {
+    let (from, to) = (edge.from, edge.to);
+    let bulge = rng.gen_range(-MAX_BULGE..=MAX_BULGE);
+    // A quadratic Bézier curve with its control point off the middle of the edge
+    let control = (
+        (from.0 + to.0) / 2.0 - bulge * (to.1 - from.1),
+        (from.1 + to.1) / 2.0 + bulge * (to.0 - from.0),
+    );
+    let at = |t: f64| {
+        let u = 1.0 - t;
+        (
+            u * u * from.0 + 2.0 * u * t * control.0 + t * t * to.0,
+            u * u * from.1 + 2.0 * u * t * control.1 + t * t * to.1,
+        )
+    };
+    (0..CURVE_PIECES)
+        .map(|piece| {
+            let t0 = piece as f64 / CURVE_PIECES as f64;
+            let t1 = (piece + 1) as f64 / CURVE_PIECES as f64;
+            wall(edge.neighbor, to_f32(at(t0)), to_f32(at(t1)))
+        })
+        .collect()
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+fn to_f32((x, y): (f64, f64)) -> (f32, f32) {
+    (x as f32, y as f32)
+}
@@ -0,0 +1,76 @@
+//! Bindings for generating and drawing mazes in the browser without a server. The library is only
+//! an rlib, so the cdylib for the browser is asked for when building it:
+//!
+//! ```sh
+//! cargo rustc --lib --release --crate-type cdylib --target wasm32-unknown-unknown \
+//!     --no-default-features --features wasm
+//! wasm-bindgen --target web --out-dir pkg target/wasm32-unknown-unknown/release/dadalus.wasm
+//! ```
+//!
+//! ```js
+//! import init, { generate } from "./pkg/dadalus.js";
+//!
+//! await init();
+//! const maze = generate(20, 10, 42n);
+//! document.body.innerHTML = maze.svg(25);
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use crate::error::Error;
+use crate::generator::MazeGenerator;
+use crate::maze::Maze;
+use crate::svg::SvgOptions;
+use crate::wilsons::Generator;
+
+/// A maze handed to JavaScript, where it is called `Maze`
+#[wasm_bindgen(js_name = Maze)]
+pub struct WasmMaze(Maze);
+
+/// Generate a `width` by `height` maze with Wilson's algorithm from `seed`, which is a BigInt
+/// in JavaScript. The same seed gives the same maze as `dadalus --seed` does.
+#[wasm_bindgen]
+pub fn generate(width: u32, height: u32, seed: u64) -> Result<WasmMaze, JsError> {
+    if width.checked_mul(height).is_none() {
+        return Err(Error::TooLarge {
+            width: width.into(),
+            height: height.into(),
+        }
+        .into());
+    }
+    let maze = Generator.generate_seeded(width as usize, height as usize, seed);
+    Ok(WasmMaze(maze))
+}
+
+#[wasm_bindgen(js_class = Maze)]
+impl WasmMaze {
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> u32 {
+        self.0.width
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> u32 {
+        self.0.height
+    }
+
+    /// The closed walls of every cell row by row, as a `Uint8Array` with one number from 0 to
+    /// 15 per cell, adding 1 for a wall to the north, 2 to the east, 4 to the south and 8 to the
+    /// west. The entrance and exit are left open.
+    pub fn walls(&self) -> Vec<u8> {
+        let (width, height) = (self.0.width as usize, self.0.height as usize);
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| !self.0.tile_mask(x, y) & 0xf)
+            .collect()
+    }
+
+    /// The maze drawn as an SVG document with `cell_size` units per cell
+    pub fn svg(&self, cell_size: f32) -> String {
+        let options = SvgOptions {
+            cell_size,
+            ..SvgOptions::default()
+        };
+        self.0.to_svg(&options)
+    }
+}
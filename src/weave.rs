@@ -0,0 +1,100 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use rand::prelude::*;
+
+use crate::disjoint_set::DisjointSet;
+use crate::generator::MazeGenerator;
+use crate::grid::{Direction, RectGrid};
+use crate::maze::Maze;
+
+/// Generates weave mazes, where passages may tunnel under perpendicular passages, with Kruskal's
+/// algorithm.
+///
+/// Crossings are laid out first: each interior cell becomes one with probability `density`,
+/// unless it is next to another crossing or its passages would make a loop. A crossing cell
+/// gets a straight passage over it and a [tunnel](crate::Cell::tunnel) under it. The rest of the
+/// maze is filled in by removing random walls between unconnected cells, leaving the walls of
+/// crossing cells alone.
+#[derive(Clone, Copy, Debug)]
+pub struct Generator {
+    pub density: f64,
+}
+
+impl Generator {
+    pub fn new(density: f64) -> Self {
+        Self {
+            density: density.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Self::new(0.3)
+    }
+}
+
+impl MazeGenerator for Generator {
+    fn generate<R: Rng + ?Sized>(&self, width: usize, height: usize, rng: &mut R) -> Maze {
+        let grid = RectGrid::new(width, height);
+        let mut maze = Maze::new(width as u32, height as u32);
+        let mut sets = DisjointSet::new(grid.len());
+        let mut crossing = vec![false; grid.len()];
+
+        let mut interior: Vec<usize> = (0..grid.len())
+            .filter(|idx| {
+                let (x, y) = grid.coords(*idx);
+                x > 0 && y > 0 && x + 1 < width && y + 1 < height
+            })
+            .collect();
+        interior.shuffle(rng);
+        for idx in interior {
+            if !rng.gen_bool(self.density) {
+                continue;
+            }
+            let [north, south, east, west] =
+                Direction::ALL.map(|direction| grid.adjacent_index(idx, direction).unwrap());
+            if [north, south, east, west].iter().any(|n| crossing[*n]) {
+                continue;
+            }
+            let (over, under) = if rng.gen_bool(0.5) {
+                ([north, south], [east, west])
+            } else {
+                ([east, west], [north, south])
+            };
+            // Every cell involved has to start out in a different set for the crossing to stay
+            // free of loops
+            let roots: Vec<usize> = [idx, north, south, east, west]
+                .iter()
+                .map(|cell| sets.find(*cell))
+                .collect();
+            if (1..roots.len()).any(|i| roots[..i].contains(&roots[i])) {
+                continue;
+            }
+
+            crossing[idx] = true;
+            maze.cell_at_index_mut(idx).set_tunnel();
+            for cell in over {
+                let direction = Direction::iter()
+                    .find(|d| grid.adjacent_index(idx, *d) == Some(cell))
+                    .unwrap();
+                maze.open_wall(idx, direction);
+                sets.union(idx, cell);
+            }
+            sets.union(under[0], under[1]);
+        }
+
+        let mut edges = grid.edges();
+        edges.shuffle(rng);
+        for edge in edges {
+            if crossing[edge.from] || crossing[edge.to] {
+                continue;
+            }
+            if sets.union(edge.from, edge.to) {
+                maze.open_wall(edge.from, edge.direction);
+            }
+        }
+        maze
+    }
+}
@@ -1,19 +1,360 @@
-use std::fmt::{Display, Write};
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
 
 use rand::prelude::*;
 
+use crate::generator::{MazeGenerator, Seeded};
+use crate::graph::Graph;
+use crate::grid::{Direction, Grid, RectGrid};
 use crate::maze::{Cell as MazeCell, Maze};
 
+/// Generates mazes using Wilson's algorithm, which produces every possible perfect maze with
+/// equal probability
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Generator;
+
+impl Generator {
+    /// Wilson's algorithm with its own random numbers from `seed`, which is the quickest way to
+    /// reproducible mazes. See [`MazeGenerator::seeded`] for the other generators.
+    pub fn with_seed(seed: u64) -> Seeded<Self> {
+        Generator.seeded(seed)
+    }
+
+    /// Generate a maze as [`MazeGenerator::generate`] does, calling `frame` with the maze carved
+    /// so far each time at least `cells_per_frame` more cells have joined it, and once more with
+    /// the finished maze. Cells that have not joined yet have all of their walls closed.
+    pub fn animate<R: Rng + ?Sized, F: FnMut(&Maze)>(
+        &self,
+        width: usize,
+        height: usize,
+        rng: &mut R,
+        cells_per_frame: usize,
+        mut frame: F,
+    ) -> Maze {
+        let cells_per_frame = cells_per_frame.max(1);
+        let mut last_frame = 0;
+        let maze = Walks::new(width, height).generate(rng, |walks, in_maze, event| {
+            if matches!(event, GenerationEvent::PathCommitted { .. })
+                && in_maze - last_frame >= cells_per_frame
+            {
+                last_frame = in_maze;
+                frame(&walks.snapshot());
+            }
+        });
+        frame(&maze);
+        maze
+    }
+
+    /// Generate a maze as [`MazeGenerator::generate`] does, calling `progress` with the number
+    /// of cells in the maze so far and the number of cells in the grid each time a walk joins
+    /// the maze. Large mazes can take a while, and the first walks are the slowest because they
+    /// wander until they find the few cells already in the maze.
+    pub fn generate_with_progress<R: Rng + ?Sized, F: FnMut(usize, usize)>(
+        &self,
+        width: usize,
+        height: usize,
+        rng: &mut R,
+        mut progress: F,
+    ) -> Maze {
+        let total = width * height;
+        Walks::new(width, height).generate(rng, |_, in_maze, event| {
+            if let GenerationEvent::PathCommitted { .. } = event {
+                progress(in_maze, total)
+            }
+        })
+    }
+
+    /// Generate a maze as [`MazeGenerator::generate`] does, calling `observer` with every step
+    /// the algorithm takes, from the first cell of the maze through each random walk to the
+    /// path it adds to the maze. The events are enough to replay the generation, for an
+    /// animation or to show how the algorithm works.
+    ///
+    /// ```
+    /// use dadalus::wilsons::GenerationEvent;
+    ///
+    /// let mut loops = 0;
+    /// let mut rng = rand::thread_rng();
+    /// dadalus::Generator.generate_with_observer(10, 10, &mut rng, |event| {
+    ///     if let GenerationEvent::LoopErased { .. } = event {
+    ///         loops += 1;
+    ///     }
+    /// });
+    /// println!("erased {loops} loops");
+    /// ```
+    pub fn generate_with_observer<R: Rng + ?Sized, F: FnMut(GenerationEvent)>(
+        &self,
+        width: usize,
+        height: usize,
+        rng: &mut R,
+        mut observer: F,
+    ) -> Maze {
+        Walks::new(width, height).generate(rng, |_, _, event| observer(event))
+    }
+
+    /// Wilson's algorithm as an iterator that takes one step each time it is advanced and
+    /// returns the [`GenerationEvent`] for it. Between steps, [`Steps::maze`] is the maze carved
+    /// so far, so generation can be driven a step at a time, like one step per frame of an
+    /// animation, and stopped and picked up again at any point. Running every step carves the
+    /// same maze that [`MazeGenerator::generate`] does with the same random numbers.
+    ///
+    /// ```
+    /// use dadalus::{Generator, MazeGenerator};
+    /// use rand::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    ///
+    /// let mut steps = Generator.steps(12, 8, ChaCha8Rng::seed_from_u64(7));
+    /// for event in steps.by_ref().take(20) {
+    ///     println!("{event:?}");
+    /// }
+    /// print!("{}", steps.maze());
+    /// let maze = steps.finish();
+    /// assert_eq!(maze.to_code(), Generator.generate_seeded(12, 8, 7).to_code());
+    /// ```
+    pub fn steps<R: Rng>(&self, width: usize, height: usize, rng: R) -> Steps<R> {
+        Steps::new(Walks::new(width, height), rng)
+    }
+}
+
+/// A step of Wilson's algorithm, passed to the observer of [`Generator::generate_with_observer`]
+/// and returned by [`Generator::steps`]. Cells are given by their `(x, y)` coordinates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GenerationEvent {
+    /// The maze started out as `cell` alone, before any walks
+    MazeStarted { cell: (usize, usize) },
+    /// A random walk started from `cell`, which is not in the maze yet
+    WalkStarted { cell: (usize, usize) },
+    /// The walk moved from `from` to its neighbor `to` in `direction`
+    WalkStep {
+        from: (usize, usize),
+        to: (usize, usize),
+        direction: Direction,
+    },
+    /// The walk came back to `cell`, which it had already passed through, and the loop it made
+    /// since then was erased
+    LoopErased { cell: (usize, usize) },
+    /// The walk from `start` reached the maze, and the `length` cells of its path without the
+    /// loops joined the maze
+    PathCommitted {
+        start: (usize, usize),
+        length: usize,
+    },
+}
+
+/// The steps of Wilson's algorithm, made by [`Generator::steps`]
+#[derive(Debug)]
+pub struct Steps<R> {
+    walks: Walks,
+    rng: R,
+    phase: Phase,
+    directions: [Direction; 4],
+    /// Every cell the current walk has passed through
+    walk_indexes: Vec<usize>,
+    in_maze: usize,
+}
+
+/// Where the algorithm is up to between two steps
+#[derive(Clone, Copy, Debug)]
+enum Phase {
+    /// No cell is in the maze yet
+    Start,
+    /// The last walk has joined the maze and the next one has not started
+    Between,
+    /// The walk from `start` is at `curr`, which is not in the maze
+    Walking {
+        start: usize,
+        curr: usize,
+    },
+    /// The walk from `start` came back to `curr`, which it had already passed through
+    Looped {
+        start: usize,
+        curr: usize,
+    },
+    /// The walk from `start` has reached the maze
+    Reached {
+        start: usize,
+    },
+    Done,
+}
+
+impl<R: Rng> Steps<R> {
+    fn new(walks: Walks, rng: R) -> Self {
+        let walk_indexes = Vec::with_capacity(walks.len());
+        Self {
+            walks,
+            rng,
+            phase: Phase::Start,
+            directions: Direction::ALL,
+            walk_indexes,
+            in_maze: 0,
+        }
+    }
+
+    /// The maze as carved so far, with every wall of the cells outside of it closed. The cells
+    /// of the current walk are not in the maze until its path joins it.
+    pub fn maze(&self) -> Maze {
+        self.walks.snapshot()
+    }
+
+    /// The number of cells in the maze so far
+    pub fn in_maze(&self) -> usize {
+        self.in_maze
+    }
+
+    /// Run the rest of the steps and return the finished maze
+    pub fn finish(mut self) -> Maze {
+        self.by_ref().for_each(drop);
+        self.maze()
+    }
+
+    fn step(&mut self) -> Option<GenerationEvent> {
+        use Cell::*;
+        use Direction::*;
+        let walks = &mut self.walks;
+        match self.phase {
+            Phase::Start => {
+                if walks.is_empty() {
+                    self.phase = Phase::Done;
+                    return None;
+                }
+                walks.unvisited_candidates.shuffle(&mut self.rng);
+
+                // Choose an initial cell at random to be part of the maze
+                let initial_idx = walks.choose_walk_start().unwrap();
+                *walks.cell_mut(initial_idx) = InMaze(MazeCell::new(false, false));
+                self.in_maze = 1;
+                self.phase = Phase::Between;
+                Some(GenerationEvent::MazeStarted {
+                    cell: walks.coords(initial_idx),
+                })
+            }
+            Phase::Between => {
+                let Some(start) = walks.choose_walk_start() else {
+                    self.phase = Phase::Done;
+                    return None;
+                };
+                self.walk_indexes.clear();
+                self.phase = Phase::Walking { start, curr: start };
+                Some(GenerationEvent::WalkStarted {
+                    cell: walks.coords(start),
+                })
+            }
+            Phase::Walking { start, curr } => {
+                self.walk_indexes.push(curr);
+                let (direction, adjacent_idx) =
+                    walks.choose_random_adjacent(curr, &mut self.directions, &mut self.rng);
+                *walks.cell_mut(curr) = Walk(direction);
+                self.phase = match walks.cell(adjacent_idx) {
+                    InMaze(_) => Phase::Reached { start },
+                    // Stepping back onto the walk overrides its direction there on the next
+                    // step, which cuts the loop out of the path
+                    Walk(_) => Phase::Looped {
+                        start,
+                        curr: adjacent_idx,
+                    },
+                    Empty => Phase::Walking {
+                        start,
+                        curr: adjacent_idx,
+                    },
+                };
+                Some(GenerationEvent::WalkStep {
+                    from: walks.coords(curr),
+                    to: walks.coords(adjacent_idx),
+                    direction,
+                })
+            }
+            Phase::Looped { start, curr } => {
+                self.phase = Phase::Walking { start, curr };
+                Some(GenerationEvent::LoopErased {
+                    cell: walks.coords(curr),
+                })
+            }
+            Phase::Reached { start } => {
+                // Add the walk to the maze. Because the path may intersect itself and directions
+                // in the cells that are traversed multiple times are overridden, follow the path
+                // based on the directions not the indexes traversed. That means that some cells
+                // traversed in the walk will not be included in the maze in this.
+                let mut curr_idx = start;
+                let mut last_direction: Option<Direction> = None;
+                let joined = self.in_maze;
+                loop {
+                    let cell = walks.cell_mut(curr_idx);
+                    let direction = match cell {
+                        Walk(direction) => *direction,
+                        InMaze(mc) => {
+                            // Open up the existing maze cell so that the walk path enters it
+                            match last_direction.map(Direction::opposite) {
+                                Some(West) => mc.set_west_open(),
+                                Some(North) => mc.set_north_open(),
+                                _ => {}
+                            }
+                            break;
+                        }
+                        _ => unreachable!(),
+                    };
+                    // Open up walls along the walk direction. Maze cells "own" the north and
+                    // west direction, so track if those are the walls entered from or leaving
+                    // through
+                    *cell = InMaze(MazeCell::new(
+                        direction == West || last_direction == Some(East),
+                        direction == North || last_direction == Some(South),
+                    ));
+                    curr_idx = walks.adjacent_index(curr_idx, direction).unwrap();
+                    last_direction = Some(direction);
+                    self.in_maze += 1;
+                }
+
+                // Remove unused walk cells, so that they are not mistaken for the next walk
+                // and the maze in between steps shows only cells that have joined it
+                for idx in self.walk_indexes.iter() {
+                    if let cell @ Walk(_) = walks.cell_mut(*idx) {
+                        *cell = Empty;
+                    }
+                }
+                self.phase = Phase::Between;
+                Some(GenerationEvent::PathCommitted {
+                    start: walks.coords(start),
+                    length: self.in_maze - joined,
+                })
+            }
+            Phase::Done => None,
+        }
+    }
+}
+
+impl<R: Rng> Iterator for Steps<R> {
+    type Item = GenerationEvent;
+
+    fn next(&mut self) -> Option<GenerationEvent> {
+        let event = self.step();
+        #[cfg(feature = "log")]
+        if let Some(event) = &event {
+            log::trace!("wilson's algorithm: {event:?}");
+        }
+        event
+    }
+}
+
+impl MazeGenerator for Generator {
+    /// Carve a maze by repeatedly adding loop-erased random walks to it
+    fn generate<R: Rng + ?Sized>(&self, width: usize, height: usize, rng: &mut R) -> Maze {
+        Walks::new(width, height).generate(rng, |_, _, _| {})
+    }
+}
+
+/// The state of a grid while Wilson's algorithm runs: which cells are in the maze and the
+/// direction the current walk took through each cell it has visited
 #[derive(Debug)]
-pub struct Generator {
+struct Walks {
     cells: Box<[Cell]>,
-    pub width: usize,
-    pub height: usize,
+    width: usize,
+    height: usize,
     unvisited_candidates: Vec<usize>,
 }
 
-impl Generator {
-    pub fn new(width: usize, height: usize) -> Self {
+impl Walks {
+    fn new(width: usize, height: usize) -> Self {
         let len = width * height;
         let cells = vec![Cell::Empty; len].into_boxed_slice();
         let unvisited_candidates: Vec<usize> = (0..len).collect();
@@ -43,99 +384,44 @@ impl Generator {
     }
 
     fn adjacent_index(&self, index: usize, direction: Direction) -> Option<usize> {
-        use Direction::*;
-        match direction {
-            West if index % self.width == 0 => None,
-            West => Some(index - 1),
-            East if index % self.width == self.width - 1 => None,
-            East => Some(index + 1),
-            North if index < self.width => None,
-            North => Some(index - self.width),
-            South if index >= self.width * (self.height - 1) => None,
-            South => Some(index + self.width),
-        }
+        RectGrid::new(self.width, self.height).adjacent_index(index, direction)
     }
 
-    pub fn generate<R: Rng>(mut self, rng: &mut R) -> Maze {
-        use Cell::*;
-        use Direction::*;
+    fn coords(&self, index: usize) -> (usize, usize) {
+        RectGrid::new(self.width, self.height).coords(index)
+    }
+
+    /// Run the algorithm, calling `observe` with each event and the number of cells in the maze
+    /// after it
+    fn generate<R: Rng + ?Sized>(
+        self,
+        rng: &mut R,
+        mut observe: impl FnMut(&Self, usize, GenerationEvent),
+    ) -> Maze {
         if self.is_empty() {
             return Maze::new(0, 0);
         }
-        self.unvisited_candidates.shuffle(rng);
-
-        // Choose an initial cell at random to be part of the maze
-        let initial_idx = self.choose_walk_start().unwrap();
-        *self.cell_mut(initial_idx) = InMaze(MazeCell::new(false, false));
-
-        let mut directions = [North, South, East, West];
-        let mut walk_indexes = Vec::with_capacity(self.len());
-        while let Some(start_idx) = self.choose_walk_start() {
-            walk_indexes.clear();
-            let mut curr_idx = start_idx;
-
-            // Perform the walk
-            loop {
-                walk_indexes.push(curr_idx);
-
-                let (direction, adjacent_idx) =
-                    self.choose_random_adjacent(curr_idx, &mut directions, rng);
-
-                *self.cell_mut(curr_idx) = Walk(direction);
-                if let InMaze(_) = self.cell(adjacent_idx) {
-                    break;
-                }
-                curr_idx = adjacent_idx;
-            }
-
-            // Add the walk to the maze. Because the path may intersect itself and directions in
-            // the cells that are traversed multiple times are overridden, follow the path based on
-            // the directions not the indexes traversed. That means that some cells traversed in
-            // the walk will not be included in the maze in this.
-            curr_idx = start_idx;
-            let mut last_direction: Option<Direction> = None;
-            loop {
-                let cell = self.cell_mut(curr_idx);
-                let direction = match cell {
-                    Walk(direction) => *direction,
-                    InMaze(mc) => {
-                        // Open up the existing maze cell so that the walk path enters it
-                        match last_direction {
-                            Some(East) => mc.set_west_open(),
-                            Some(South) => mc.set_north_open(),
-                            _ => {}
-                        }
-                        break;
-                    }
-                    _ => unreachable!(),
-                };
-                // Open up walls along the walk direction. Maze cells "own" the north and west
-                // direction, so track if those are the walls entered from or leaving through
-                *cell = InMaze(MazeCell::new(
-                    direction == West || last_direction == Some(East),
-                    direction == North || last_direction == Some(South),
-                ));
-                curr_idx = self.adjacent_index(curr_idx, direction).unwrap();
-                last_direction = Some(direction);
-            }
+        let mut steps = Steps::new(self, rng);
+        while let Some(event) = steps.next() {
+            observe(&steps.walks, steps.in_maze, event);
+        }
 
-            // Remove unused walk cells. This does not need to be done except when visualizing the
-            // maze generation. Keep it in here anyway because visualization is helpful (and cool)
-            // and it has minimal impact on performance.
-            for idx in walk_indexes.iter() {
-                if let cell @ Walk(_) = self.cell_mut(*idx) {
-                    *cell = Empty;
-                }
+        let mut maze = Maze::new(steps.walks.width as u32, steps.walks.height as u32);
+        for (idx, cell) in steps.walks.cells.iter().enumerate() {
+            match cell {
+                Cell::InMaze(maze_cell) => *maze.cell_at_index_mut(idx) = *maze_cell,
+                _ => unreachable!(),
             }
         }
+        maze
+    }
 
-        println!("{}", self);
-
+    /// The maze as carved so far, with every wall of the cells outside of it closed
+    fn snapshot(&self) -> Maze {
         let mut maze = Maze::new(self.width as u32, self.height as u32);
         for (idx, cell) in self.cells.iter().enumerate() {
-            match cell {
-                InMaze(maze_cell) => *maze.cell_mut(idx) = *maze_cell,
-                _ => unreachable!(),
+            if let Cell::InMaze(maze_cell) = cell {
+                *maze.cell_at_index_mut(idx) = *maze_cell;
             }
         }
         maze
@@ -155,7 +441,7 @@ impl Generator {
         None
     }
 
-    fn choose_random_adjacent<R: Rng>(
+    fn choose_random_adjacent<R: Rng + ?Sized>(
         &self,
         from_idx: usize,
         directions: &mut [Direction],
@@ -175,45 +461,69 @@ impl Generator {
     }
 }
 
-impl Display for Generator {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for row in 0..(self.height) {
-            for col in 0..self.width {
-                f.write_char('-')?;
-                let idx = row * self.width + col;
-                match self.cell(idx) {
-                    Cell::InMaze(mc) if mc.north_open() => f.write_char(' ')?,
-                    _ => f.write_char('-')?,
-                }
-            }
-            f.write_char('-')?;
-            f.write_char('\n')?;
-            for col in 0..self.width {
-                let idx = row * self.width + col;
-                match self.cell(idx) {
-                    Cell::InMaze(mc) if mc.west_open() => f.write_char(' ')?,
-                    _ => f.write_char('|')?,
-                }
-                match self.cell(idx) {
-                    Cell::Empty => f.write_char('X')?,
-                    Cell::InMaze { .. } => f.write_char(' ')?,
-                    Cell::Walk(Direction::North) => f.write_char('^')?,
-                    Cell::Walk(Direction::East) => f.write_char('>')?,
-                    Cell::Walk(Direction::South) => f.write_char('v')?,
-                    Cell::Walk(Direction::West) => f.write_char('<')?,
-                }
-            }
+/// Carve a maze into `grid` with Wilson's algorithm, opening walls between its cells. The walls
+/// should all be closed to begin with. A grid made of several separate pieces gets a separate
+/// maze in each piece.
+///
+/// A plain [`Graph`], like an [`AdjacencyList`](crate::graph::AdjacencyList), has no walls to
+/// open, so wrap it in a [`Carving`](crate::tessellation::Carving). The passages carved into it
+/// are the edges of a uniformly random spanning tree.
+///
+/// ```
+/// use dadalus::graph::AdjacencyList;
+/// use dadalus::tessellation::Carving;
+/// use dadalus::wilsons;
+///
+/// let rooms = AdjacencyList::from_edges(4, [(0, 1), (1, 2), (2, 3), (3, 0)]);
+/// let mut carving = Carving::new(rooms);
+/// wilsons::carve(&mut carving, &mut rand::thread_rng());
+/// assert_eq!(carving.edges().count(), 3);
+/// ```
+pub fn carve<G: Grid, R: Rng + ?Sized>(grid: &mut G, rng: &mut R) {
+    let len = grid.len();
+    let mut candidates: Vec<usize> = (0..len).collect();
+    candidates.shuffle(rng);
+
+    // Walks only finish once they reach the maze, so start with a random cell of each piece
+    let mut in_maze = vec![false; len];
+    let mut reached = vec![false; len];
+    for &idx in candidates.iter() {
+        if !reached[idx] {
+            in_maze[idx] = true;
+            flood(grid, idx, &mut reached);
+        }
+    }
+    // The cell each walk cell was last left towards. Following these from the start of the walk
+    // retraces it with the loops erased.
+    let mut next = vec![0usize; len];
+    while let Some(start_idx) = candidates.pop() {
+        let mut curr_idx = start_idx;
+        while !in_maze[curr_idx] {
+            let neighbors = grid.neighbors(curr_idx);
+            next[curr_idx] = *neighbors.choose(rng).expect("cell has no neighbors");
+            curr_idx = next[curr_idx];
+        }
 
-            f.write_char('|')?;
-            f.write_char('\n')?;
+        curr_idx = start_idx;
+        while !in_maze[curr_idx] {
+            in_maze[curr_idx] = true;
+            grid.link(curr_idx, next[curr_idx]);
+            curr_idx = next[curr_idx];
         }
-        for _ in 0..self.width {
-            f.write_char('-')?;
-            f.write_char('-')?;
+    }
+}
+
+/// Mark every cell that can be reached from `start`
+fn flood<G: Graph>(grid: &G, start: usize, reached: &mut [bool]) {
+    reached[start] = true;
+    let mut stack = vec![start];
+    while let Some(idx) = stack.pop() {
+        for neighbor in grid.neighbors(idx) {
+            if !reached[neighbor] {
+                reached[neighbor] = true;
+                stack.push(neighbor);
+            }
         }
-        f.write_char('-')?;
-        f.write_char('\n')?;
-        Ok(())
     }
 }
 
@@ -225,10 +535,49 @@ enum Cell {
     Walk(Direction),
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::AdjacencyList;
+    use crate::tessellation::Carving;
+
+    #[test]
+    fn carving_an_adjacency_list_gives_a_spanning_tree() {
+        // A 5x5 grid of rooms with the diagonals of every other room as extra doors
+        let mut edges = Vec::new();
+        for idx in 0..25 {
+            let (x, y) = (idx % 5, idx / 5);
+            if x < 4 {
+                edges.push((idx, idx + 1));
+            }
+            if y < 4 {
+                edges.push((idx, idx + 5));
+            }
+            if x < 4 && y < 4 && idx % 2 == 0 {
+                edges.push((idx, idx + 6));
+            }
+        }
+        for seed in 0..20 {
+            let mut carving = Carving::new(AdjacencyList::from_edges(25, edges.iter().copied()));
+            carve(
+                &mut carving,
+                &mut rand_chacha::ChaCha8Rng::seed_from_u64(seed),
+            );
+            assert_eq!(carving.edges().count(), 24);
+            assert!(carving.edges().all(|(a, b)| edges.contains(&(a, b))));
+
+            let mut reached = [false; 25];
+            reached[0] = true;
+            let mut stack = vec![0];
+            while let Some(idx) = stack.pop() {
+                for &next in carving.passages(idx) {
+                    if !reached[next] {
+                        reached[next] = true;
+                        stack.push(next);
+                    }
+                }
+            }
+            assert!(reached.iter().all(|reached| *reached));
+        }
+    }
 }